@@ -0,0 +1,36 @@
+//! 標準入出力でmjaiのメッセージをやりとりし、ツモ切りだけをする打ち手
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+use riichi_mahjong_rs::mjai::message::Message;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut seat = 0;
+    for line in stdin.lock().lines() {
+        let reply = match Message::parse(&line?)? {
+            Message::Hello => Message::Join {
+                name: "tsumogiri".to_string(),
+                room: "default".to_string(),
+            },
+            Message::StartGame { id, .. } => {
+                seat = id;
+                Message::None
+            }
+            Message::Tsumo {
+                actor,
+                pai: Some(pai),
+            } if actor == seat => Message::Dahai {
+                actor,
+                pai,
+                tsumogiri: true,
+            },
+            Message::EndGame => break,
+            _ => Message::None,
+        };
+        writeln!(stdout, "{}", reply)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
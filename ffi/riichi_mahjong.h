@@ -0,0 +1,188 @@
+/* riichi_mahjong_rs の C インターフェース（このファイルは cbindgen で生成する） */
+
+#ifndef RIICHI_MAHJONG_H
+#define RIICHI_MAHJONG_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// `RmScore`に入る役の数の上限
+#define RM_MAX_YAKU 16
+
+// 関数の結果（`RmOk`以外の時は、`RmErrorBufferTooSmall`で必要な長さを返す以外は出力を書き換えない）
+typedef enum RmErrorCode {
+  // 成功
+  RM_OK = 0,
+  // 必要なポインタが`NULL`
+  RM_ERROR_NULL_POINTER = 1,
+  // 文字列がUTF-8でない
+  RM_ERROR_INVALID_UTF8 = 2,
+  // 手牌の文字列を読み込めない
+  RM_ERROR_PARSE = 3,
+  // 引数の値が範囲外（牌・風・言語・役の番号など）
+  RM_ERROR_INVALID_ARGUMENT = 4,
+  // 手牌を解析できない
+  RM_ERROR_INVALID_HAND = 5,
+  // 書き込む領域が足りない
+  RM_ERROR_BUFFER_TOO_SMALL = 6,
+  // ライブラリの内部でパニックした
+  RM_ERROR_PANIC = 7,
+} RmErrorCode;
+
+// 手牌（中身は見せない）
+//
+// `rm_hand_parse`で作り、呼び出し側が`rm_hand_free`で解放する。
+// それ以外の関数は、結果を呼び出し側の用意した領域に書き込むので解放するものはない。
+typedef struct RmHand RmHand;
+
+// 手牌の（牌以外の）状態（`Status`に対応する）
+//
+// すべて`0`にすると、東場の東家・門前・ロン和了になる。
+typedef struct RmHandStatus {
+  // 立直したか
+  bool has_claimed_ready;
+  // 鳴いたか
+  bool has_claimed_open;
+  // 自摸しているか
+  bool is_self_picked;
+  // 一発か
+  bool is_one_shot;
+  // ダブル立直したか
+  bool has_claimed_double_ready;
+  // 最後の牌（海底牌・河底牌）で和了ったか
+  bool is_last_tile;
+  // 嶺上牌で和了ったか
+  bool is_dead_wall_draw;
+  // 搶槓で和了ったか
+  bool is_robbing_a_quad;
+  // 誰も鳴いていない第一巡の自摸か
+  bool is_first_turn;
+  // 槓子の数
+  uint32_t kan_count;
+  // 自風（`0`から順に東・南・西・北）
+  uint8_t player_wind;
+  // 場風（`0`から順に東・南・西・北）
+  uint8_t prevailing_wind;
+} RmHandStatus;
+
+// 成立した役
+typedef struct RmYaku {
+  // 役の番号（`Kind`の定義順、`0`が立直）
+  uint32_t kind;
+  // 翻数（役満は`13`）
+  uint32_t han;
+} RmYaku;
+
+// 点数計算の結果
+typedef struct RmScore {
+  // 和了の形で役があるか（`false`なら他の値はすべて`0`）
+  bool won;
+  // 翻数（ドラを含む）
+  uint32_t han;
+  // 符
+  uint32_t fu;
+  // ドラの枚数
+  uint32_t dora;
+  // 役満の倍数（役満でなければ`0`）
+  uint32_t yakuman;
+  // 基本点
+  int32_t base_points;
+  // ロン和了の放銃者の支払い
+  int32_t ron;
+  // 自摸和了の子の支払い
+  int32_t tsumo_child;
+  // 自摸和了の親の支払い（和了者が親なら`0`）
+  int32_t tsumo_dealer;
+  // `yaku`に入っている役の数
+  size_t yaku_len;
+  // 成立した役
+  RmYaku yaku[RM_MAX_YAKU];
+} RmScore;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// エラーの説明（NUL終端の静的な文字列で、解放しない）
+//
+// `code`は`RmErrorCode`の値。定義にない値には`"unknown error"`を返す
+// （Cの列挙型は範囲外の値も持てるので、`RmErrorCode`としては受け取らない）。
+const char *rm_error_message(int32_t code);
+
+// 手牌の文字列（`Hand::parse`の形式）を読み込む
+//
+// 成功すると`*out`に手牌を入れる。不要になったら`rm_hand_free`で解放すること。
+//
+// # Safety
+//
+// `text`はNUL終端の文字列、`out`は書き込めるポインタであること。
+RmErrorCode rm_hand_parse(const char *text, RmHand **out);
+
+// `rm_hand_parse`で作った手牌を解放する（`NULL`なら何もしない）
+//
+// # Safety
+//
+// `hand`は`rm_hand_parse`で作り、まだ解放していないものであること。
+void rm_hand_free(RmHand *hand);
+
+// 手牌を`1m2m3m...`の形式の文字列にする
+//
+// # Safety
+//
+// `hand`は有効な手牌、`buffer`は`capacity`バイト書き込める領域（`capacity`が`0`なら`NULL`でもよい）、
+// `len`は書き込めるポインタであること。
+RmErrorCode rm_hand_to_string(const RmHand *hand, char *buffer, size_t capacity, size_t *len);
+
+// 向聴数（和了の形なら`-1`）を求める
+//
+// # Safety
+//
+// `hand`は有効な手牌、`out`は書き込めるポインタであること。
+RmErrorCode rm_hand_shanten(const RmHand *hand, int32_t *out);
+
+// ツモった牌を除いた手牌の和了牌（牌の番号`0`〜`33`）を求める
+//
+// 和了牌は34種類を超えないので、`capacity`が`34`あれば足りる。
+// 聴牌していなければ`*len`は`0`になる。
+//
+// # Safety
+//
+// `hand`は有効な手牌、`tiles`は`capacity`個書き込める領域、`len`は書き込めるポインタであること。
+RmErrorCode rm_hand_waits(const RmHand *hand, uint8_t *tiles, size_t capacity, size_t *len);
+
+// ツモった牌で和了った時の役と点数を求める
+//
+// `dora`はドラ（表示牌ではない）の牌の番号`dora_len`個（`dora_len`が`0`なら`NULL`でもよい）。
+// 和了の形でないか役がなければ、`out->won`が`false`になる。支払いは四人麻雀で、自風が東なら親とする。
+//
+// # Safety
+//
+// `hand`は有効な手牌、`status`は読めるポインタ、`dora`は`dora_len`個読める領域、
+// `out`は書き込めるポインタであること。
+RmErrorCode rm_hand_score(const RmHand *hand,
+                          const RmHandStatus *status,
+                          const uint8_t *dora,
+                          size_t dora_len,
+                          RmScore *out);
+
+// 役の名前を書き込む
+//
+// `lang`は`0`が日本語、`1`が英語。`has_opened`なら喰い下がり役に「（鳴）」が付く。
+//
+// # Safety
+//
+// `buffer`は`capacity`バイト書き込める領域（`capacity`が`0`なら`NULL`でもよい）、
+// `len`は書き込めるポインタであること。
+RmErrorCode rm_yaku_name(uint32_t kind,
+                         bool has_opened,
+                         uint8_t lang,
+                         char *buffer,
+                         size_t capacity,
+                         size_t *len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RIICHI_MAHJONG_H */
@@ -0,0 +1,11 @@
+#!/bin/sh
+# C インターフェースを静的ライブラリとしてビルドし、ffi/test.c をリンクして実行する
+set -eu
+
+cd "$(dirname "$0")/.."
+cargo rustc --release --lib --features ffi --crate-type cdylib,staticlib
+target_dir="${CARGO_TARGET_DIR:-target}/release"
+out="$target_dir/riichi_mahjong_ffi_test"
+${CC:-cc} -std=c99 -Wall -Wextra -Iffi ffi/test.c "$target_dir/libriichi_mahjong_rs.a" \
+  -lpthread -ldl -lm -o "$out"
+"$out"
@@ -0,0 +1,101 @@
+/* C インターフェースのテスト（ffi/run_test.sh でビルドして実行する） */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "riichi_mahjong.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                  \
+  do {                                                               \
+    if (!(cond)) {                                                   \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                \
+      failures++;                                                    \
+    }                                                                \
+  } while (0)
+
+/* 読み込み・向聴数・和了牌 */
+static void test_waits(void) {
+  RmHand *hand = NULL;
+  CHECK(rm_hand_parse("234m456p678s2388p", &hand) == RM_OK);
+
+  int32_t shanten = 99;
+  CHECK(rm_hand_shanten(hand, &shanten) == RM_OK);
+  CHECK(shanten == 0);
+
+  uint8_t tiles[34];
+  size_t len = 0;
+  CHECK(rm_hand_waits(hand, tiles, 2, &len) == RM_ERROR_BUFFER_TOO_SMALL);
+  CHECK(len == 3);
+  CHECK(rm_hand_waits(hand, tiles, sizeof(tiles), &len) == RM_OK);
+  CHECK(len == 3);
+  /* 1p・4p・7p */
+  CHECK(tiles[0] == 9 && tiles[1] == 12 && tiles[2] == 15);
+
+  char text[64];
+  CHECK(rm_hand_to_string(hand, text, sizeof(text), &len) == RM_OK);
+  CHECK(strcmp(text, "2m3m4m4p5p6p6s7s8s2p3p8p8p") == 0);
+  CHECK(len == strlen(text));
+
+  rm_hand_free(hand);
+}
+
+/* 役と点数 */
+static void test_score(void) {
+  RmHand *hand = NULL;
+  CHECK(rm_hand_parse("234m456p678s2388p 4p", &hand) == RM_OK);
+
+  RmHandStatus status;
+  memset(&status, 0, sizeof(status));
+  status.is_self_picked = true;
+  status.player_wind = 1;
+  uint8_t dora[] = {1}; /* 2m */
+  RmScore score;
+  CHECK(rm_hand_score(hand, &status, dora, 1, &score) == RM_OK);
+  CHECK(score.won);
+  CHECK(score.han == 4 && score.fu == 20 && score.dora == 1);
+  CHECK(score.tsumo_child == 1300 && score.tsumo_dealer == 2600);
+  CHECK(score.yaku_len == 3);
+
+  char name[64];
+  size_t len = 0;
+  for (size_t i = 0; i < score.yaku_len; i++) {
+    CHECK(rm_yaku_name(score.yaku[i].kind, false, 1, name, sizeof(name), &len) ==
+          RM_OK);
+    printf("%s %u\n", name, score.yaku[i].han);
+  }
+  CHECK(rm_yaku_name(score.yaku[0].kind, false, 0, name, sizeof(name), &len) == RM_OK);
+  CHECK(strcmp(name, "門前清自摸和") == 0);
+
+  status.player_wind = 4;
+  CHECK(rm_hand_score(hand, &status, NULL, 0, &score) == RM_ERROR_INVALID_ARGUMENT);
+
+  rm_hand_free(hand);
+}
+
+/* エラー */
+static void test_errors(void) {
+  RmHand *hand = NULL;
+  CHECK(rm_hand_parse("1m2m", &hand) == RM_ERROR_PARSE);
+  CHECK(hand == NULL);
+  CHECK(rm_hand_parse(NULL, &hand) == RM_ERROR_NULL_POINTER);
+  CHECK(rm_hand_parse("\xff", &hand) == RM_ERROR_INVALID_UTF8);
+  CHECK(strcmp(rm_error_message(RM_ERROR_PARSE), "cannot parse hand") == 0);
+  CHECK(strcmp(rm_error_message(99), "unknown error") == 0);
+  rm_hand_free(NULL);
+}
+
+int main(void) {
+  test_waits();
+  test_score();
+  test_errors();
+  if (failures > 0) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return EXIT_FAILURE;
+  }
+  printf("ok\n");
+  return EXIT_SUCCESS;
+}
@@ -0,0 +1,14 @@
+//! 出来事の記録の読み込みはパニックせず、読めた記録は書き出して読み直すと同じになる
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::board::event::{self, Event};
+
+fuzz_target!(|data: &str| {
+    for line in data.lines() {
+        if let Ok(e) = Event::parse(line) {
+            assert_eq!(Event::parse(&e.to_string()).as_ref(), Ok(&e));
+        }
+    }
+    let _ = event::from_text(data);
+});
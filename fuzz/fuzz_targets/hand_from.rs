@@ -0,0 +1,15 @@
+//! `Hand::parse_lossy`は読めない文字を無視するので、どんな文字列でもパニックしない
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::hand::Hand;
+
+fuzz_target!(|data: &str| {
+    let hand = Hand::parse_lossy(data);
+    // 副露は3枚として数える
+    let len = hand.tiles().len() + hand.opened().len() * 3 + hand.drawn().iter().count();
+    assert_eq!(hand.summarize_tiles().iter().sum::<u32>() as usize, len);
+    let _ = hand.to_string();
+    let _ = hand.to_short_string();
+    let _ = hand.to_emoji();
+});
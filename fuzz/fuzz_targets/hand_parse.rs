@@ -0,0 +1,17 @@
+//! `Hand::parse`で読めた手牌は、`to_short_string`で書き出して読み直すと（手牌を並べ替えた）同じ手牌になる
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::hand::Hand;
+
+fuzz_target!(|data: &str| {
+    let mut hand = match Hand::parse(data) {
+        Ok(hand) => hand,
+        Err(_) => return,
+    };
+    let short = hand.to_short_string();
+    let parsed = Hand::parse(&short).expect(&short);
+    hand.sort();
+    assert_eq!(parsed, hand);
+    assert_eq!(Hand::parse_lossy(&short), hand);
+});
@@ -0,0 +1,14 @@
+//! mjaiのメッセージの読み込みと副露への変換はパニックせず、読めたメッセージは書き出して読み直すと同じになる
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::mjai::message::Message;
+
+fuzz_target!(|data: &str| {
+    if let Ok(message) = Message::parse(data) {
+        assert_eq!(Message::parse(&message.to_string()).as_ref(), Ok(&message));
+        for players in [3, 4] {
+            let _ = message.to_open_tiles(players);
+        }
+    }
+});
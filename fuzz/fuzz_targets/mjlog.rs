@@ -0,0 +1,15 @@
+//! 天鳳のmjlog形式の牌譜の読み込みと、読めた牌譜の判断の時点の再現は、どんな文字列でもパニックしない
+//!
+//! `cargo fuzz run mjlog corpus/mjlog ../testdata`のように、サンプルの牌譜を種にして実行する。
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::log::mjlog::MjLog;
+
+fuzz_target!(|data: &str| {
+    if let Ok(log) = MjLog::parse(data) {
+        for round in &log.rounds {
+            let _ = round.decision_points();
+        }
+    }
+});
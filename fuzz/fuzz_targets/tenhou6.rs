@@ -0,0 +1,18 @@
+//! tenhou.net/6のJSON形式の牌譜の読み込みと、読めた牌譜のmjlog形式への変換・書き出しは、どんな文字列でもパニックしない
+//!
+//! `cargo fuzz run tenhou6 corpus/tenhou6 ../testdata`のように、サンプルの牌譜を種にして実行する。
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::log::tenhou6::Tenhou6;
+
+fuzz_target!(|data: &str| {
+    if let Ok(log) = Tenhou6::parse(data) {
+        let _ = log.to_json();
+        if let Ok(mjlog) = log.to_mjlog() {
+            for round in &mjlog.rounds {
+                let _ = round.decision_points();
+            }
+        }
+    }
+});
@@ -0,0 +1,17 @@
+//! 牌の名前の読み込みは`Tile::from_name`と`FromStr`で一致し、読めた牌は名前に戻すと同じ牌になる
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use riichi_mahjong_rs::tile::Tile;
+
+fuzz_target!(|data: &str| {
+    let parsed = data.parse::<Tile>();
+    match Tile::from_name(data) {
+        Some(tile) => {
+            assert_eq!(parsed, Ok(tile));
+            assert_eq!(Tile::from_name(&tile.to_string()), Some(tile));
+        }
+        // `FromStr`だけが赤ドラの五（`0m`など）を読める
+        None => assert!(parsed.is_err() || parsed.unwrap().is_red_dora()),
+    }
+});
@@ -0,0 +1,289 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::get_winning_tiles;
+use crate::hand_info::shanten::shanten;
+use crate::hand_info::status::Status;
+use crate::settings::Settings;
+use crate::tile::*;
+use crate::winning_hand::score::{self, Score};
+
+/// 1つのスレッドがまとめて受け持つ手牌の数
+const CHUNK_SIZE: usize = 64;
+
+/// 一括評価できる手牌（文字列・牌の種類ごとの枚数・`Hand`）
+pub trait BatchHand: Sync {
+    /// 評価する手牌にする
+    fn to_hand(&self) -> Result<Hand>;
+}
+
+/// `Hand::parse`の形式の文字列
+impl BatchHand for str {
+    fn to_hand(&self) -> Result<Hand> {
+        return Ok(Hand::parse(self)?);
+    }
+}
+
+impl BatchHand for &str {
+    fn to_hand(&self) -> Result<Hand> {
+        return (*self).to_hand();
+    }
+}
+
+impl BatchHand for String {
+    fn to_hand(&self) -> Result<Hand> {
+        return self.as_str().to_hand();
+    }
+}
+
+/// 副露のない手牌として扱う（ツモった牌は区別しない）
+impl BatchHand for TileSummarize {
+    fn to_hand(&self) -> Result<Hand> {
+        if let Some(t) = self.iter().position(|&count| count > 4) {
+            return Err(Error::TooManyCopies(t as TileType));
+        }
+        let len: u32 = self.iter().sum();
+        if len != 13 && len != 14 {
+            return Err(Error::WrongNumberOfTiles(len));
+        }
+        return Ok(Hand::from_summarized(self));
+    }
+}
+
+impl BatchHand for Hand {
+    fn to_hand(&self) -> Result<Hand> {
+        return Ok(self.clone());
+    }
+}
+
+/// 一括評価の条件
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// 点数計算に使う手牌の状態（立直・自摸・自風など）
+    pub status: Status,
+    /// 点数計算に使う設定
+    pub settings: Settings,
+    /// ドラ（表示牌ではない）の種類
+    pub dora: Vec<TileType>,
+    /// スレッド数（`0`なら使えるCPUの数）
+    pub threads: usize,
+}
+
+/// 1つの手牌の評価
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Evaluation {
+    /// 評価した手牌
+    pub hand: Hand,
+    /// 向聴数（和了の形なら`-1`）
+    pub shanten: i32,
+    /// ツモった牌を除いた13枚の和了牌と、その牌で和了った時の点数（役がなければ`None`）
+    pub waits: Vec<(TileType, Option<Score>)>,
+    /// 14枚で和了の形なら点数（役がなければ`None`）
+    ///
+    /// ツモった牌が分からない手牌（牌の種類ごとの枚数など）は、最も高くなる牌で和了ったとする。
+    pub score: Option<Score>,
+}
+
+/// 手牌をまとめて評価する（向聴数・和了牌・役・点数）
+///
+/// `options.threads`個のスレッドで分けて評価するが、結果は入力と同じ順に並び、
+/// スレッド数によらず同じになる。読めない手牌はその位置に`Err`を返す。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::batch::{evaluate, BatchOptions};
+///
+/// let hands = ["123m456p789s1155z 5z", "123m456p789s1123z", "1m"];
+/// let results = evaluate(&hands, &BatchOptions::default());
+/// assert_eq!(results[0].as_ref().unwrap().shanten, -1);
+/// assert!(results[0].as_ref().unwrap().score.is_some());
+/// assert_eq!(results[1].as_ref().unwrap().shanten, 1);
+/// assert!(results[2].is_err());
+/// ```
+pub fn evaluate<H: BatchHand>(hands: &[H], options: &BatchOptions) -> Vec<Result<Evaluation>> {
+    return parallel_map(hands, options.threads, |hand| evaluate_one(hand, options));
+}
+
+/// 1つの手牌を評価する
+pub fn evaluate_one<H: BatchHand + ?Sized>(hand: &H, options: &BatchOptions) -> Result<Evaluation> {
+    let hand = hand.to_hand()?;
+    let shanten = shanten(&hand);
+
+    let mut waits = Vec::new();
+    let mut closed = hand.clone();
+    closed.set_drawn(None);
+    if closed.tiles().len() % 3 == 1 {
+        for tile in get_winning_tiles(&closed)? {
+            let mut won = closed.clone();
+            won.set_drawn(Some(Tile::new(tile)));
+            waits.push((tile, calculate(&won, options)?));
+        }
+    }
+
+    let score = if hand.drawn().is_some() {
+        calculate(&hand, options)?
+    } else if shanten == -1 {
+        // どの牌で和了ったか分からないので、最も高くなる牌を選ぶ
+        let mut best: Option<Score> = None;
+        let mut tried = [false; Tile::LEN];
+        for (i, tile) in hand.tiles().iter().enumerate() {
+            if tried[tile.get() as usize] {
+                continue;
+            }
+            tried[tile.get() as usize] = true;
+            let mut tiles = hand.tiles().to_vec();
+            let drawn = tiles.remove(i);
+            let won = Hand::new_with_opened(tiles, hand.opened().to_vec(), Some(drawn));
+            if let Some(score) = calculate(&won, options)? {
+                if best
+                    .as_ref()
+                    .is_none_or(|b| score.base_points > b.base_points)
+                {
+                    best = Some(score);
+                }
+            }
+        }
+        best
+    } else {
+        None
+    };
+
+    return Ok(Evaluation {
+        hand,
+        shanten,
+        waits,
+        score,
+    });
+}
+
+/// 和了った手牌の点数を求める
+fn calculate(hand: &Hand, options: &BatchOptions) -> Result<Option<Score>> {
+    return score::calculate(hand, &options.status, &options.settings, &options.dora, 0);
+}
+
+/// `items`の各要素に`f`を適用した結果を、入力と同じ順に返す
+///
+/// `threads`個（`0`なら使えるCPUの数）のスレッドが`CHUNK_SIZE`個ずつ取り合って処理する。
+fn parallel_map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+    let chunks: Vec<&[T]> = items.chunks(CHUNK_SIZE).collect();
+    let threads = threads.min(chunks.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let done: Mutex<Vec<(usize, Vec<R>)>> = Mutex::new(Vec::with_capacity(chunks.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(chunk) = chunks.get(index) else {
+                    break;
+                };
+                let result: Vec<R> = chunk.iter().map(&f).collect();
+                done.lock().unwrap().push((index, result));
+            });
+        }
+    });
+
+    let mut done = done.into_inner().unwrap();
+    done.sort_unstable_by_key(|(index, _)| *index);
+    return done.into_iter().flat_map(|(_, result)| result).collect();
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::hand_analyzer::HandAnalyzer;
+    use crate::test_util::RandomHands;
+    use crate::tile::Wind;
+
+    #[test]
+    /// 文字列・牌の種類ごとの枚数の手牌を評価する
+    fn evaluate_hands() {
+        let options = BatchOptions {
+            status: Status {
+                is_self_picked: true,
+                player_wind: Wind::South,
+                ..Status::new()
+            },
+            ..BatchOptions::default()
+        };
+        let results = evaluate(
+            &["234m456p678s2388p", "234m456p678s2388p 1p", "1z1z"],
+            &options,
+        );
+        let ready = results[0].as_ref().unwrap();
+        assert_eq!(ready.shanten, 0);
+        let waits: Vec<TileType> = ready.waits.iter().map(|(t, _)| *t).collect();
+        assert_eq!(waits, vec![Tile::P1, Tile::P4, Tile::P7]);
+        // 門前清自摸和・平和・断么九（1pは断么九にならない）
+        assert_eq!(ready.waits[0].1.as_ref().unwrap().han, 2);
+        assert_eq!(ready.waits[1].1.as_ref().unwrap().han, 3);
+        assert_eq!(ready.score, None);
+
+        let won = results[1].as_ref().unwrap();
+        assert_eq!(won.shanten, -1);
+        assert_eq!(won.score, ready.waits[0].1);
+        assert!(results[2].is_err());
+
+        // 最も高くなる和了牌を選ぶ
+        let summary = Hand::parse_lossy("234m456p678s2388p 4p").summarize_tiles();
+        let results = evaluate(&[summary], &options);
+        assert_eq!(results[0].as_ref().unwrap().score.as_ref().unwrap().han, 3);
+        let mut too_many = summary;
+        too_many[Tile::M2 as usize] = 5;
+        assert_eq!(
+            evaluate_one(&too_many, &options).unwrap_err(),
+            Error::TooManyCopies(Tile::M2)
+        );
+        too_many[Tile::M2 as usize] = 3;
+        assert_eq!(
+            evaluate_one(&too_many, &options).unwrap_err(),
+            Error::WrongNumberOfTiles(16)
+        );
+    }
+
+    #[test]
+    /// スレッド数によらず入力と同じ順・同じ結果になる
+    fn deterministic() {
+        let mut random = RandomHands::new(44);
+        let hands: Vec<Hand> = (0..300).map(|_| random.hand(true)).collect();
+        let single = evaluate(
+            &hands,
+            &BatchOptions {
+                threads: 1,
+                ..BatchOptions::default()
+            },
+        );
+        let multi = evaluate(
+            &hands,
+            &BatchOptions {
+                threads: 4,
+                ..BatchOptions::default()
+            },
+        );
+        assert_eq!(single.len(), hands.len());
+        for ((hand, a), b) in hands.iter().zip(&single).zip(&multi) {
+            let a = a.as_ref().unwrap();
+            assert_eq!(a, b.as_ref().unwrap());
+            assert_eq!(&a.hand, hand);
+            assert_eq!(a.shanten, HandAnalyzer::new(hand).unwrap().shanten);
+        }
+    }
+}
@@ -0,0 +1,26 @@
+/// 局の状態
+#[cfg(feature = "std")]
+pub mod round;
+/// 流局（荒牌平局）の処理
+#[cfg(feature = "std")]
+pub mod exhaustive_draw;
+/// 対局（複数の局の進行）
+#[cfg(feature = "std")]
+pub mod game;
+/// 山
+#[cfg(feature = "std")]
+pub mod wall;
+/// 点数の支払い
+pub mod payment;
+/// 打ち手のインターフェース
+#[cfg(feature = "std")]
+pub mod agent;
+/// 簡単な打ち手（ランダム・向聴数優先）
+#[cfg(feature = "std")]
+pub mod bot;
+/// 局・対局の進行
+#[cfg(feature = "std")]
+pub mod engine;
+/// 局の中の出来事（牌譜）と再現
+#[cfg(feature = "std")]
+pub mod event;
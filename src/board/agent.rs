@@ -0,0 +1,297 @@
+use crate::board::event::Event;
+use crate::board::game::Placement;
+use crate::board::round::{Discard, Round};
+use crate::board::wall::Wall;
+use crate::hand::Hand;
+use crate::hand_info::opened::OpenTiles;
+use crate::hand_info::status::Status;
+use crate::tile::{Tile, TileType, Wind};
+
+/// 自分の手番で選べる行動
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TurnAction {
+    /// 自摸和了
+    Tsumo,
+    /// 暗槓
+    ClosedKan(TileType),
+    /// 加槓
+    AddedKan(TileType),
+    /// 北抜き（三人麻雀のみ）
+    North,
+    /// 牌を捨てる（`ready`が`true`なら立直を宣言する）
+    Discard { tile: TileType, ready: bool },
+}
+
+/// 他家の捨て牌に対して選べる行動
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CallAction {
+    /// 何もしない
+    Pass,
+    /// ロン和了
+    Ron,
+    /// ポン
+    Pon,
+    /// 大明槓
+    Kan,
+    /// チー（手牌から出す2枚の種類）
+    Chi([TileType; 2]),
+}
+
+/// プレイヤーから見える局の情報
+///
+/// 公開されている情報と、そのプレイヤー自身の手牌のみを参照できる。
+pub struct PlayerView<'a> {
+    round: &'a Round,
+    wall: &'a Wall,
+    seat: usize,
+}
+
+impl<'a> PlayerView<'a> {
+    pub fn new(round: &'a Round, wall: &'a Wall, seat: usize) -> PlayerView<'a> {
+        PlayerView { round, wall, seat }
+    }
+
+    /// 自分の席
+    pub fn seat(&self) -> usize {
+        self.seat
+    }
+
+    /// 人数
+    pub fn players(&self) -> usize {
+        self.round.players.len()
+    }
+
+    /// 自分の手牌
+    pub fn hand(&self) -> &Hand {
+        &self.round.players[self.seat].hand
+    }
+
+    /// 自分の手牌の（牌以外の）状態
+    pub fn status(&self) -> &Status {
+        &self.round.players[self.seat].status
+    }
+
+    /// 場風
+    pub fn prevailing_wind(&self) -> Wind {
+        self.round.prevailing_wind
+    }
+
+    /// 親の席
+    pub fn dealer(&self) -> usize {
+        self.round.dealer
+    }
+
+    /// 本場
+    pub fn honba(&self) -> u32 {
+        self.round.honba
+    }
+
+    /// 供託されている立直棒の本数
+    pub fn deposits(&self) -> u32 {
+        self.round.deposits
+    }
+
+    /// めくられているドラ表示牌
+    pub fn dora_indicators(&self) -> &[Tile] {
+        self.wall.dora_indicators()
+    }
+
+    /// 自摸山の残り枚数
+    pub fn remaining(&self) -> usize {
+        self.wall.remaining()
+    }
+
+    /// 指定した席の持ち点
+    pub fn score(&self, seat: usize) -> i32 {
+        self.round.players[seat].score
+    }
+
+    /// 指定した席の河
+    pub fn river(&self, seat: usize) -> &[Discard] {
+        &self.round.players[seat].river
+    }
+
+    /// 指定した席の副露
+    pub fn opened(&self, seat: usize) -> &[OpenTiles] {
+        self.round.players[seat].hand.opened()
+    }
+
+    /// 指定した席が立直しているか
+    pub fn has_claimed_ready(&self, seat: usize) -> bool {
+        self.round.players[seat].status.has_claimed_ready
+    }
+
+    /// 指定した席の抜きドラの枚数
+    pub fn north_bonus(&self, seat: usize) -> u32 {
+        self.round.players[seat].north_bonus
+    }
+}
+
+/// 打ち手から見た局の中の出来事
+///
+/// 公開されない牌（他家の自摸・嶺上牌の自摸・北抜きで補充した牌）は`None`になる。
+/// ツモ切りか否かと和了牌は公開される情報なので、局の進行の側で求めて渡す。
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ObservedEvent<'a> {
+    /// 自摸
+    Draw { seat: usize, tile: Option<Tile> },
+    /// 嶺上牌の自摸
+    DeadWallDraw { seat: usize, tile: Option<Tile> },
+    /// 北抜き（`tile`は補充した嶺上牌）
+    North { seat: usize, tile: Option<Tile> },
+    /// 打牌（`ready`なら立直宣言牌、`tsumogiri`なら直前に自摸した牌をそのまま捨てた）
+    Discard {
+        seat: usize,
+        tile: Tile,
+        ready: bool,
+        tsumogiri: bool,
+    },
+    /// 和了（`from`は放銃者、自摸なら`None`。`tile`は和了牌）
+    Win {
+        seat: usize,
+        from: Option<usize>,
+        tile: Option<Tile>,
+        han: u32,
+        fu: u32,
+        yakuman: u32,
+    },
+    /// 隠す牌のない出来事（立直・鳴き・槓・ドラ・流局・局の終了）
+    Public(&'a Event),
+}
+
+impl ObservedEvent<'_> {
+    /// 席`observer`から見えるように、他家の公開されない牌を隠す
+    pub fn seen_by(self, observer: usize) -> Self {
+        let hide = |seat: usize, tile: Option<Tile>| if seat == observer { tile } else { None };
+        match self {
+            ObservedEvent::Draw { seat, tile } => ObservedEvent::Draw {
+                seat,
+                tile: hide(seat, tile),
+            },
+            ObservedEvent::DeadWallDraw { seat, tile } => ObservedEvent::DeadWallDraw {
+                seat,
+                tile: hide(seat, tile),
+            },
+            ObservedEvent::North { seat, tile } => ObservedEvent::North {
+                seat,
+                tile: hide(seat, tile),
+            },
+            other => other,
+        }
+    }
+}
+
+/// 局の中の出来事を起きた順に受け取り、打ち手に見せる出来事にする
+#[derive(Debug, Clone)]
+pub(crate) struct EventObserver {
+    /// 各席が最後に自摸した牌（ツモ切りか否かと自摸和了の和了牌を求める）
+    last_draw: Vec<Option<Tile>>,
+    /// 最後に捨てられた（もしくは加槓された）牌
+    last_discard: Option<Tile>,
+}
+
+impl EventObserver {
+    pub(crate) fn new(players: usize) -> EventObserver {
+        EventObserver {
+            last_draw: vec![None; players],
+            last_discard: None,
+        }
+    }
+
+    /// 出来事を打ち手に見せる出来事にする（牌はまだ隠さない）
+    ///
+    /// 配牌（山の並び）は誰にも見せないので`None`を返す。
+    pub(crate) fn observe<'a>(&mut self, event: &'a Event) -> Option<ObservedEvent<'a>> {
+        let observed = match event {
+            Event::Deal { .. } => return None,
+            Event::Draw { seat, tile } => {
+                self.last_draw[*seat] = Some(*tile);
+                ObservedEvent::Draw {
+                    seat: *seat,
+                    tile: Some(*tile),
+                }
+            }
+            Event::DeadWallDraw { seat, tile } => {
+                self.last_draw[*seat] = Some(*tile);
+                ObservedEvent::DeadWallDraw {
+                    seat: *seat,
+                    tile: Some(*tile),
+                }
+            }
+            Event::North { seat, tile } => {
+                self.last_draw[*seat] = Some(*tile);
+                ObservedEvent::North {
+                    seat: *seat,
+                    tile: Some(*tile),
+                }
+            }
+            Event::Discard { seat, tile, ready } => {
+                let tsumogiri = self.last_draw[*seat] == Some(*tile);
+                self.last_draw[*seat] = None;
+                self.last_discard = Some(*tile);
+                ObservedEvent::Discard {
+                    seat: *seat,
+                    tile: *tile,
+                    ready: *ready,
+                    tsumogiri,
+                }
+            }
+            Event::ClosedKan { seat, .. } => {
+                self.last_draw[*seat] = None;
+                ObservedEvent::Public(event)
+            }
+            Event::AddedKan { seat, tile } => {
+                self.last_draw[*seat] = None;
+                self.last_discard = Some(Tile::new(*tile));
+                ObservedEvent::Public(event)
+            }
+            Event::Win {
+                seat,
+                from,
+                han,
+                fu,
+                yakuman,
+            } => ObservedEvent::Win {
+                seat: *seat,
+                from: *from,
+                tile: match from {
+                    Some(_) => self.last_discard,
+                    None => self.last_draw[*seat],
+                },
+                han: *han,
+                fu: *fu,
+                yakuman: *yakuman,
+            },
+            _ => ObservedEvent::Public(event),
+        };
+        Some(observed)
+    }
+}
+
+/// 局の進行から呼ばれ、打牌・鳴き・立直・槓・和了を決める打ち手
+///
+/// 渡される`actions`は必ず1つ以上あり、その中のいずれかを返さなければならない。
+pub trait Agent {
+    /// 自分の手番で行う行動を選ぶ
+    fn act(&mut self, view: &PlayerView, actions: &[TurnAction]) -> TurnAction;
+
+    /// 席`from`の捨て牌（もしくは加槓した牌）`tile`に対する行動を選ぶ
+    fn respond(
+        &mut self,
+        view: &PlayerView,
+        tile: Tile,
+        from: usize,
+        actions: &[CallAction],
+    ) -> CallAction;
+
+    /// 局の開始（配牌の後、最初の自摸の前）を知らせる
+    fn start_round(&mut self, _view: &PlayerView) {}
+
+    /// 局の中の出来事を起きた順に知らせる
+    ///
+    /// 行動を選ばせる前と局の終了時に、それまでの出来事がまとめて渡される（`view`はまとめた後の状態）。
+    fn observe(&mut self, _view: &PlayerView, _event: &ObservedEvent) {}
+
+    /// 対局の終了と最終順位を知らせる
+    fn end_game(&mut self, _placements: &[Placement]) {}
+}
@@ -0,0 +1,110 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::board::agent::*;
+use crate::hand_info::hand_analyzer::HandAnalyzer;
+use crate::hand_info::shanten::ShantenState;
+use crate::tile::{Tile, TileType};
+
+/// 選べる行動の中から一様ランダムに選ぶ打ち手
+pub struct RandomAgent {
+    rng: StdRng,
+}
+
+impl RandomAgent {
+    /// 同じシードからは同じ選択をする打ち手を作る
+    pub fn new(seed: u64) -> RandomAgent {
+        RandomAgent {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn act(&mut self, _view: &PlayerView, actions: &[TurnAction]) -> TurnAction {
+        *actions.choose(&mut self.rng).unwrap()
+    }
+
+    fn respond(
+        &mut self,
+        _view: &PlayerView,
+        _tile: Tile,
+        _from: usize,
+        actions: &[CallAction],
+    ) -> CallAction {
+        *actions.choose(&mut self.rng).unwrap()
+    }
+}
+
+/// 向聴数が最も小さくなる牌を捨てる打ち手
+///
+/// 和了れる時は必ず和了り、聴牌すれば立直できる限り立直する。鳴き・槓はしない（北は必ず抜く）。
+/// 向聴数が同じ打牌の中では、孤立牌、么九牌の順に優先して捨てる。
+#[derive(Default)]
+pub struct GreedyAgent;
+
+impl GreedyAgent {
+    pub fn new() -> GreedyAgent {
+        GreedyAgent
+    }
+
+    /// 打牌の候補から向聴数が最小になるものを選ぶ
+    fn choose_discard(view: &PlayerView, actions: &[TurnAction]) -> Option<TurnAction> {
+        let hand = view.hand();
+        let single = HandAnalyzer::new(hand).map(|a| a.single).unwrap_or_default();
+        let state = ShantenState::new(hand);
+
+        let mut best: Option<((i32, bool, bool), TileType)> = None;
+        for action in actions {
+            let tile = match action {
+                TurnAction::Discard { tile, ready: false } => *tile,
+                _ => continue,
+            };
+            let mut test = state;
+            if test.remove(tile).is_err() {
+                continue;
+            }
+            let shanten = test.shanten();
+            let key = (
+                shanten,
+                !single.contains(&tile),
+                !(tile >= Tile::Z1 || tile % 9 == 0 || tile % 9 == 8),
+            );
+            if best.is_none_or(|(k, _)| key < k) {
+                best = Some((key, tile));
+            }
+        }
+        let (_, tile) = best?;
+        let ready = TurnAction::Discard { tile, ready: true };
+        if actions.contains(&ready) {
+            return Some(ready);
+        }
+        Some(TurnAction::Discard { tile, ready: false })
+    }
+}
+
+impl Agent for GreedyAgent {
+    fn act(&mut self, view: &PlayerView, actions: &[TurnAction]) -> TurnAction {
+        for preferred in [TurnAction::Tsumo, TurnAction::North] {
+            if actions.contains(&preferred) {
+                return preferred;
+            }
+        }
+        GreedyAgent::choose_discard(view, actions).unwrap_or(actions[0])
+    }
+
+    fn respond(
+        &mut self,
+        _view: &PlayerView,
+        _tile: Tile,
+        _from: usize,
+        actions: &[CallAction],
+    ) -> CallAction {
+        if actions.contains(&CallAction::Ron) {
+            CallAction::Ron
+        } else {
+            CallAction::Pass
+        }
+    }
+}
@@ -0,0 +1,926 @@
+use rand::Rng;
+
+use rand::seq::SliceRandom;
+
+use crate::board::agent::*;
+use crate::board::event::{self, Event};
+use crate::board::exhaustive_draw::{self, ExhaustiveDraw};
+use crate::board::game::{Game, Placement, RoundEnd};
+use crate::board::payment::{ron_payment, tsumo_payments};
+use crate::board::round::Round;
+use crate::board::wall::{dora_from_indicator, Wall};
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::*;
+use crate::hand_info::shanten::ShantenState;
+use crate::hand_info::opened::OpenType;
+use crate::settings::Settings;
+use crate::tile::*;
+use crate::winning_hand::score::{self, Score};
+
+/// 配牌の枚数
+const INITIAL_HAND_LEN: usize = 13;
+/// 1局の中でできる槓の最大数
+const MAX_KANS: usize = 4;
+/// 立直に必要な持ち点（供託する立直棒の点数）
+const READY_DEPOSIT: i32 = 1000;
+
+/// 和了
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Win {
+    /// 和了った席
+    pub seat: usize,
+    /// 点数計算の結果
+    pub score: Score,
+}
+
+/// 局の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RoundResult {
+    /// 和了（ダブロンの場合は放銃者に近い順）
+    pub wins: Vec<Win>,
+    /// 放銃した席（自摸和了と流局の場合は`None`）
+    pub discarder: Option<usize>,
+    /// 荒牌平局の結果
+    pub exhaustive_draw: Option<ExhaustiveDraw>,
+    /// 和了・流局による点数の移動（席順、立直棒の供託は含まない）
+    pub score_changes: Vec<i32>,
+    /// `Game::end_round`に渡す局の終わり方
+    pub end: RoundEnd,
+    /// 局の中の出来事（配牌は含まない）
+    pub events: Vec<Event>,
+}
+
+/// 対局の記録
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GameRecord {
+    /// 対局の中の出来事（`Replay`で再現できる）
+    pub events: Vec<Event>,
+    /// 最終順位
+    pub placements: Vec<Placement>,
+}
+
+/// 山から親を起点に13枚ずつ配牌する
+pub fn deal(wall: &mut Wall, dealer: usize, players: usize) -> Result<Vec<Hand>> {
+    let mut tiles: Vec<Vec<Tile>> = vec![Vec::new(); players];
+    for _ in 0..INITIAL_HAND_LEN {
+        for i in 0..players {
+            let tile = wall.draw().ok_or(Error::TooFewTilesToDeal)?;
+            tiles[(dealer + i) % players].push(tile);
+        }
+    }
+    Ok(tiles
+        .into_iter()
+        .map(|mut t| {
+            t.sort();
+            Hand::new(t, None)
+        })
+        .collect())
+}
+
+/// 山の並び順を指定して配牌し、対局の現在の局を作る
+pub fn start_round(game: &Game, tiles: Vec<Tile>) -> Result<(Round, Wall)> {
+    let mut wall = Wall::from_tiles(tiles)?;
+    let hands = deal(&mut wall, game.dealer(), game.scores.len())?;
+    Ok((game.new_round(hands), wall))
+}
+
+/// 局を最後まで進める
+///
+/// `agents`は席順に並べた打ち手。点数の移動は`round`に反映される。
+///
+/// 以下は簡略化している。
+/// * 立直後の暗槓はできない
+/// * 槓ドラは槓をした直後にめくる
+/// * 途中流局（四風連打・四槓散了など）と喰い替えの禁止はない
+pub fn play_round(
+    round: &mut Round,
+    wall: &mut Wall,
+    agents: &mut [Box<dyn Agent>],
+    settings: &Settings,
+) -> Result<RoundResult> {
+    if agents.len() != round.players.len() {
+        return Err(Error::AgentCountMismatch {
+            agents: agents.len(),
+            players: round.players.len(),
+        });
+    }
+    let mut engine = Engine::new(round, wall, settings)?;
+    engine.run(agents)
+}
+
+/// 対局を最後まで進め、出来事の記録と最終順位を返す
+///
+/// 山は`rng`だけから作られるので、同じシードと打ち手からは同じ記録ができる。
+pub fn play_game<R: Rng + ?Sized>(
+    game: &mut Game,
+    agents: &mut [Box<dyn Agent>],
+    settings: &Settings,
+    rng: &mut R,
+) -> Result<GameRecord> {
+    let players = game.scores.len();
+    let mut events: Vec<Event> = Vec::new();
+    while !game.is_finished {
+        let mut tiles = Wall::tile_set(players);
+        tiles.shuffle(rng);
+        let (mut round, mut wall) = start_round(game, tiles.clone())?;
+        events.push(Event::Deal { wall: tiles });
+        let mut result = play_round(&mut round, &mut wall, agents, settings)?;
+        events.append(&mut result.events);
+        game.end_round(&round, result.end, settings);
+    }
+    let placements = game.placements(settings);
+    for agent in agents.iter_mut() {
+        agent.end_game(&placements);
+    }
+    Ok(GameRecord { events, placements })
+}
+
+/// 局の進行の段階
+enum Phase {
+    /// 自摸山から引いて手番を始める
+    Draw(usize),
+    /// 自摸（もしくは嶺上牌）を引いた後の手番
+    Turn { seat: usize, is_dead_wall_draw: bool },
+    /// 鳴いた後の打牌
+    AfterCall(usize),
+    /// 局の終了
+    Finished(RoundResult),
+}
+
+/// 局の進行を管理する
+struct Engine<'a> {
+    round: &'a mut Round,
+    wall: &'a mut Wall,
+    settings: &'a Settings,
+    /// 各プレイヤーの和了牌（手牌が変わるたびに更新する）
+    waits: Vec<Vec<TileType>>,
+    /// 和了牌を見逃したことによる振聴（同巡内もしくは立直後）
+    missed: Vec<bool>,
+    /// この局で行われた槓の数
+    kans: usize,
+    /// 局の中の出来事
+    events: Vec<Event>,
+    /// 打ち手に知らせた出来事の数
+    notified: usize,
+    /// 打ち手に見せる出来事を作る
+    observer: EventObserver,
+}
+
+impl<'a> Engine<'a> {
+    fn new(round: &'a mut Round, wall: &'a mut Wall, settings: &'a Settings) -> Result<Engine<'a>> {
+        let len = round.players.len();
+        let mut engine = Engine {
+            round,
+            wall,
+            settings,
+            waits: vec![Vec::new(); len],
+            missed: vec![false; len],
+            kans: 0,
+            events: Vec::new(),
+            notified: 0,
+            observer: EventObserver::new(len),
+        };
+        for seat in 0..len {
+            engine.update_waits(seat)?;
+        }
+        Ok(engine)
+    }
+
+    fn run(&mut self, agents: &mut [Box<dyn Agent>]) -> Result<RoundResult> {
+        for (seat, agent) in agents.iter_mut().enumerate() {
+            agent.start_round(&PlayerView::new(self.round, self.wall, seat));
+        }
+        let mut phase = Phase::Draw(self.round.dealer);
+        loop {
+            phase = match phase {
+                Phase::Draw(seat) => {
+                    if self.wall.remaining() == 0 {
+                        Phase::Finished(self.exhaustive_draw()?)
+                    } else {
+                        let event = event::draw(self.round, self.wall, seat)?;
+                        self.events.push(event);
+                        Phase::Turn {
+                            seat,
+                            is_dead_wall_draw: false,
+                        }
+                    }
+                }
+                Phase::Turn {
+                    seat,
+                    is_dead_wall_draw,
+                } => {
+                    let actions = self.turn_actions(seat, is_dead_wall_draw)?;
+                    match self.act(agents, seat, &actions)? {
+                        TurnAction::Tsumo => {
+                            let score = self
+                                .evaluate_self_pick(seat, is_dead_wall_draw)?
+                                .ok_or(Error::NotWinningHand(seat))?;
+                            Phase::Finished(self.settle_self_pick(seat, score))
+                        }
+                        TurnAction::ClosedKan(tile) => {
+                            let event = event::closed_kan(self.round, seat, tile)?;
+                            self.events.push(event);
+                            self.after_kan(seat)?
+                        }
+                        TurnAction::AddedKan(tile) => {
+                            let event = event::added_kan(self.round, seat, tile)?;
+                            self.events.push(event);
+                            // 搶槓
+                            let wins = self.respond(agents, seat, Tile::new(tile), true)?.0;
+                            if wins.is_empty() {
+                                self.after_kan(seat)?
+                            } else {
+                                Phase::Finished(self.settle_ron(seat, wins))
+                            }
+                        }
+                        TurnAction::North => {
+                            let event = event::north(self.round, self.wall, seat)?;
+                            self.events.push(event);
+                            self.update_waits(seat)?;
+                            Phase::Turn {
+                                seat,
+                                is_dead_wall_draw: true,
+                            }
+                        }
+                        TurnAction::Discard { tile, ready } => {
+                            self.discard(agents, seat, tile, ready)?
+                        }
+                    }
+                }
+                Phase::AfterCall(seat) => {
+                    let actions = self.discard_actions(seat, false)?;
+                    match self.act(agents, seat, &actions)? {
+                        TurnAction::Discard { tile, ready } => {
+                            self.discard(agents, seat, tile, ready)?
+                        }
+                        action => return Err(Error::NotADiscard(action)),
+                    }
+                }
+                Phase::Finished(mut result) => {
+                    self.notify(agents);
+                    result.events = std::mem::take(&mut self.events);
+                    return Ok(result);
+                }
+            };
+        }
+    }
+
+    /// まだ知らせていない出来事を全員に知らせる（他家の公開されない牌は隠す）
+    fn notify(&mut self, agents: &mut [Box<dyn Agent>]) {
+        for event in &self.events[self.notified..] {
+            let Some(observed) = self.observer.observe(event) else {
+                continue;
+            };
+            for (seat, agent) in agents.iter_mut().enumerate() {
+                let view = PlayerView::new(self.round, self.wall, seat);
+                agent.observe(&view, &observed.seen_by(seat));
+            }
+        }
+        self.notified = self.events.len();
+    }
+
+    /// 打ち手に手番の行動を選ばせる
+    fn act(
+        &mut self,
+        agents: &mut [Box<dyn Agent>],
+        seat: usize,
+        actions: &[TurnAction],
+    ) -> Result<TurnAction> {
+        self.notify(agents);
+        let view = PlayerView::new(self.round, self.wall, seat);
+        let action = agents[seat].act(&view, actions);
+        if !actions.contains(&action) {
+            return Err(Error::IllegalAction { seat, action });
+        }
+        Ok(action)
+    }
+
+    /// 自摸した後に選べる行動
+    fn turn_actions(&self, seat: usize, is_dead_wall_draw: bool) -> Result<Vec<TurnAction>> {
+        let player = &self.round.players[seat];
+        let hand = &player.hand;
+        let mut actions: Vec<TurnAction> = Vec::new();
+
+        if let Some(t) = hand.drawn() {
+            if self.waits[seat].contains(&t.get())
+                && self.evaluate_self_pick(seat, is_dead_wall_draw)?.is_some()
+            {
+                actions.push(TurnAction::Tsumo);
+            }
+        }
+
+        let is_ready = player.status.has_claimed_ready;
+        let can_replace = self.wall.remaining() > 0;
+        if !is_ready && can_replace && self.kans < MAX_KANS {
+            let counts = closed_counts(hand);
+            for tile in Tile::M1..=Tile::Z7 {
+                if counts[tile as usize] == 4 {
+                    actions.push(TurnAction::ClosedKan(tile));
+                }
+            }
+            for open in hand.opened() {
+                let tile = open.tiles[0].get();
+                if open.category == OpenType::Pon && counts[tile as usize] > 0 {
+                    actions.push(TurnAction::AddedKan(tile));
+                }
+            }
+        }
+        let has_north = match hand.drawn() {
+            Some(t) if is_ready => t.get() == Tile::Z4,
+            _ => closed_counts(hand)[Tile::Z4 as usize] > 0,
+        };
+        if self.round.players.len() == 3 && can_replace && has_north {
+            actions.push(TurnAction::North);
+        }
+
+        actions.append(&mut self.discard_actions(seat, true)?);
+        Ok(actions)
+    }
+
+    /// 打牌の候補（立直中はツモ切りのみ）
+    fn discard_actions(&self, seat: usize, can_ready: bool) -> Result<Vec<TurnAction>> {
+        let player = &self.round.players[seat];
+        let hand = &player.hand;
+        if player.status.has_claimed_ready {
+            let tile = hand.drawn().ok_or(Error::NoDrawnTile(seat))?;
+            return Ok(vec![TurnAction::Discard {
+                tile: tile.get(),
+                ready: false,
+            }]);
+        }
+
+        let mut candidates: Vec<TileType> = hand.tiles().iter().map(|t| t.get()).collect();
+        if let Some(t) = hand.drawn() {
+            candidates.push(t.get());
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        // 門前・1000点以上・自摸が残っている場合、聴牌になる打牌で立直できる
+        let state = ShantenState::new(hand);
+        let can_ready = can_ready
+            && !player.status.has_claimed_open
+            && player.score >= READY_DEPOSIT
+            && self.wall.remaining() >= self.round.players.len()
+            && state.shanten() <= 0;
+
+        let mut actions: Vec<TurnAction> = Vec::new();
+        for tile in candidates {
+            actions.push(TurnAction::Discard { tile, ready: false });
+            if can_ready {
+                let mut test = state;
+                test.remove(tile)?;
+                if test.shanten() == 0 {
+                    actions.push(TurnAction::Discard { tile, ready: true });
+                }
+            }
+        }
+        Ok(actions)
+    }
+
+    /// 牌を捨て、他家の鳴き・ロンを処理する
+    fn discard(
+        &mut self,
+        agents: &mut [Box<dyn Agent>],
+        seat: usize,
+        tile: TileType,
+        ready: bool,
+    ) -> Result<Phase> {
+        let player = &self.round.players[seat];
+        let is_tsumogiri = player.hand.drawn().map(|t| t.get()) == Some(tile);
+        // 同巡内の振聴は自分の打牌で解消する（立直後は解消しない）
+        if !player.status.has_claimed_ready {
+            self.missed[seat] = false;
+        }
+        let event = event::discard(self.round, seat, tile, ready)?;
+        self.events.push(event);
+        if !is_tsumogiri {
+            self.update_waits(seat)?;
+        }
+
+        let discarded = self.round.players[seat].river.last().unwrap().tile;
+        let (wins, call) = self.respond(agents, seat, discarded, false)?;
+        if !wins.is_empty() {
+            return Ok(Phase::Finished(self.settle_ron(seat, wins)));
+        }
+        // ロンされなければ立直が成立する
+        if ready {
+            let event = event::ready(self.round, seat)?;
+            self.events.push(event);
+        }
+
+        let (caller, action) = match call {
+            Some(call) => call,
+            None => return Ok(Phase::Draw((seat + 1) % self.round.players.len())),
+        };
+        let (tiles, category) = match action {
+            CallAction::Pon => (vec![tile; 2], OpenType::Pon),
+            CallAction::Kan => (vec![tile; 3], OpenType::Kan),
+            CallAction::Chi(tiles) => (tiles.to_vec(), OpenType::Chi),
+            _ => return Err(Error::NotACall(action)),
+        };
+        let event = event::call(self.round, caller, seat, category, &tiles)?;
+        self.events.push(event);
+        if category == OpenType::Kan {
+            return self.after_kan(caller);
+        }
+        Ok(Phase::AfterCall(caller))
+    }
+
+    /// 他家の捨て牌（`is_robbing_a_quad`なら加槓した牌）に対する行動を集める
+    ///
+    /// ロンした席と点数（放銃者に近い順）、およびロンがない場合に優先される鳴きを返す。
+    #[allow(clippy::type_complexity)]
+    fn respond(
+        &mut self,
+        agents: &mut [Box<dyn Agent>],
+        discarder: usize,
+        tile: Tile,
+        is_robbing_a_quad: bool,
+    ) -> Result<(Vec<(usize, Score)>, Option<(usize, CallAction)>)> {
+        let len = self.round.players.len();
+        let mut wins: Vec<(usize, Score)> = Vec::new();
+        let mut call: Option<(usize, CallAction)> = None;
+        for i in 1..len {
+            let seat = (discarder + i) % len;
+            let mut actions = vec![CallAction::Pass];
+            let is_wait = self.waits[seat].contains(&tile.get());
+            let mut score: Option<Score> = None;
+            if is_wait && !self.is_furiten(seat) {
+                score = self.evaluate_ron(seat, tile, is_robbing_a_quad)?;
+                if score.is_some() {
+                    actions.push(CallAction::Ron);
+                }
+            }
+            if !is_robbing_a_quad {
+                actions.append(&mut self.call_actions(seat, tile.get(), i == 1));
+            }
+            let action = if actions.len() == 1 {
+                CallAction::Pass
+            } else {
+                self.notify(agents);
+                let view = PlayerView::new(self.round, self.wall, seat);
+                agents[seat].respond(&view, tile, discarder, &actions)
+            };
+            if !actions.contains(&action) {
+                return Err(Error::IllegalCall { seat, action });
+            }
+            match action {
+                CallAction::Ron => wins.push((seat, score.unwrap())),
+                CallAction::Pass => {}
+                // ポン・大明槓はチーより優先される
+                CallAction::Pon | CallAction::Kan => {
+                    if !matches!(call, Some((_, CallAction::Pon | CallAction::Kan))) {
+                        call = Some((seat, action));
+                    }
+                }
+                CallAction::Chi(_) => {
+                    if call.is_none() {
+                        call = Some((seat, action));
+                    }
+                }
+            }
+            if is_wait && action != CallAction::Ron {
+                self.missed[seat] = true;
+            }
+        }
+        Ok((wins, call))
+    }
+
+    /// 捨て牌に対して選べる鳴き
+    fn call_actions(&self, seat: usize, tile: TileType, is_next: bool) -> Vec<CallAction> {
+        let player = &self.round.players[seat];
+        // 河底牌は鳴けない
+        if player.status.has_claimed_ready || self.wall.remaining() == 0 {
+            return Vec::new();
+        }
+        let counts = closed_counts(&player.hand);
+        let count = |t: TileType| counts[t as usize];
+        let mut actions: Vec<CallAction> = Vec::new();
+        if count(tile) >= 2 {
+            actions.push(CallAction::Pon);
+        }
+        if count(tile) >= 3 && self.kans < MAX_KANS {
+            actions.push(CallAction::Kan);
+        }
+        if is_next && self.settings.game_mode.allows_chi() && tile <= Tile::S9 {
+            let number = tile % 9;
+            let mut pairs: Vec<[TileType; 2]> = Vec::new();
+            if number >= 2 {
+                pairs.push([tile - 2, tile - 1]);
+            }
+            if (1..=7).contains(&number) {
+                pairs.push([tile - 1, tile + 1]);
+            }
+            if number <= 6 {
+                pairs.push([tile + 1, tile + 2]);
+            }
+            for pair in pairs {
+                if count(pair[0]) > 0 && count(pair[1]) > 0 {
+                    actions.push(CallAction::Chi(pair));
+                }
+            }
+        }
+        actions
+    }
+
+    /// 槓の後の処理（槓ドラをめくり、嶺上牌を引く）
+    fn after_kan(&mut self, seat: usize) -> Result<Phase> {
+        self.kans += 1;
+        self.events.push(event::reveal_dora(self.wall));
+        let event = event::draw_replacement(self.round, self.wall, seat)?;
+        self.events.push(event);
+        self.update_waits(seat)?;
+        Ok(Phase::Turn {
+            seat,
+            is_dead_wall_draw: true,
+        })
+    }
+
+    /// ツモった牌を除いた手牌の和了牌を求め直す
+    fn update_waits(&mut self, seat: usize) -> Result<()> {
+        self.waits[seat] = get_winning_tiles(&self.round.players[seat].hand)?;
+        Ok(())
+    }
+
+    /// 振聴か否か
+    fn is_furiten(&self, seat: usize) -> bool {
+        self.missed[seat]
+            || self.round.players[seat]
+                .river
+                .iter()
+                .any(|d| self.waits[seat].contains(&d.tile.get()))
+    }
+
+    /// ドラ（立直していれば裏ドラを含む）の種類
+    fn dora(&self, seat: usize) -> Vec<TileType> {
+        let players = self.round.players.len();
+        let mut indicators: Vec<Tile> = self.wall.dora_indicators().to_vec();
+        if self.round.players[seat].status.has_claimed_ready {
+            indicators.extend_from_slice(self.wall.ura_dora_indicators());
+        }
+        indicators
+            .iter()
+            .map(|t| dora_from_indicator(t.get(), players))
+            .collect()
+    }
+
+    fn evaluate_self_pick(&self, seat: usize, is_dead_wall_draw: bool) -> Result<Option<Score>> {
+        let player = &self.round.players[seat];
+        let mut status = player.status.clone();
+        status.is_self_picked = true;
+        status.is_dead_wall_draw = is_dead_wall_draw;
+        status.is_last_tile = !is_dead_wall_draw && self.wall.remaining() == 0;
+        score::calculate(
+            &player.hand,
+            &status,
+            self.settings,
+            &self.dora(seat),
+            player.north_bonus,
+        )
+    }
+
+    fn evaluate_ron(&self, seat: usize, tile: Tile, is_robbing_a_quad: bool) -> Result<Option<Score>> {
+        let player = &self.round.players[seat];
+        let mut hand = player.hand.clone();
+        hand.set_drawn(Some(tile));
+        let mut status = player.status.clone();
+        status.is_self_picked = false;
+        status.is_robbing_a_quad = is_robbing_a_quad;
+        status.is_last_tile = !is_robbing_a_quad && self.wall.remaining() == 0;
+        score::calculate(
+            &hand,
+            &status,
+            self.settings,
+            &self.dora(seat),
+            player.north_bonus,
+        )
+    }
+
+    /// 自摸和了の支払いを求める
+    fn settle_self_pick(&mut self, seat: usize, score: Score) -> RoundResult {
+        let len = self.round.players.len();
+        let payments = tsumo_payments(
+            score.base_points,
+            seat,
+            self.round.dealer,
+            len,
+            self.settings.three_player_tsumo,
+        );
+        let mut score_changes = vec![0; len];
+        for (payer, payment) in payments.iter().enumerate() {
+            if payer == seat {
+                continue;
+            }
+            let payment = payment + self.round.honba as i32 * 100;
+            score_changes[payer] -= payment;
+            score_changes[seat] += payment;
+        }
+        score_changes[seat] += self.round.deposits as i32 * READY_DEPOSIT;
+        let end = RoundEnd::Win {
+            dealer_won: seat == self.round.dealer,
+        };
+        self.finish(vec![Win { seat, score }], None, None, score_changes, end)
+    }
+
+    /// ロン和了の支払いを求める（本場と供託は放銃者に近い和了者が受け取る）
+    fn settle_ron(&mut self, discarder: usize, wins: Vec<(usize, Score)>) -> RoundResult {
+        let mut score_changes = vec![0; self.round.players.len()];
+        for (i, (seat, score)) in wins.iter().enumerate() {
+            let mut payment = ron_payment(score.base_points, *seat == self.round.dealer);
+            if i == 0 {
+                payment += self.round.honba as i32 * 300;
+                score_changes[*seat] += self.round.deposits as i32 * READY_DEPOSIT;
+            }
+            score_changes[discarder] -= payment;
+            score_changes[*seat] += payment;
+        }
+        let dealer_won = wins.iter().any(|(seat, _)| *seat == self.round.dealer);
+        let wins = wins
+            .into_iter()
+            .map(|(seat, score)| Win { seat, score })
+            .collect();
+        let end = RoundEnd::Win { dealer_won };
+        self.finish(wins, Some(discarder), None, score_changes, end)
+    }
+
+    fn exhaustive_draw(&mut self) -> Result<RoundResult> {
+        let result = exhaustive_draw::resolve(self.round, self.settings)?;
+        let score_changes = result.score_changes.clone();
+        let end = RoundEnd::Draw {
+            dealer_ready: result.dealer_keeps,
+        };
+        Ok(self.finish(Vec::new(), None, Some(result), score_changes, end))
+    }
+
+    /// 和了・流局を記録し、点数の移動を反映して局を終える
+    fn finish(
+        &mut self,
+        wins: Vec<Win>,
+        discarder: Option<usize>,
+        exhaustive_draw: Option<ExhaustiveDraw>,
+        score_changes: Vec<i32>,
+        end: RoundEnd,
+    ) -> RoundResult {
+        for win in &wins {
+            self.events.push(Event::Win {
+                seat: win.seat,
+                from: discarder,
+                han: win.score.han,
+                fu: win.score.fu,
+                yakuman: win.score.yakuman,
+            });
+        }
+        if let Some(result) = &exhaustive_draw {
+            self.events.push(Event::ExhaustiveDraw {
+                ready: result.ready.clone(),
+                nagashi_mangan: result.nagashi_mangan.clone(),
+            });
+        }
+        let event = event::end_round(self.round, end, &score_changes);
+        self.events.push(event);
+        RoundResult {
+            wins,
+            discarder,
+            exhaustive_draw,
+            score_changes,
+            end,
+            // 打ち手に知らせてから`run`で移す
+            events: Vec::new(),
+        }
+    }
+}
+
+/// 副露とツモった牌を含めない、手の中の牌を種類別に数える
+fn closed_counts(hand: &Hand) -> TileSummarize {
+    let mut counts: TileSummarize = [0; Tile::LEN];
+    for t in hand.tiles().iter().chain(hand.drawn().iter()) {
+        counts[t.get() as usize] += 1;
+    }
+    counts
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::bot::{GreedyAgent, RandomAgent};
+    use crate::settings::GameLength;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// 決まった打牌だけをする打ち手
+    struct ScriptedAgent {
+        discards: Vec<TileType>,
+    }
+
+    impl Agent for ScriptedAgent {
+        fn act(&mut self, _view: &PlayerView, actions: &[TurnAction]) -> TurnAction {
+            if actions.contains(&TurnAction::Tsumo) {
+                return TurnAction::Tsumo;
+            }
+            let tile = self.discards.remove(0);
+            TurnAction::Discard { tile, ready: false }
+        }
+
+        fn respond(
+            &mut self,
+            _view: &PlayerView,
+            _tile: Tile,
+            _from: usize,
+            actions: &[CallAction],
+        ) -> CallAction {
+            if actions.contains(&CallAction::Ron) {
+                CallAction::Ron
+            } else {
+                CallAction::Pass
+            }
+        }
+    }
+
+    /// 手牌と山を指定して局を作る（山は先頭から配牌後の自摸の順に並べる）
+    fn make_round(hands: [&str; 4], draws: &str) -> (Round, Wall) {
+        let settings = Settings::new();
+        let game = Game::new(&settings);
+        let round = game.new_round(hands.iter().map(|h| Hand::parse_lossy(h)).collect());
+        let mut tiles = vec![Tile::new(Tile::S9); 14];
+        tiles.extend_from_slice(Hand::parse_lossy(draws).tiles());
+        (round, Wall::from_tiles(tiles).unwrap())
+    }
+
+    #[test]
+    /// 配牌は親から13枚ずつ
+    fn deal_test() {
+        let mut wall = Wall::new(4, &mut StdRng::seed_from_u64(0));
+        let hands = deal(&mut wall, 1, 4).unwrap();
+        assert!(hands.iter().all(|h| h.tiles().len() == 13));
+        assert_eq!(wall.remaining(), 136 - 14 - 52);
+    }
+
+    #[test]
+    /// 放銃者がロン和了の点数と本場・供託を支払う
+    fn ron() {
+        let (mut round, mut wall) = make_round(
+            [
+                "123456789m1299p",
+                "123m456p789s1122z",
+                "111m222p33s444z55z",
+                "123m456p789s1z234p",
+            ],
+            "3s",
+        );
+        round.honba = 1;
+        round.deposits = 1;
+        let mut agents: Vec<Box<dyn Agent>> = (0..4)
+            .map(|_| Box::new(ScriptedAgent { discards: vec![Tile::S3] }) as Box<dyn Agent>)
+            .collect();
+        let result = play_round(&mut round, &mut wall, &mut agents, &Settings::new()).unwrap();
+        assert_eq!(result.discarder, Some(0));
+        assert_eq!(result.wins.len(), 1);
+        assert_eq!(result.wins[0].seat, 2);
+        // 対々和・三暗刻の満貫に1本場と供託1本
+        assert_eq!(result.score_changes, vec![-8300, 0, 9300, 0]);
+        assert_eq!(round.deposits, 0);
+    }
+
+    #[test]
+    /// 向聴数優先の打ち手で1局を最後まで進める
+    fn greedy_round() {
+        let settings = Settings::new();
+        let game = Game::new(&settings);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut wall = Wall::new(4, &mut rng);
+        let hands = deal(&mut wall, 0, 4).unwrap();
+        let mut round = game.new_round(hands);
+        let mut agents: Vec<Box<dyn Agent>> =
+            (0..4).map(|_| Box::new(GreedyAgent::new()) as Box<dyn Agent>).collect();
+        let result = play_round(&mut round, &mut wall, &mut agents, &settings).unwrap();
+        let total: i32 = round.players.iter().map(|p| p.score).sum();
+        assert_eq!(total + round.deposits as i32 * 1000, 100000);
+        assert!(!result.wins.is_empty() || result.exhaustive_draw.is_some());
+    }
+
+    #[test]
+    /// ランダムな打ち手で対局を最後まで進める
+    fn random_game() {
+        for mut settings in [Settings::new(), Settings::new_three_players()] {
+            settings.game_length = GameLength::EastOnly;
+            let players = settings.game_mode.players();
+            let mut game = Game::new(&settings);
+            let mut agents: Vec<Box<dyn Agent>> = (0..players)
+                .map(|i| Box::new(RandomAgent::new(i as u64)) as Box<dyn Agent>)
+                .collect();
+            let mut rng = StdRng::seed_from_u64(2);
+            let record = play_game(&mut game, &mut agents, &settings, &mut rng).unwrap();
+            assert!(game.is_finished);
+            let mut ranks: Vec<usize> = record.placements.iter().map(|p| p.rank).collect();
+            ranks.sort_unstable();
+            assert_eq!(ranks, (1..=players).collect::<Vec<usize>>());
+        }
+    }
+
+    /// 見せられた自摸を確かめながらランダムに打つ打ち手
+    struct PeekingAgent {
+        seat: usize,
+        inner: RandomAgent,
+        /// 見せられた自摸の数（自家の分、他家の分）
+        draws: std::rc::Rc<std::cell::Cell<(usize, usize)>>,
+    }
+
+    impl Agent for PeekingAgent {
+        fn act(&mut self, view: &PlayerView, actions: &[TurnAction]) -> TurnAction {
+            return self.inner.act(view, actions);
+        }
+
+        fn respond(
+            &mut self,
+            view: &PlayerView,
+            tile: Tile,
+            from: usize,
+            actions: &[CallAction],
+        ) -> CallAction {
+            return self.inner.respond(view, tile, from, actions);
+        }
+
+        fn observe(&mut self, _view: &PlayerView, event: &ObservedEvent) {
+            let (seat, tile) = match *event {
+                ObservedEvent::Draw { seat, tile }
+                | ObservedEvent::DeadWallDraw { seat, tile }
+                | ObservedEvent::North { seat, tile } => (seat, tile),
+                _ => return,
+            };
+            let (own, others) = self.draws.get();
+            if seat == self.seat {
+                assert!(tile.is_some());
+                self.draws.set((own + 1, others));
+            } else {
+                assert_eq!(tile, None);
+                self.draws.set((own, others + 1));
+            }
+        }
+    }
+
+    #[test]
+    /// 他家の自摸・嶺上牌の自摸・北抜きの補充牌は見せない
+    fn others_draws_are_hidden() {
+        let mut settings = Settings::new_three_players();
+        settings.game_length = GameLength::EastOnly;
+        let draws = std::rc::Rc::new(std::cell::Cell::new((0, 0)));
+        let mut game = Game::new(&settings);
+        let mut agents: Vec<Box<dyn Agent>> = (0..3)
+            .map(|seat| {
+                Box::new(PeekingAgent {
+                    seat,
+                    inner: RandomAgent::new(seat as u64 + 20),
+                    draws: draws.clone(),
+                }) as Box<dyn Agent>
+            })
+            .collect();
+        let mut rng = StdRng::seed_from_u64(4);
+        play_game(&mut game, &mut agents, &settings, &mut rng).unwrap();
+        let (own, others) = draws.get();
+        assert!(own > 0);
+        assert_eq!(others, own * 2);
+    }
+
+    #[test]
+    /// 同じシードからは同じ記録ができ、記録から同じ状態が再現される
+    fn replay() {
+        let mut settings = Settings::new_three_players();
+        settings.game_length = GameLength::EastOnly;
+        let play = || {
+            let mut game = Game::new(&settings);
+            let mut agents: Vec<Box<dyn Agent>> = (0..3)
+                .map(|i| Box::new(RandomAgent::new(i + 10)) as Box<dyn Agent>)
+                .collect();
+            let mut rng = StdRng::seed_from_u64(3);
+            let record = play_game(&mut game, &mut agents, &settings, &mut rng).unwrap();
+            (game, record)
+        };
+        let (game, record) = play();
+        assert_eq!(play().1, record);
+
+        let events = event::from_text(&event::to_text(&record.events)).unwrap();
+        assert_eq!(events, record.events);
+        let mut replay = event::Replay::new(&settings);
+        for e in &events {
+            replay.apply(e).unwrap();
+        }
+        assert_eq!(format!("{:?}", replay.game), format!("{:?}", game));
+        assert_eq!(replay.game.placements(&settings), record.placements);
+
+        // 記録と異なる出来事は反映できない
+        let mut replay = event::Replay::new(&settings);
+        replay.apply(&events[0]).unwrap();
+        let first_draw = replay.wall.as_ref().unwrap().clone().draw().unwrap();
+        let wrong = Event::Draw {
+            seat: 0,
+            tile: Tile::new((first_draw.get() + 1) % Tile::LEN as TileType),
+        };
+        assert!(matches!(
+            replay.apply(&wrong),
+            Err(Error::ReplayDiverged { .. })
+        ));
+    }
+}
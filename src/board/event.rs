@@ -0,0 +1,592 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::engine::start_round;
+use crate::board::game::{Game, RoundEnd};
+use crate::board::round::{Discard, Round};
+use crate::board::wall::Wall;
+use crate::error::{Error, Result};
+use crate::hand_info::opened::{OpenFrom, OpenType};
+use crate::settings::Settings;
+use crate::tile::*;
+
+/// 局の中の出来事
+///
+/// 局・山を変化させる出来事は、この後にある同名の関数で局に反映すると同時に作られる。
+/// `apply`で同じ関数を呼び直すため、記録した順に反映すれば同じ状態が再現される。
+/// 1行のテキストとの相互変換ができる（`to_string`と`parse`）。
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Event {
+    /// 配牌（山の並び順。`Wall::from_tiles`の形式）
+    Deal { wall: Vec<Tile> },
+    /// 自摸
+    Draw { seat: usize, tile: Tile },
+    /// 嶺上牌の自摸
+    DeadWallDraw { seat: usize, tile: Tile },
+    /// 打牌（`ready`なら立直宣言牌）
+    Discard { seat: usize, tile: Tile, ready: bool },
+    /// 立直の成立（立直棒の供託）
+    Ready { seat: usize },
+    /// ポン・チー・大明槓（`tiles`は手牌から出した牌の種類）
+    Call {
+        seat: usize,
+        from: usize,
+        category: OpenType,
+        tiles: Vec<TileType>,
+        called: Tile,
+    },
+    /// 暗槓
+    ClosedKan { seat: usize, tile: TileType },
+    /// 加槓
+    AddedKan { seat: usize, tile: TileType },
+    /// 北抜き（`tile`は補充した嶺上牌）
+    North { seat: usize, tile: Tile },
+    /// ドラ表示牌をめくる
+    Dora { indicator: Tile },
+    /// 和了（`from`は放銃者、自摸なら`None`）
+    Win {
+        seat: usize,
+        from: Option<usize>,
+        han: u32,
+        fu: u32,
+        yakuman: u32,
+    },
+    /// 荒牌平局（各自の聴牌と流し満貫の席）
+    ExhaustiveDraw {
+        ready: Vec<bool>,
+        nagashi_mangan: Vec<usize>,
+    },
+    /// 局の終了と点数の移動
+    EndRound {
+        end: RoundEnd,
+        score_changes: Vec<i32>,
+    },
+}
+
+impl Event {
+    /// 記録された出来事を局と山に反映する
+    ///
+    /// 記録時と結果が食い違った場合はエラーを返す。`Deal`は局を作るので`Replay`で扱う。
+    pub fn apply(&self, round: &mut Round, wall: &mut Wall) -> Result<()> {
+        let result = match self {
+            Event::Deal { .. } => return Err(Error::DealOnRound),
+            Event::Draw { seat, .. } => draw(round, wall, *seat)?,
+            Event::DeadWallDraw { seat, .. } => draw_replacement(round, wall, *seat)?,
+            Event::Discard { seat, tile, ready } => discard_tile(round, *seat, *tile, *ready)?,
+            Event::Ready { seat } => ready(round, *seat)?,
+            Event::Call {
+                seat,
+                from,
+                category,
+                tiles,
+                ..
+            } => call(round, *seat, *from, *category, tiles)?,
+            Event::ClosedKan { seat, tile } => closed_kan(round, *seat, *tile)?,
+            Event::AddedKan { seat, tile } => added_kan(round, *seat, *tile)?,
+            Event::North { seat, .. } => north(round, wall, *seat)?,
+            Event::Dora { .. } => reveal_dora(wall),
+            Event::Win { .. } | Event::ExhaustiveDraw { .. } => self.clone(),
+            Event::EndRound { end, score_changes } => end_round(round, *end, score_changes),
+        };
+        if result != *self {
+            return Err(Error::ReplayDiverged {
+                expected: Box::new(self.clone()),
+                got: Box::new(result),
+            });
+        }
+        Ok(())
+    }
+
+    /// 1行のテキストから読み込む
+    pub fn parse(line: &str) -> Result<Event> {
+        let invalid = || Error::InvalidEvent(line.to_string());
+        let words: Vec<&str> = line.split_ascii_whitespace().collect();
+        let word = |i: usize| words.get(i).copied().ok_or_else(invalid);
+        let seat = |i: usize| word(i)?.parse::<usize>().map_err(|_| invalid());
+        let tile = |i: usize| parse_tiles(word(i)?)?.first().copied().ok_or_else(invalid);
+
+        let event = match word(0)? {
+            "deal" => Event::Deal {
+                wall: parse_tiles(word(1)?)?,
+            },
+            "draw" => Event::Draw {
+                seat: seat(1)?,
+                tile: tile(2)?,
+            },
+            "dead_wall_draw" => Event::DeadWallDraw {
+                seat: seat(1)?,
+                tile: tile(2)?,
+            },
+            "discard" => Event::Discard {
+                seat: seat(1)?,
+                tile: tile(2)?,
+                ready: words.get(3) == Some(&"ready"),
+            },
+            "ready" => Event::Ready { seat: seat(1)? },
+            "call" => Event::Call {
+                seat: seat(1)?,
+                from: seat(2)?,
+                category: match word(3)? {
+                    "chi" => OpenType::Chi,
+                    "pon" => OpenType::Pon,
+                    "kan" => OpenType::Kan,
+                    _ => return Err(invalid()),
+                },
+                tiles: parse_tiles(word(4)?)?.iter().map(|t| t.get()).collect(),
+                called: tile(5)?,
+            },
+            "closed_kan" => Event::ClosedKan {
+                seat: seat(1)?,
+                tile: tile(2)?.get(),
+            },
+            "added_kan" => Event::AddedKan {
+                seat: seat(1)?,
+                tile: tile(2)?.get(),
+            },
+            "north" => Event::North {
+                seat: seat(1)?,
+                tile: tile(2)?,
+            },
+            "dora" => Event::Dora { indicator: tile(1)? },
+            "win" => {
+                let number = |i: usize| word(i)?.parse::<u32>().map_err(|_| invalid());
+                Event::Win {
+                    seat: seat(1)?,
+                    from: if word(2)? == "-" { None } else { Some(seat(2)?) },
+                    han: number(3)?,
+                    fu: number(4)?,
+                    yakuman: number(5)?,
+                }
+            }
+            "exhaustive_draw" => Event::ExhaustiveDraw {
+                ready: word(1)?.chars().map(|c| c == '1').collect(),
+                nagashi_mangan: parse_list(word(2)?).map_err(|_| invalid())?,
+            },
+            "end_round" => {
+                let keeps = word(2)? == "1";
+                Event::EndRound {
+                    end: match word(1)? {
+                        "win" => RoundEnd::Win { dealer_won: keeps },
+                        "draw" => RoundEnd::Draw {
+                            dealer_ready: keeps,
+                        },
+                        _ => return Err(invalid()),
+                    },
+                    score_changes: parse_list(word(3)?).map_err(|_| invalid())?,
+                }
+            }
+            _ => return Err(invalid()),
+        };
+        Ok(event)
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Deal { wall } => write!(f, "deal {}", tiles_to_string(wall)),
+            Event::Draw { seat, tile } => write!(f, "draw {} {}", seat, tile),
+            Event::DeadWallDraw { seat, tile } => {
+                write!(f, "dead_wall_draw {} {}", seat, tile)
+            }
+            Event::Discard { seat, tile, ready } => {
+                write!(f, "discard {} {}", seat, tile)?;
+                if *ready {
+                    write!(f, " ready")?;
+                }
+                Ok(())
+            }
+            Event::Ready { seat } => write!(f, "ready {}", seat),
+            Event::Call {
+                seat,
+                from,
+                category,
+                tiles,
+                called,
+            } => {
+                let category = match category {
+                    OpenType::Chi => "chi",
+                    OpenType::Pon => "pon",
+                    OpenType::Kan => "kan",
+                };
+                let tiles: Vec<Tile> = tiles.iter().map(|t| Tile::new(*t)).collect();
+                write!(
+                    f,
+                    "call {} {} {} {} {}",
+                    seat,
+                    from,
+                    category,
+                    tiles_to_string(&tiles),
+                    called
+                )
+            }
+            Event::ClosedKan { seat, tile } => {
+                write!(f, "closed_kan {} {}", seat, Tile::new(*tile))
+            }
+            Event::AddedKan { seat, tile } => {
+                write!(f, "added_kan {} {}", seat, Tile::new(*tile))
+            }
+            Event::North { seat, tile } => write!(f, "north {} {}", seat, tile),
+            Event::Dora { indicator } => write!(f, "dora {}", indicator),
+            Event::Win {
+                seat,
+                from,
+                han,
+                fu,
+                yakuman,
+            } => {
+                let from = from.map_or(String::from("-"), |s| s.to_string());
+                write!(f, "win {} {} {} {} {}", seat, from, han, fu, yakuman)
+            }
+            Event::ExhaustiveDraw {
+                ready,
+                nagashi_mangan,
+            } => {
+                let ready: String = ready.iter().map(|&r| if r { '1' } else { '0' }).collect();
+                write!(f, "exhaustive_draw {} {}", ready, list_to_string(nagashi_mangan))
+            }
+            Event::EndRound { end, score_changes } => {
+                let (name, keeps) = match end {
+                    RoundEnd::Win { dealer_won } => ("win", dealer_won),
+                    RoundEnd::Draw { dealer_ready } => ("draw", dealer_ready),
+                };
+                write!(
+                    f,
+                    "end_round {} {} {}",
+                    name,
+                    if *keeps { 1 } else { 0 },
+                    list_to_string(score_changes)
+                )
+            }
+        }
+    }
+}
+
+impl FromStr for Event {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Event> {
+        Event::parse(s)
+    }
+}
+
+/// 記録された出来事から対局を再現する
+#[derive(Debug, Clone)]
+pub struct Replay {
+    settings: Settings,
+    /// 対局
+    pub game: Game,
+    /// 進行中（もしくは最後に終わった）局
+    pub round: Option<Round>,
+    /// 進行中（もしくは最後に終わった）局の山
+    pub wall: Option<Wall>,
+}
+
+impl Replay {
+    /// 対局の開始時の状態を作る
+    pub fn new(settings: &Settings) -> Replay {
+        Replay {
+            settings: settings.clone(),
+            game: Game::new(settings),
+            round: None,
+            wall: None,
+        }
+    }
+
+    /// 出来事を1つ反映する
+    pub fn apply(&mut self, event: &Event) -> Result<()> {
+        if let Event::Deal { wall } = event {
+            let (round, wall) = start_round(&self.game, wall.clone())?;
+            self.round = Some(round);
+            self.wall = Some(wall);
+            return Ok(());
+        }
+        let (round, wall) = match (self.round.as_mut(), self.wall.as_mut()) {
+            (Some(round), Some(wall)) => (round, wall),
+            _ => return Err(Error::NoRound(Box::new(event.clone()))),
+        };
+        event.apply(round, wall)?;
+        if let Event::EndRound { end, .. } = event {
+            self.game.end_round(round, *end, &self.settings);
+        }
+        Ok(())
+    }
+}
+
+/// 出来事の列を1行1つのテキストにする
+pub fn to_text(events: &[Event]) -> String {
+    let mut result = String::new();
+    for event in events {
+        result.push_str(&event.to_string());
+        result.push('\n');
+    }
+    result
+}
+
+/// 1行1つのテキストから出来事の列を読み込む（空行は無視する）
+pub fn from_text(text: &str) -> Result<Vec<Event>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(Event::parse)
+        .collect()
+}
+
+/// 自摸山から1枚引く
+pub fn draw(round: &mut Round, wall: &mut Wall, seat: usize) -> Result<Event> {
+    let tile = wall.draw().ok_or(Error::NoTileInWall)?;
+    round.players[seat].hand.set_drawn(Some(tile));
+    Ok(Event::Draw { seat, tile })
+}
+
+/// 嶺上牌を1枚引く
+pub fn draw_replacement(round: &mut Round, wall: &mut Wall, seat: usize) -> Result<Event> {
+    let tile = wall
+        .draw_replacement()
+        .ok_or(Error::NoReplacementTile)?;
+    round.players[seat].hand.set_drawn(Some(tile));
+    Ok(Event::DeadWallDraw { seat, tile })
+}
+
+/// 牌を捨てて河に並べ、一発・立直・第一巡の状態を更新する
+pub fn discard(round: &mut Round, seat: usize, tile: TileType, ready: bool) -> Result<Event> {
+    check_ready(round, seat, ready)?;
+    let discarded = round.players[seat]
+        .hand
+        .discard(tile)
+        .ok_or(Error::NoTileToDiscard(tile))?;
+    Ok(put_in_river(round, seat, discarded, ready))
+}
+
+/// `discard`と同じだが、赤ドラか否かも区別して牌を捨てる
+pub fn discard_tile(round: &mut Round, seat: usize, tile: Tile, ready: bool) -> Result<Event> {
+    check_ready(round, seat, ready)?;
+    let discarded = round.players[seat]
+        .hand
+        .discard_tile(tile)
+        .ok_or(Error::NoTileToDiscard(tile.get()))?;
+    Ok(put_in_river(round, seat, discarded, ready))
+}
+
+fn check_ready(round: &Round, seat: usize, ready: bool) -> Result<()> {
+    if ready && round.players[seat].status.has_claimed_ready {
+        return Err(Error::AlreadyReady(seat));
+    }
+    Ok(())
+}
+
+fn put_in_river(round: &mut Round, seat: usize, discarded: Tile, ready: bool) -> Event {
+    let player = &mut round.players[seat];
+    player.status.is_one_shot = ready;
+    if ready {
+        player.status.has_claimed_ready = true;
+        player.status.has_claimed_double_ready = player.status.is_first_turn;
+    }
+    player.status.is_first_turn = false;
+    player.river.push(Discard {
+        tile: discarded,
+        is_called: false,
+        is_ready: ready,
+    });
+    Event::Discard {
+        seat,
+        tile: discarded,
+        ready,
+    }
+}
+
+/// 立直棒を供託する
+pub fn ready(round: &mut Round, seat: usize) -> Result<Event> {
+    round.players[seat].score -= 1000;
+    round.deposits += 1;
+    Ok(Event::Ready { seat })
+}
+
+/// 席`from`が最後に捨てた牌を鳴く
+pub fn call(
+    round: &mut Round,
+    seat: usize,
+    from: usize,
+    category: OpenType,
+    tiles: &[TileType],
+) -> Result<Event> {
+    let len = round.players.len();
+    let discard = round.players[from]
+        .river
+        .last_mut()
+        .ok_or(Error::NoDiscardToCall(from))?;
+    discard.is_called = true;
+    let called = discard.tile;
+    let player = &mut round.players[seat];
+    player
+        .hand
+        .call(tiles, called, category, open_from(seat, from, len))?;
+    player.status.has_claimed_open = true;
+    if category == OpenType::Kan {
+        player.status.kan_count += 1;
+    }
+    interrupt(round);
+    Ok(Event::Call {
+        seat,
+        from,
+        category,
+        tiles: tiles.to_vec(),
+        called,
+    })
+}
+
+/// 暗槓する
+pub fn closed_kan(round: &mut Round, seat: usize, tile: TileType) -> Result<Event> {
+    let player = &mut round.players[seat];
+    player.hand.closed_kan(tile)?;
+    player.status.kan_count += 1;
+    interrupt(round);
+    Ok(Event::ClosedKan { seat, tile })
+}
+
+/// 加槓する
+pub fn added_kan(round: &mut Round, seat: usize, tile: TileType) -> Result<Event> {
+    let player = &mut round.players[seat];
+    player.hand.added_kan(tile)?;
+    player.status.kan_count += 1;
+    interrupt(round);
+    Ok(Event::AddedKan { seat, tile })
+}
+
+/// 北を抜いて嶺上牌を補充する
+pub fn north(round: &mut Round, wall: &mut Wall, seat: usize) -> Result<Event> {
+    let tile = wall
+        .draw_replacement()
+        .ok_or(Error::NoReplacementTile)?;
+    round.extract_north(seat, tile)?;
+    Ok(Event::North { seat, tile })
+}
+
+/// 新しいドラ表示牌をめくる
+pub fn reveal_dora(wall: &mut Wall) -> Event {
+    wall.reveal_dora();
+    Event::Dora {
+        // めくられているドラ表示牌は必ず1枚以上ある
+        indicator: *wall.dora_indicators().last().unwrap(),
+    }
+}
+
+/// 点数の移動を反映して局を終える（和了なら供託は和了者が受け取った扱いになる）
+pub fn end_round(round: &mut Round, end: RoundEnd, score_changes: &[i32]) -> Event {
+    round.apply_score_changes(score_changes);
+    if matches!(end, RoundEnd::Win { .. }) {
+        round.deposits = 0;
+    }
+    Event::EndRound {
+        end,
+        score_changes: score_changes.to_vec(),
+    }
+}
+
+/// 鳴き・槓で全員の一発と第一巡が消える
+fn interrupt(round: &mut Round) {
+    for player in &mut round.players {
+        player.status.is_one_shot = false;
+        player.status.is_first_turn = false;
+    }
+}
+
+/// 鳴いた席から見た、捨てた席の位置
+pub(crate) fn open_from(caller: usize, discarder: usize, players: usize) -> OpenFrom {
+    match (discarder + players - caller) % players {
+        1 => OpenFrom::Following,
+        d if d == players - 1 => OpenFrom::Previous,
+        _ => OpenFrom::Opposite,
+    }
+}
+
+fn tiles_to_string(tiles: &[Tile]) -> String {
+    tiles.iter().map(|t| t.to_string()).collect()
+}
+
+/// `1m2m3m`のような2文字単位の牌の列を読み込む（`0m`などは赤ドラの五）
+fn parse_tiles(s: &str) -> Result<Vec<Tile>> {
+    let chars: Vec<char> = s.chars().collect();
+    chars
+        .chunks(2)
+        .map(|c| {
+            let name: String = c.iter().collect();
+            name.parse().map_err(|_| Error::InvalidTileName(name))
+        })
+        .collect()
+}
+
+/// カンマ区切りの数値の列（空なら`-`）
+fn list_to_string<T: ToString>(list: &[T]) -> String {
+    if list.is_empty() {
+        return String::from("-");
+    }
+    list.iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn parse_list<T: FromStr>(s: &str) -> std::result::Result<Vec<T>, T::Err> {
+    if s == "-" {
+        return Ok(Vec::new());
+    }
+    s.split(',').map(|x| x.parse::<T>()).collect()
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// テキストとの相互変換
+    fn text_round_trip() {
+        let events = vec![
+            Event::Deal {
+                wall: vec![Tile::new(Tile::M1), Tile::new_red(Tile::S5), Tile::new(Tile::Z7)],
+            },
+            Event::Draw {
+                seat: 0,
+                tile: Tile::new(Tile::P5),
+            },
+            Event::Draw {
+                seat: 1,
+                tile: Tile::new_red(Tile::P5),
+            },
+            Event::Discard {
+                seat: 0,
+                tile: Tile::new(Tile::P5),
+                ready: true,
+            },
+            Event::Call {
+                seat: 2,
+                from: 1,
+                category: OpenType::Chi,
+                tiles: vec![Tile::S3, Tile::S5],
+                called: Tile::new(Tile::S4),
+            },
+            Event::Win {
+                seat: 3,
+                from: None,
+                han: 3,
+                fu: 40,
+                yakuman: 0,
+            },
+            Event::ExhaustiveDraw {
+                ready: vec![true, false, false, true],
+                nagashi_mangan: vec![],
+            },
+            Event::EndRound {
+                end: RoundEnd::Draw { dealer_ready: true },
+                score_changes: vec![1500, -1500, -1500, 1500],
+            },
+        ];
+        let text = to_text(&events);
+        assert!(text.starts_with("deal 1m0s7z\ndraw 0 5p\ndraw 1 0p\ndiscard 0 5p ready\ncall 2 1 chi 3s5s 4s\n"));
+        assert_eq!(from_text(&text).unwrap(), events);
+        assert_eq!(
+            Event::parse("discard x 5p"),
+            Err(Error::InvalidEvent("discard x 5p".to_string()))
+        );
+    }
+}
@@ -0,0 +1,291 @@
+use crate::board::payment::{base_points, tsumo_payments};
+use crate::board::round::{Discard, Round};
+use crate::error::Result;
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::*;
+use crate::hand_info::opened::OpenType;
+use crate::hand_info::status::Status;
+use crate::settings::Settings;
+use crate::tile::*;
+use crate::winning_hand::checker;
+
+/// 不聴罰符の総額
+pub const NOT_READY_PENALTY: i32 = 3000;
+
+/// 荒牌平局の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExhaustiveDraw {
+    /// 各プレイヤーが聴牌しているか（席順）
+    pub ready: Vec<bool>,
+    /// 流し満貫を達成したプレイヤーの席
+    pub nagashi_mangan: Vec<usize>,
+    /// 各プレイヤーの点数の移動（席順）
+    pub score_changes: Vec<i32>,
+    /// 親が連荘するか
+    pub dealer_keeps: bool,
+}
+
+/// 流局時に聴牌と認められるか否か
+///
+/// 役のない聴牌と、自分で4枚使っている牌のみを待つ聴牌の扱いは`Settings`に従う。
+pub fn is_ready(hand: &Hand, status: &Status, settings: &Settings) -> Result<bool> {
+    let mut winning_tiles = get_winning_tiles(hand)?;
+    if !settings.ready_with_own_four_tiles {
+        let counts = count_own_tiles(hand);
+        winning_tiles.retain(|t| counts[*t as usize] < 4);
+    }
+    if winning_tiles.is_empty() {
+        return Ok(false);
+    }
+    if settings.ready_without_winning_hand {
+        return Ok(true);
+    }
+    // 立直していれば必ず役がある
+    if status.has_claimed_ready || status.has_claimed_double_ready {
+        return Ok(true);
+    }
+    for tile in winning_tiles {
+        if has_winning_hand_on(hand, tile, status, settings)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 流し満貫を達成しているか否か
+///
+/// 河がすべて么九牌で、1枚も鳴かれていなければ成立する。
+pub fn is_nagashi_mangan(river: &[Discard]) -> bool {
+    !river.is_empty()
+        && river
+            .iter()
+            .all(|d| !d.is_called && is_1_9_honor(d.tile.get()))
+}
+
+/// 荒牌平局の点数の移動と親の連荘を求める
+///
+/// 流し満貫が成立した場合は満貫の自摸和了と同じ支払いになり、不聴罰符は支払われない。
+pub fn resolve(round: &Round, settings: &Settings) -> Result<ExhaustiveDraw> {
+    let len = round.players.len();
+    let mut ready: Vec<bool> = Vec::with_capacity(len);
+    for player in &round.players {
+        ready.push(is_ready(&player.hand, &player.status, settings)?);
+    }
+    let nagashi_mangan: Vec<usize> = (0..len)
+        .filter(|&seat| is_nagashi_mangan(&round.players[seat].river))
+        .collect();
+
+    let mut score_changes = vec![0; len];
+    if !nagashi_mangan.is_empty() {
+        for &winner in &nagashi_mangan {
+            let payments = tsumo_payments(
+                base_points(5, 0),
+                winner,
+                round.dealer,
+                len,
+                settings.three_player_tsumo,
+            );
+            for (payer, payment) in payments.iter().enumerate() {
+                score_changes[payer] -= payment;
+                score_changes[winner] += payment;
+            }
+        }
+    } else {
+        let ready_count = ready.iter().filter(|&&r| r).count();
+        // 全員聴牌・全員不聴の場合は支払いなし
+        if ready_count > 0 && ready_count < len {
+            let receive = NOT_READY_PENALTY / ready_count as i32;
+            let payment = NOT_READY_PENALTY / (len - ready_count) as i32;
+            for seat in 0..len {
+                score_changes[seat] = if ready[seat] { receive } else { -payment };
+            }
+        }
+    }
+
+    Ok(ExhaustiveDraw {
+        dealer_keeps: ready[round.dealer],
+        ready,
+        nagashi_mangan,
+        score_changes,
+    })
+}
+
+/// 么九牌か否か
+fn is_1_9_honor(tile: TileType) -> bool {
+    matches!(
+        tile,
+        Tile::M1 | Tile::M9 | Tile::P1 | Tile::P9 | Tile::S1 | Tile::S9 | Tile::Z1..=Tile::Z7
+    )
+}
+
+/// 副露も含めて、手牌で使っている牌を種類別に数える（槓子は4枚と数える）
+fn count_own_tiles(hand: &Hand) -> TileSummarize {
+    let mut counts = hand.summarize_tiles();
+    if let Some(t) = hand.drawn() {
+        counts[t.get() as usize] -= 1;
+    }
+    for open in hand.opened() {
+        if open.category == OpenType::Kan {
+            counts[open.tiles[0].get() as usize] += 1;
+        }
+    }
+    counts
+}
+
+/// 与えられた牌で和了った時に役があるか否か（ロン・自摸のどちらかで役があれば良い）
+fn has_winning_hand_on(
+    hand: &Hand,
+    tile: TileType,
+    status: &Status,
+    settings: &Settings,
+) -> Result<bool> {
+    let mut test_hand = hand.clone();
+    test_hand.set_drawn(Some(Tile::new(tile)));
+    let analyzer = HandAnalyzer::new(&test_hand)?;
+    let mut test_status = status.clone();
+    for is_self_picked in [false, true] {
+        test_status.is_self_picked = is_self_picked;
+        let result = checker::check(&analyzer, &test_hand, &test_status, settings)?;
+        if result.values().any(|(_, has_won, _)| *has_won) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::round::Player;
+
+    fn make_round(hands: [&str; 4]) -> Round {
+        let players = hands
+            .iter()
+            .map(|h| Player::new(Hand::parse_lossy(h), 25000))
+            .collect();
+        Round::new(Wind::East, 0, 0, 0, players)
+    }
+
+    #[test]
+    /// 親のみ聴牌
+    fn only_dealer_is_ready() {
+        let mut round = make_round([
+            "123m456p789s1122z",
+            "13579m13579p135s",
+            "13579m13579p135s",
+            "13579m13579p135s",
+        ]);
+        let settings = Settings::new();
+        let result = round.exhaustive_draw(&settings).unwrap();
+        assert_eq!(result.ready, vec![true, false, false, false]);
+        assert_eq!(result.score_changes, vec![3000, -1000, -1000, -1000]);
+        assert!(result.dealer_keeps);
+        assert_eq!(round.players[0].score, 28000);
+        assert_eq!(round.players[1].score, 24000);
+    }
+
+    #[test]
+    /// 子が2人聴牌
+    fn two_players_are_ready() {
+        let round = make_round([
+            "13579m13579p135s",
+            "123m456p789s1122z",
+            "13579m13579p135s",
+            "1112345678999m",
+        ]);
+        let settings = Settings::new();
+        let result = resolve(&round, &settings).unwrap();
+        assert_eq!(result.score_changes, vec![-1500, 1500, -1500, 1500]);
+        assert!(!result.dealer_keeps);
+    }
+
+    #[test]
+    /// 全員不聴なら支払いはない
+    fn nobody_is_ready() {
+        let round = make_round([
+            "13579m13579p135s",
+            "13579m13579p135s",
+            "13579m13579p135s",
+            "13579m13579p135s",
+        ]);
+        let settings = Settings::new();
+        let result = resolve(&round, &settings).unwrap();
+        assert_eq!(result.score_changes, vec![0, 0, 0, 0]);
+        assert!(!result.dealer_keeps);
+    }
+
+    #[test]
+    /// 役のない聴牌は設定によっては聴牌と認めない
+    fn ready_without_winning_hand() {
+        let hand = Hand::parse_lossy("123m456p78s 789m 111s");
+        let mut status = Status::new();
+        status.has_claimed_open = true;
+        let mut settings = Settings::new();
+        assert!(is_ready(&hand, &status, &settings).unwrap());
+        settings.ready_without_winning_hand = false;
+        assert!(!is_ready(&hand, &status, &settings).unwrap());
+        // 門前なら自摸の役がある
+        let hand = Hand::parse_lossy("123m456p78s789m111s");
+        assert!(is_ready(&hand, &Status::new(), &settings).unwrap());
+    }
+
+    #[test]
+    /// 自分で4枚使っている牌のみを待つ聴牌
+    fn ready_with_own_four_tiles() {
+        let hand = Hand::parse_lossy("1111m456p789s111z");
+        let status = Status::new();
+        let mut settings = Settings::new();
+        assert!(!is_ready(&hand, &status, &settings).unwrap());
+        settings.ready_with_own_four_tiles = true;
+        assert!(is_ready(&hand, &status, &settings).unwrap());
+    }
+
+    #[test]
+    /// 流し満貫は満貫の自摸和了と同じ支払いで、不聴罰符は支払われない
+    fn nagashi_mangan() {
+        let mut round = make_round([
+            "123m456p789s1122z",
+            "13579m13579p135s",
+            "13579m13579p135s",
+            "13579m13579p135s",
+        ]);
+        for t in ["1m", "9p", "1z", "7z"] {
+            round.players[2].river.push(Discard::new(Tile::from_name(t).unwrap()));
+        }
+        round.players[1].river.push(Discard::new(Tile::from_name("1m").unwrap()));
+        round.players[1].river.push(Discard {
+            tile: Tile::from_name("9s").unwrap(),
+            is_called: true,
+            is_ready: false,
+        });
+        round.players[3].river.push(Discard::new(Tile::from_name("5m").unwrap()));
+
+        let settings = Settings::new();
+        let result = resolve(&round, &settings).unwrap();
+        assert_eq!(result.nagashi_mangan, vec![2]);
+        assert_eq!(result.score_changes, vec![-4000, -2000, 8000, -2000]);
+        assert!(result.dealer_keeps);
+    }
+
+    #[test]
+    /// 三人麻雀の流局
+    fn three_players() {
+        let players = [
+            "123p456p789s1122z",
+            "11399p13579s135z",
+            "11m11p11s123456z",
+        ]
+        .iter()
+        .map(|h| Player::new(Hand::parse_lossy(h), 35000))
+        .collect();
+        let mut round = Round::new(Wind::East, 1, 0, 0, players);
+        let settings = Settings::new_three_players();
+        let result = round.exhaustive_draw(&settings).unwrap();
+        assert_eq!(result.ready, vec![true, false, false]);
+        assert_eq!(result.score_changes, vec![3000, -1500, -1500]);
+        assert!(!result.dealer_keeps);
+        assert_eq!(round.player_wind(0), Wind::West);
+        assert_eq!(round.players[0].status.player_wind, Wind::West);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::board::round::{Player, Round};
+use crate::hand::Hand;
+use crate::settings::{GameLength, Settings};
+use crate::tile::Wind;
+
+/// 局の終わり方
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RoundEnd {
+    /// 和了（ダブロンの場合も含め、親が和了ったか否か）
+    Win { dealer_won: bool },
+    /// 流局（親が聴牌していたか否か）
+    Draw { dealer_ready: bool },
+}
+
+/// 終局時の順位と成績
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Placement {
+    /// 席
+    pub seat: usize,
+    /// 順位（1始まり）
+    pub rank: usize,
+    /// 最終的な持ち点
+    pub score: i32,
+    /// 返し点との差にウマとオカを加えた成績（点）
+    pub points: i32,
+}
+
+/// 対局
+///
+/// 席`0`を起家とし、局ごとに`new_round`で局を作り、終わった局を`end_round`に渡して進行させる。
+#[derive(Debug, Clone)]
+pub struct Game {
+    /// 各プレイヤーの持ち点（席順）
+    pub scores: Vec<i32>,
+    /// 場風
+    pub prevailing_wind: Wind,
+    /// 場の中で何局目か（0始まり。親の席と一致する）
+    pub round_number: usize,
+    /// 本場
+    pub honba: u32,
+    /// 供託されている立直棒の本数
+    pub deposits: u32,
+    /// 終局したか
+    pub is_finished: bool,
+}
+
+impl Game {
+    pub fn new(settings: &Settings) -> Game {
+        Game {
+            scores: vec![settings.initial_score; settings.game_mode.players()],
+            prevailing_wind: Wind::East,
+            round_number: 0,
+            honba: 0,
+            deposits: 0,
+            is_finished: false,
+        }
+    }
+
+    /// 親の席を返す
+    pub fn dealer(&self) -> usize {
+        self.round_number
+    }
+
+    /// 配牌から現在の局を作る
+    pub fn new_round(&self, hands: Vec<Hand>) -> Round {
+        let players = hands
+            .into_iter()
+            .zip(&self.scores)
+            .map(|(hand, score)| Player::new(hand, *score))
+            .collect();
+        Round::new(
+            self.prevailing_wind,
+            self.dealer(),
+            self.honba,
+            self.deposits,
+            players,
+        )
+    }
+
+    /// 終わった局の結果を反映して次の局に進める
+    ///
+    /// 終局した場合は`true`を返す。
+    pub fn end_round(&mut self, round: &Round, end: RoundEnd, settings: &Settings) -> bool {
+        self.scores = round.players.iter().map(|p| p.score).collect();
+        self.deposits = round.deposits;
+
+        // 飛び
+        if settings.busting && self.scores.iter().any(|&s| s < 0) {
+            return self.finish();
+        }
+
+        let dealer_keeps = match end {
+            RoundEnd::Win { dealer_won } => dealer_won,
+            RoundEnd::Draw { dealer_ready } => dealer_ready,
+        };
+        let is_extra_round = self.prevailing_wind == extra_wind(settings);
+        let someone_reached = self.scores.iter().any(|&s| s >= settings.returning_score);
+
+        // 延長戦は返し点に誰かが達した時点で終了
+        if is_extra_round && someone_reached {
+            return self.finish();
+        }
+
+        // オーラスの和了止め・聴牌止め
+        if self.is_last_round(settings)
+            && dealer_keeps
+            && settings.dealer_stop_at_last_round
+            && self.ranking()[0] == self.dealer()
+            && self.scores[self.dealer()] >= settings.returning_score
+        {
+            return self.finish();
+        }
+
+        // 連荘もしくは流局なら積み棒を積む
+        if dealer_keeps || matches!(end, RoundEnd::Draw { .. }) {
+            self.honba += 1;
+        } else {
+            self.honba = 0;
+        }
+        if dealer_keeps {
+            return false;
+        }
+
+        // 親を回す
+        let was_last_round = self.is_last_round(settings);
+        self.round_number += 1;
+        if self.round_number == self.scores.len() {
+            self.round_number = 0;
+            self.prevailing_wind = next_wind(self.prevailing_wind);
+        }
+
+        if is_extra_round && self.round_number == 0 {
+            return self.finish();
+        }
+        if was_last_round && (someone_reached || !settings.extra_round) {
+            return self.finish();
+        }
+        false
+    }
+
+    /// 現在の局がオーラスか否か
+    pub fn is_last_round(&self, settings: &Settings) -> bool {
+        self.prevailing_wind == last_wind(settings) && self.round_number == self.scores.len() - 1
+    }
+
+    /// 持ち点の高い順に席を並べる（同点の場合は起家に近い方が上位）
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut seats: Vec<usize> = (0..self.scores.len()).collect();
+        seats.sort_by(|&a, &b| self.scores[b].cmp(&self.scores[a]).then(a.cmp(&b)));
+        seats
+    }
+
+    /// ウマとオカを含めた最終順位を席順で返す
+    ///
+    /// 残っている供託は1位のものとする。`settings.uma`が人数より短い場合、足りない順位のウマは`0`とする。
+    pub fn placements(&self, settings: &Settings) -> Vec<Placement> {
+        let len = self.scores.len();
+        let oka = (settings.returning_score - settings.initial_score) * len as i32;
+        let ranking = self.ranking();
+        let mut result: Vec<Placement> = Vec::with_capacity(len);
+        for seat in 0..len {
+            let rank = ranking.iter().position(|&s| s == seat).unwrap();
+            let mut score = self.scores[seat];
+            if rank == 0 {
+                score += self.deposits as i32 * 1000;
+            }
+            let mut points = score - settings.returning_score + settings.uma.get(rank).copied().unwrap_or(0);
+            if rank == 0 {
+                points += oka;
+            }
+            result.push(Placement {
+                seat,
+                rank: rank + 1,
+                score,
+                points,
+            });
+        }
+        result
+    }
+
+    fn finish(&mut self) -> bool {
+        self.is_finished = true;
+        true
+    }
+}
+
+/// 次の場風を返す
+fn next_wind(wind: Wind) -> Wind {
+    match wind {
+        Wind::East => Wind::South,
+        Wind::South => Wind::West,
+        Wind::West => Wind::North,
+        Wind::North => Wind::East,
+    }
+}
+
+/// 延長戦を除いた最後の場風を返す
+fn last_wind(settings: &Settings) -> Wind {
+    match settings.game_length {
+        GameLength::EastOnly => Wind::East,
+        GameLength::EastSouth => Wind::South,
+    }
+}
+
+/// 延長戦の場風を返す
+fn extra_wind(settings: &Settings) -> Wind {
+    next_wind(last_wind(settings))
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::GameMode;
+
+    /// 点数の移動だけを反映した局を作る
+    fn play(game: &mut Game, changes: [i32; 4], end: RoundEnd, settings: &Settings) -> bool {
+        let mut round = game.new_round(vec![Hand::new(Vec::new(), None); 4]);
+        round.apply_score_changes(&changes);
+        game.end_round(&round, end, settings)
+    }
+
+    #[test]
+    /// 子の和了で親が流れ、親の連荘と流局で本場が積まれる
+    fn rotate_dealer_and_honba() {
+        let mut settings = Settings::new();
+        settings.game_length = GameLength::EastOnly;
+        let mut game = Game::new(&settings);
+
+        let child_win = RoundEnd::Win { dealer_won: false };
+        let dealer_win = RoundEnd::Win { dealer_won: true };
+
+        assert!(!play(&mut game, [6000, -2000, -2000, -2000], dealer_win, &settings));
+        assert_eq!((game.dealer(), game.honba), (0, 1));
+        assert!(!play(&mut game, [0, 0, 0, 0], RoundEnd::Draw { dealer_ready: false }, &settings));
+        assert_eq!((game.dealer(), game.honba), (1, 2));
+        assert!(!play(&mut game, [0, 0, 2000, -2000], child_win, &settings));
+        assert_eq!((game.dealer(), game.honba), (2, 0));
+        assert!(!play(&mut game, [0, 0, 0, 0], child_win, &settings));
+        assert_eq!(game.dealer(), 3);
+        assert!(game.is_last_round(&settings));
+        // 東4局終了時に返し点に達している人がいるので終局
+        assert!(play(&mut game, [0, 0, 0, 0], child_win, &settings));
+        assert!(game.is_finished);
+        assert_eq!(game.scores, vec![31000, 23000, 25000, 21000]);
+    }
+
+    #[test]
+    /// 東南戦は南4局まで続く
+    fn east_south_game() {
+        let settings = Settings::new();
+        let mut game = Game::new(&settings);
+        let child_win = RoundEnd::Win { dealer_won: false };
+        for _ in 0..4 {
+            assert!(!play(&mut game, [0, 0, 0, 0], child_win, &settings));
+        }
+        assert_eq!((game.prevailing_wind, game.dealer()), (Wind::South, 0));
+        for _ in 0..3 {
+            assert!(!play(&mut game, [0, 0, 0, 0], child_win, &settings));
+        }
+        assert!(play(&mut game, [5000, -5000, 0, 0], child_win, &settings));
+    }
+
+    #[test]
+    /// 誰かの持ち点が0点未満になったら終局
+    fn busting() {
+        let settings = Settings::new();
+        let mut game = Game::new(&settings);
+        let end = RoundEnd::Win { dealer_won: false };
+        assert!(play(&mut game, [0, 26000, -26000, 0], end, &settings));
+        assert!(game.is_finished);
+    }
+
+    #[test]
+    /// オーラスで1位の親が和了れば終局
+    fn dealer_stops_at_last_round() {
+        let mut settings = Settings::new();
+        settings.game_length = GameLength::EastOnly;
+        let mut game = Game::new(&settings);
+        game.round_number = 3;
+        let end = RoundEnd::Win { dealer_won: true };
+        assert!(play(&mut game, [-2000, -2000, -2000, 6000], end, &settings));
+
+        settings.dealer_stop_at_last_round = false;
+        let mut game = Game::new(&settings);
+        game.round_number = 3;
+        assert!(!play(&mut game, [-2000, -2000, -2000, 6000], end, &settings));
+        assert_eq!((game.dealer(), game.honba), (3, 1));
+    }
+
+    #[test]
+    /// 返し点に誰も達していなければ西入し、誰かが達した時点で終局
+    fn extra_round() {
+        let settings = Settings::new();
+        let mut game = Game::new(&settings);
+        game.prevailing_wind = Wind::South;
+        game.round_number = 3;
+        let end = RoundEnd::Win { dealer_won: false };
+        assert!(!play(&mut game, [2000, -2000, 0, 0], end, &settings));
+        assert_eq!((game.prevailing_wind, game.dealer()), (Wind::West, 0));
+        assert!(!play(&mut game, [0, 2000, -2000, 0], end, &settings));
+        assert!(play(&mut game, [3000, 0, 0, -3000], end, &settings));
+        assert_eq!(game.scores, vec![30000, 25000, 23000, 22000]);
+    }
+
+    #[test]
+    /// 三人麻雀は3局で場風が変わる
+    fn three_players() {
+        let settings = Settings::new_three_players();
+        let mut game = Game::new(&settings);
+        assert_eq!(game.scores, vec![35000; 3]);
+        let end = RoundEnd::Win { dealer_won: false };
+        for _ in 0..3 {
+            let round = game.new_round(vec![Hand::new(Vec::new(), None); 3]);
+            assert!(!game.end_round(&round, end, &settings));
+        }
+        assert_eq!((game.prevailing_wind, game.dealer()), (Wind::South, 0));
+
+        game.scores = vec![50000, 30000, 25000];
+        let result = game.placements(&settings);
+        assert_eq!(
+            result.iter().map(|p| p.points).collect::<Vec<i32>>(),
+            vec![45000, -10000, -35000]
+        );
+    }
+
+    #[test]
+    /// ウマ・オカと供託を含めた最終順位（同点は起家に近い方が上位）
+    fn placements() {
+        let settings = Settings::new();
+        let mut game = Game::new(&settings);
+        game.scores = vec![20000, 38000, 20000, 21000];
+        game.deposits = 1;
+        let result = game.placements(&settings);
+        assert_eq!(
+            result,
+            vec![
+                Placement { seat: 0, rank: 3, score: 20000, points: -20000 },
+                Placement { seat: 1, rank: 1, score: 39000, points: 49000 },
+                Placement { seat: 2, rank: 4, score: 20000, points: -30000 },
+                Placement { seat: 3, rank: 2, score: 21000, points: 1000 },
+            ]
+        );
+    }
+
+    #[test]
+    /// ウマの数が人数と合わなくてもパニックしない
+    fn placements_with_mismatched_uma() {
+        // 四人麻雀のウマのまま三人麻雀にした
+        let settings = Settings {
+            game_mode: GameMode::ThreePlayers,
+            ..Settings::new()
+        };
+        let mut game = Game::new(&settings);
+        game.scores = vec![35000, 25000, 15000];
+        let points: Vec<i32> = game.placements(&settings).iter().map(|p| p.points).collect();
+        assert_eq!(points, vec![40000, 5000, -25000]);
+
+        // 三人麻雀のウマのまま四人麻雀にした
+        let settings = Settings {
+            game_mode: GameMode::FourPlayers,
+            ..Settings::new_three_players()
+        };
+        let mut game = Game::new(&settings);
+        game.scores = vec![50000, 40000, 30000, 20000];
+        let points: Vec<i32> = game.placements(&settings).iter().map(|p| p.points).collect();
+        assert_eq!(points, vec![50000, 0, -30000, -20000]);
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::settings::ThreePlayerTsumo;
+
+/// 翻数と符から基本点を求める（5翻以上および満貫を超える場合は満貫以上の基本点になる）
+pub fn base_points(han: u32, fu: u32) -> i32 {
+    match han {
+        0 => 0,
+        1..=4 => (fu as i32 * 2_i32.pow(han + 2)).min(2000),
+        5 => 2000,
+        6 | 7 => 3000,
+        8..=10 => 4000,
+        11 | 12 => 6000,
+        // 数え役満
+        _ => 8000,
+    }
+}
+
+/// 役満の基本点（ダブル役満などは倍数を指定する）
+pub fn yakuman_base_points(multiple: u32) -> i32 {
+    8000 * multiple as i32
+}
+
+/// 100点未満を切り上げる
+pub fn round_up(points: i32) -> i32 {
+    (points + 99) / 100 * 100
+}
+
+/// ロン和了の放銃者の支払いを求める
+pub fn ron_payment(base: i32, is_dealer: bool) -> i32 {
+    round_up(base * if is_dealer { 6 } else { 4 })
+}
+
+/// 自摸和了の各プレイヤーの支払いを席順で求める（和了者は`0`）
+///
+/// 三人麻雀では`tsumo`に従って、いない北家の分を支払わない（ツモ損）か残りの2人で折半する。
+pub fn tsumo_payments(
+    base: i32,
+    winner: usize,
+    dealer: usize,
+    players: usize,
+    tsumo: ThreePlayerTsumo,
+) -> Vec<i32> {
+    let mut result = vec![0; players];
+    for payer in 0..players {
+        if payer == winner {
+            continue;
+        }
+        let mut payment = if winner == dealer || payer == dealer {
+            base * 2
+        } else {
+            base
+        };
+        // 北家の分（子の支払い）を折半する
+        if players == 3 && tsumo == ThreePlayerTsumo::Split {
+            payment += if winner == dealer { base } else { base / 2 };
+        }
+        result[payer] = round_up(payment);
+    }
+    result
+}
+
+/// 和了の点数の支払い方（本場は含まない）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Payment {
+    /// ロン和了の放銃者の支払い
+    Ron(i32),
+    /// 子の自摸和了の子・親それぞれの支払い
+    Tsumo { child: i32, dealer: i32 },
+    /// 親の自摸和了の子それぞれの支払い
+    DealerTsumo(i32),
+}
+
+impl Payment {
+    /// 基本点から支払いを求める（`discarder`が`None`なら自摸和了）
+    pub fn new(
+        base: i32,
+        winner: usize,
+        dealer: usize,
+        discarder: Option<usize>,
+        players: usize,
+        tsumo: ThreePlayerTsumo,
+    ) -> Payment {
+        if discarder.is_some() {
+            return Payment::Ron(ron_payment(base, winner == dealer));
+        }
+        let payments = tsumo_payments(base, winner, dealer, players, tsumo);
+        let child = (0..players)
+            .filter(|&p| p != winner && p != dealer)
+            .map(|p| payments[p])
+            .next()
+            .unwrap_or(0);
+        if winner == dealer {
+            return Payment::DealerTsumo(child);
+        }
+        return Payment::Tsumo {
+            child,
+            dealer: payments[dealer],
+        };
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 基本点
+    fn base_points_test() {
+        assert_eq!(base_points(1, 30), 240);
+        assert_eq!(base_points(3, 40), 1280);
+        // 4翻40符は満貫
+        assert_eq!(base_points(4, 40), 2000);
+        assert_eq!(base_points(6, 30), 3000);
+        assert_eq!(base_points(13, 30), 8000);
+        assert_eq!(yakuman_base_points(2), 16000);
+    }
+
+    #[test]
+    /// ロン和了
+    fn ron() {
+        assert_eq!(ron_payment(base_points(1, 30), false), 1000);
+        assert_eq!(ron_payment(base_points(1, 30), true), 1500);
+        assert_eq!(ron_payment(base_points(4, 30), false), 7700);
+    }
+
+    #[test]
+    /// 四人麻雀の自摸和了
+    fn tsumo_four_players() {
+        let base = base_points(1, 30);
+        assert_eq!(tsumo_payments(base, 1, 0, 4, ThreePlayerTsumo::Loss), vec![500, 0, 300, 300]);
+        assert_eq!(tsumo_payments(base, 0, 0, 4, ThreePlayerTsumo::Loss), vec![0, 500, 500, 500]);
+    }
+
+    #[test]
+    /// 三人麻雀の自摸和了（ツモ損と北家折半）
+    fn tsumo_three_players() {
+        let base = base_points(5, 30);
+        assert_eq!(tsumo_payments(base, 1, 0, 3, ThreePlayerTsumo::Loss), vec![4000, 0, 2000]);
+        assert_eq!(tsumo_payments(base, 1, 0, 3, ThreePlayerTsumo::Split), vec![5000, 0, 3000]);
+        assert_eq!(tsumo_payments(base, 0, 0, 3, ThreePlayerTsumo::Loss), vec![0, 4000, 4000]);
+        assert_eq!(tsumo_payments(base, 0, 0, 3, ThreePlayerTsumo::Split), vec![0, 6000, 6000]);
+    }
+
+    #[test]
+    /// 和了の支払い方
+    fn payment() {
+        let base = base_points(4, 20);
+        assert_eq!(Payment::new(base, 1, 0, Some(2), 4, ThreePlayerTsumo::Loss), Payment::Ron(5200));
+        assert_eq!(
+            Payment::new(base, 1, 0, None, 4, ThreePlayerTsumo::Loss),
+            Payment::Tsumo {
+                child: 1300,
+                dealer: 2600
+            }
+        );
+        assert_eq!(Payment::new(base, 0, 0, None, 4, ThreePlayerTsumo::Loss), Payment::DealerTsumo(2600));
+        assert_eq!(
+            Payment::new(base_points(5, 30), 1, 0, None, 3, ThreePlayerTsumo::Split),
+            Payment::Tsumo {
+                child: 3000,
+                dealer: 5000
+            }
+        );
+    }
+}
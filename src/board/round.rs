@@ -0,0 +1,156 @@
+use crate::board::exhaustive_draw::{self, ExhaustiveDraw};
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::hand_info::status::Status;
+use crate::settings::Settings;
+use crate::tile::{Tile, Wind};
+
+/// 捨て牌
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Discard {
+    /// 捨てた牌
+    pub tile: Tile,
+    /// 他家に鳴かれたか
+    pub is_called: bool,
+    /// 立直宣言牌か
+    pub is_ready: bool,
+}
+
+impl Discard {
+    pub fn new(tile: Tile) -> Discard {
+        Discard {
+            tile,
+            is_called: false,
+            is_ready: false,
+        }
+    }
+}
+
+/// 局の中での各プレイヤーの状態
+#[derive(Debug, Clone)]
+pub struct Player {
+    /// 手牌
+    pub hand: Hand,
+    /// 河（捨て牌を捨てた順に並べたもの）
+    pub river: Vec<Discard>,
+    /// 手牌の（牌以外の）状態
+    pub status: Status,
+    /// 持ち点
+    pub score: i32,
+    /// 抜きドラ（三人麻雀で抜いた北）の枚数
+    pub north_bonus: u32,
+}
+
+impl Player {
+    pub fn new(hand: Hand, score: i32) -> Player {
+        Player {
+            hand,
+            river: Vec::new(),
+            status: Status::new(),
+            score,
+            north_bonus: 0,
+        }
+    }
+}
+
+/// 局
+#[derive(Debug, Clone)]
+pub struct Round {
+    /// 場風
+    pub prevailing_wind: Wind,
+    /// 親の席（`players`のインデックス）
+    pub dealer: usize,
+    /// 本場
+    pub honba: u32,
+    /// 供託されている立直棒の本数
+    pub deposits: u32,
+    /// 各プレイヤーの状態（席順）
+    pub players: Vec<Player>,
+}
+
+impl Round {
+    /// 局を作る
+    ///
+    /// 各プレイヤーの`Status`の自風・場風は席と親の位置から設定され、第一巡の状態になる。
+    pub fn new(
+        prevailing_wind: Wind,
+        dealer: usize,
+        honba: u32,
+        deposits: u32,
+        players: Vec<Player>,
+    ) -> Round {
+        let mut round = Round {
+            prevailing_wind,
+            dealer,
+            honba,
+            deposits,
+            players,
+        };
+        for seat in 0..round.players.len() {
+            let player_wind = round.player_wind(seat);
+            round.players[seat].status.player_wind = player_wind;
+            round.players[seat].status.prevailing_wind = prevailing_wind;
+            round.players[seat].status.is_first_turn = true;
+        }
+        round
+    }
+
+    /// 席から自風を返す
+    pub fn player_wind(&self, seat: usize) -> Wind {
+        Wind::from_seat(seat, self.dealer, self.players.len())
+    }
+
+    /// 三人麻雀で北を抜きドラとして抜き、嶺上牌を補充する
+    pub fn extract_north(&mut self, seat: usize, replacement: Tile) -> Result<()> {
+        if self.players.len() != 3 {
+            return Err(Error::NotThreePlayerGame);
+        }
+        let player = &mut self.players[seat];
+        if player.hand.discard(Tile::Z4).is_none() {
+            return Err(Error::NoNorthTile(seat));
+        }
+        player.north_bonus += 1;
+        player.hand.set_drawn(Some(replacement));
+        Ok(())
+    }
+
+    /// 点数の移動を各プレイヤーの持ち点に反映する
+    pub fn apply_score_changes(&mut self, score_changes: &[i32]) {
+        for (player, change) in self.players.iter_mut().zip(score_changes) {
+            player.score += change;
+        }
+    }
+
+    /// 荒牌平局（山が尽きた時の流局）を処理し、点数の移動を反映する
+    pub fn exhaustive_draw(&mut self, settings: &Settings) -> Result<ExhaustiveDraw> {
+        let result = exhaustive_draw::resolve(self, settings)?;
+        self.apply_score_changes(&result.score_changes);
+        Ok(result)
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 三人麻雀で北を抜く
+    fn extract_north() {
+        let players = ["19m19p19s1234567z 4z", "19m19p19s1234567z", "19m19p19s1234567z"]
+            .iter()
+            .map(|h| Player::new(Hand::parse_lossy(h), 35000))
+            .collect();
+        let mut round = Round::new(Wind::East, 0, 0, 0, players);
+        let replacement = Tile::new(Tile::P5);
+        round.extract_north(0, replacement).unwrap();
+        round.extract_north(0, replacement).unwrap();
+        assert_eq!(
+            round.extract_north(0, replacement),
+            Err(Error::NoNorthTile(0))
+        );
+        assert_eq!(round.players[0].north_bonus, 2);
+        assert_eq!(round.players[0].hand.to_string(), "1m9m1p5p9p1s9s1z2z3z5z6z7z 5p");
+        assert_eq!(round.player_wind(2), Wind::West);
+    }
+}
@@ -0,0 +1,182 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::error::{Error, Result};
+use crate::tile::*;
+
+/// 王牌の枚数
+pub const DEAD_WALL_LEN: usize = 14;
+/// 嶺上牌の枚数
+const REPLACEMENT_LEN: usize = 4;
+/// ドラ表示牌の最大枚数
+const MAX_DORA_INDICATORS: usize = 5;
+
+/// 山
+///
+/// 王牌は常に14枚に保たれ、嶺上牌を引くたびに自摸山の最後の1枚が王牌に移る。
+#[derive(Debug, Clone)]
+pub struct Wall {
+    /// 自摸山（先頭から順に引く）
+    live: Vec<Tile>,
+    /// 次に引く自摸山の位置
+    next: usize,
+    /// 嶺上牌
+    replacements: Vec<Tile>,
+    /// ドラ表示牌
+    dora_indicators: Vec<Tile>,
+    /// 裏ドラ表示牌
+    ura_dora_indicators: Vec<Tile>,
+    /// めくられたドラ表示牌の枚数
+    dora_count: usize,
+}
+
+impl Wall {
+    /// 人数に応じた牌をシャッフルして山を作る
+    pub fn new<R: Rng + ?Sized>(players: usize, rng: &mut R) -> Wall {
+        let mut tiles = Wall::tile_set(players);
+        tiles.shuffle(rng);
+        // 牌の枚数は常に王牌より多いので失敗しない
+        Wall::from_tiles(tiles).unwrap()
+    }
+
+    /// 並び順を指定して山を作る
+    ///
+    /// 先頭の14枚が王牌（嶺上牌4枚・ドラ表示牌5枚・裏ドラ表示牌5枚の順）、残りが自摸山になる。
+    pub fn from_tiles(mut tiles: Vec<Tile>) -> Result<Wall> {
+        if tiles.len() < DEAD_WALL_LEN {
+            return Err(Error::TooFewTilesForWall(tiles.len()));
+        }
+        let live = tiles.split_off(DEAD_WALL_LEN);
+        let ura_dora_indicators = tiles.split_off(REPLACEMENT_LEN + MAX_DORA_INDICATORS);
+        let dora_indicators = tiles.split_off(REPLACEMENT_LEN);
+        Ok(Wall {
+            live,
+            next: 0,
+            replacements: tiles,
+            dora_indicators,
+            ura_dora_indicators,
+            dora_count: 1,
+        })
+    }
+
+    /// 人数に応じた牌の一式を返す（三人麻雀では二萬〜八萬を抜く）
+    pub fn tile_set(players: usize) -> Vec<Tile> {
+        let mut result: Vec<Tile> = Vec::new();
+        for i in Tile::M1..=Tile::Z7 {
+            if players == 3 && (Tile::M2..=Tile::M8).contains(&i) {
+                continue;
+            }
+            for _ in 0..4 {
+                result.push(Tile::new(i));
+            }
+        }
+        result
+    }
+
+    /// 自摸山から1枚引く
+    pub fn draw(&mut self) -> Option<Tile> {
+        let tile = self.live.get(self.next).copied()?;
+        self.next += 1;
+        Some(tile)
+    }
+
+    /// 嶺上牌を1枚引き、自摸山の最後の1枚を王牌に補充する
+    pub fn draw_replacement(&mut self) -> Option<Tile> {
+        if self.remaining() == 0 || self.replacements.is_empty() {
+            return None;
+        }
+        let tile = self.replacements.remove(0);
+        if let Some(t) = self.live.pop() {
+            self.replacements.push(t);
+        }
+        Some(tile)
+    }
+
+    /// 新しいドラ表示牌をめくる
+    pub fn reveal_dora(&mut self) {
+        self.dora_count = (self.dora_count + 1).min(MAX_DORA_INDICATORS);
+    }
+
+    /// めくられているドラ表示牌を返す
+    pub fn dora_indicators(&self) -> &[Tile] {
+        &self.dora_indicators[..self.dora_count]
+    }
+
+    /// めくられているドラ表示牌に対応する裏ドラ表示牌を返す
+    pub fn ura_dora_indicators(&self) -> &[Tile] {
+        &self.ura_dora_indicators[..self.dora_count]
+    }
+
+    /// 自摸山の残り枚数を返す
+    pub fn remaining(&self) -> usize {
+        self.live.len() - self.next
+    }
+}
+
+/// ドラ表示牌からドラを求める
+///
+/// 三人麻雀では一萬の次が九萬、九萬の次が一萬になる。
+pub fn dora_from_indicator(indicator: TileType, players: usize) -> TileType {
+    match indicator {
+        Tile::M1 if players == 3 => Tile::M9,
+        Tile::M9 => Tile::M1,
+        Tile::P9 => Tile::P1,
+        Tile::S9 => Tile::S1,
+        Tile::Z4 => Tile::Z1,
+        Tile::Z7 => Tile::Z5,
+        _ => indicator + 1,
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    /// 四人麻雀は136枚、三人麻雀は二萬〜八萬を抜いた108枚
+    fn tile_set() {
+        assert_eq!(Wall::tile_set(4).len(), 136);
+        let sanma = Wall::tile_set(3);
+        assert_eq!(sanma.len(), 108);
+        assert!(sanma.iter().all(|t| !(Tile::M2..=Tile::M8).contains(&t.get())));
+    }
+
+    #[test]
+    /// 同じシードからは同じ山ができる
+    fn same_seed_same_wall() {
+        let mut wall1 = Wall::new(4, &mut StdRng::seed_from_u64(1));
+        let mut wall2 = Wall::new(4, &mut StdRng::seed_from_u64(1));
+        assert_eq!(wall1.remaining(), 136 - DEAD_WALL_LEN);
+        while let Some(t) = wall1.draw() {
+            assert_eq!(Some(t), wall2.draw());
+        }
+        assert_eq!(wall2.draw(), None);
+    }
+
+    #[test]
+    /// 嶺上牌を引くと自摸山が1枚減る
+    fn draw_replacement() {
+        let tiles = Wall::tile_set(4);
+        let mut wall = Wall::from_tiles(tiles.clone()).unwrap();
+        assert_eq!(wall.draw_replacement(), Some(tiles[0]));
+        wall.reveal_dora();
+        assert_eq!(wall.remaining(), 136 - DEAD_WALL_LEN - 1);
+        assert_eq!(wall.dora_indicators(), &tiles[4..6]);
+        assert_eq!(wall.ura_dora_indicators(), &tiles[9..11]);
+    }
+
+    #[test]
+    /// ドラ表示牌の次の牌がドラ
+    fn dora() {
+        assert_eq!(dora_from_indicator(Tile::M1, 4), Tile::M2);
+        assert_eq!(dora_from_indicator(Tile::M1, 3), Tile::M9);
+        assert_eq!(dora_from_indicator(Tile::M9, 3), Tile::M1);
+        assert_eq!(dora_from_indicator(Tile::S9, 4), Tile::S1);
+        assert_eq!(dora_from_indicator(Tile::Z4, 4), Tile::Z1);
+        assert_eq!(dora_from_indicator(Tile::Z5, 4), Tile::Z6);
+        assert_eq!(dora_from_indicator(Tile::Z7, 4), Tile::Z5);
+    }
+}
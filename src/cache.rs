@@ -0,0 +1,345 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::error::Result;
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::{get_winning_tiles, HandAnalyzer};
+use crate::hand_info::opened::OpenTiles;
+use crate::hand_info::status::Status;
+use crate::settings::Settings;
+use crate::tile::*;
+use crate::winning_hand::score::{self, Score};
+
+/// 手牌の内容（牌の種類ごとの枚数と副露）
+///
+/// 牌の並び順や赤ドラは区別しない。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct HandKey {
+    summary: TileSummarize,
+    opened: Vec<OpenTiles>,
+}
+
+impl HandKey {
+    fn new(hand: &Hand) -> HandKey {
+        return HandKey {
+            summary: hand.summarize_tiles(),
+            opened: hand.opened().to_vec(),
+        };
+    }
+}
+
+/// 点数計算の条件（和了牌・状態・設定・ドラ）を含めた手牌の内容
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ScoreKey {
+    hand: HandKey,
+    drawn: Option<TileType>,
+    status: Status,
+    settings: Settings,
+    dora: Vec<TileType>,
+    extra_dora: u32,
+}
+
+/// 最も長く使われていないものから捨てる、大きさに上限のある表
+#[derive(Debug)]
+struct Lru<K, V> {
+    capacity: usize,
+    /// 値と最後に使った時刻
+    entries: HashMap<K, (V, u64)>,
+    /// 最後に使った時刻から鍵を引く（古い順）
+    order: BTreeMap<u64, K>,
+    clock: u64,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Lru<K, V> {
+        return Lru {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        };
+    }
+
+    /// 値を取り出し、最近使ったものとする
+    fn get(&mut self, key: &K) -> Option<V> {
+        self.clock += 1;
+        let (value, used) = self.entries.get_mut(key)?;
+        let key = self.order.remove(used).unwrap();
+        *used = self.clock;
+        self.order.insert(self.clock, key);
+        return Some(value.clone());
+    }
+
+    /// 値を入れる（上限に達していれば最も長く使われていないものを捨てる）
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.clock += 1;
+        if let Some((_, used)) = self.entries.remove(&key) {
+            self.order.remove(&used);
+        } else if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.clock, key.clone());
+        self.entries.insert(key, (value, self.clock));
+    }
+
+    fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// キャッシュの利用状況
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// キャッシュから返した回数
+    pub hits: u64,
+    /// 計算し直した回数
+    pub misses: u64,
+    /// 今キャッシュに入っている結果の数
+    pub len: usize,
+}
+
+impl CacheStats {
+    /// キャッシュから返した割合（まだ使われていなければ`0.0`）
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        return self.hits as f64 / total as f64;
+    }
+}
+
+/// 手牌の解析・和了牌・点数計算の結果を覚えておくキャッシュ
+///
+/// 牌の種類ごとの枚数と副露が同じ手牌は同じ結果になるので、探索などで同じ手牌を何度も
+/// 解析する時に使う。解析・和了牌・点数計算はそれぞれ`capacity`個まで覚え、あふれたら
+/// 最も長く使われていないものから捨てる。エラーになった結果は覚えない。
+///
+/// `&self`で使えるので、`Arc`などで複数のスレッドから共有できる。
+/// 同じ手牌を複数のスレッドが同時に解析すると、それぞれが計算することがある。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::cache::AnalysisCache;
+/// use riichi_mahjong_rs::hand::Hand;
+/// use riichi_mahjong_rs::tile::Tile;
+///
+/// let cache = AnalysisCache::new(1024);
+/// let hand = Hand::parse_lossy("123m456p789s2388p");
+/// assert_eq!(cache.analyze(&hand).unwrap().shanten, 0);
+/// // 並び順が違っても同じ手牌とみなす
+/// assert_eq!(cache.analyze(&Hand::parse_lossy("8832p123m456p789s")).unwrap().shanten, 0);
+/// assert_eq!(cache.winning_tiles(&hand).unwrap(), vec![Tile::P1, Tile::P4, Tile::P7]);
+///
+/// let stats = cache.stats();
+/// assert_eq!((stats.hits, stats.misses), (1, 2));
+/// ```
+#[derive(Debug)]
+pub struct AnalysisCache {
+    analysis: Mutex<Lru<HandKey, HandAnalyzer>>,
+    waits: Mutex<Lru<HandKey, Vec<TileType>>>,
+    scores: Mutex<Lru<ScoreKey, Option<Score>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl AnalysisCache {
+    /// 解析・和了牌・点数計算の結果をそれぞれ`capacity`個まで覚えるキャッシュを作る
+    pub fn new(capacity: usize) -> AnalysisCache {
+        return AnalysisCache {
+            analysis: Mutex::new(Lru::new(capacity)),
+            waits: Mutex::new(Lru::new(capacity)),
+            scores: Mutex::new(Lru::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+    }
+
+    /// `HandAnalyzer::new`の結果を返す
+    pub fn analyze(&self, hand: &Hand) -> Result<HandAnalyzer> {
+        return self.get_or_insert(&self.analysis, HandKey::new(hand), || {
+            HandAnalyzer::new(hand)
+        });
+    }
+
+    /// `get_winning_tiles`の結果を返す（ツモった牌は無視する）
+    pub fn winning_tiles(&self, hand: &Hand) -> Result<Vec<TileType>> {
+        let mut closed = hand.clone();
+        closed.set_drawn(None);
+        return self.get_or_insert(&self.waits, HandKey::new(&closed), || {
+            get_winning_tiles(&closed)
+        });
+    }
+
+    /// `score::calculate`の結果を返す
+    pub fn calculate(
+        &self,
+        hand: &Hand,
+        status: &Status,
+        settings: &Settings,
+        dora: &[TileType],
+        extra_dora: u32,
+    ) -> Result<Option<Score>> {
+        let key = ScoreKey {
+            hand: HandKey::new(hand),
+            drawn: hand.drawn().map(|t| t.get()),
+            status: status.clone(),
+            settings: settings.clone(),
+            dora: dora.to_vec(),
+            extra_dora,
+        };
+        return self.get_or_insert(&self.scores, key, || {
+            score::calculate(hand, status, settings, dora, extra_dora)
+        });
+    }
+
+    /// これまでの利用状況
+    pub fn stats(&self) -> CacheStats {
+        return CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            len: self.analysis.lock().unwrap().len()
+                + self.waits.lock().unwrap().len()
+                + self.scores.lock().unwrap().len(),
+        };
+    }
+
+    /// 覚えている結果と利用状況を消す
+    pub fn clear(&self) {
+        self.analysis.lock().unwrap().clear();
+        self.waits.lock().unwrap().clear();
+        self.scores.lock().unwrap().clear();
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// `lru`にあればそれを、なければ`f`で計算して覚えてから返す
+    ///
+    /// 計算している間は`lru`をロックしない。
+    fn get_or_insert<K, V, F>(&self, lru: &Mutex<Lru<K, V>>, key: K, f: F) -> Result<V>
+    where
+        K: Clone + Eq + Hash,
+        V: Clone,
+        F: FnOnce() -> Result<V>,
+    {
+        if let Some(value) = lru.lock().unwrap().get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = f()?;
+        lru.lock().unwrap().insert(key, value.clone());
+        return Ok(value);
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::RandomHands;
+    use crate::tile::Wind;
+    use std::thread;
+
+    #[test]
+    /// 最も長く使われていないものから捨てる
+    fn lru_eviction() {
+        let mut lru: Lru<u32, u32> = Lru::new(2);
+        lru.insert(1, 10);
+        lru.insert(2, 20);
+        assert_eq!(lru.get(&1), Some(10));
+        lru.insert(3, 30);
+        assert_eq!(lru.get(&2), None);
+        assert_eq!(lru.get(&1), Some(10));
+        assert_eq!(lru.get(&3), Some(30));
+        lru.insert(1, 11);
+        lru.insert(4, 40);
+        assert_eq!(lru.len(), 2);
+        assert_eq!(lru.get(&3), None);
+        assert_eq!(lru.get(&1), Some(11));
+
+        let mut empty: Lru<u32, u32> = Lru::new(0);
+        empty.insert(1, 10);
+        assert_eq!(empty.get(&1), None);
+    }
+
+    #[test]
+    /// 副露・和了牌・状態が違えば別の手牌として計算する
+    fn keys() {
+        let cache = AnalysisCache::new(16);
+        let closed = Hand::parse_lossy("111m234p567s3355z 5z");
+        let opened = Hand::parse_lossy("234p567s3355z ^111m 5z");
+        assert_eq!(closed.summarize_tiles(), opened.summarize_tiles());
+
+        let status = Status {
+            is_self_picked: true,
+            ..Status::new()
+        };
+        let settings = Settings::new();
+        let a = cache.calculate(&closed, &status, &settings, &[], 0).unwrap();
+        let b = cache.calculate(&opened, &status, &settings, &[], 0).unwrap();
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(a, score::calculate(&closed, &status, &settings, &[], 0).unwrap());
+        assert_eq!(b, score::calculate(&opened, &status, &settings, &[], 0).unwrap());
+
+        let ron = Status {
+            player_wind: Wind::South,
+            ..Status::new()
+        };
+        let c = cache.calculate(&closed, &ron, &settings, &[], 0).unwrap();
+        assert_ne!(a, c);
+        assert_eq!(cache.stats().misses, 3);
+        assert_eq!(cache.calculate(&closed, &status, &settings, &[], 0).unwrap(), a);
+        assert_eq!(cache.stats().hits, 1);
+
+        // 和了牌だけ違う手牌
+        let other = Hand::parse_lossy("111m234p567s3555z 3z");
+        cache.calculate(&other, &status, &settings, &[], 0).unwrap();
+        assert_eq!(cache.stats().misses, 4);
+
+        cache.clear();
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[test]
+    /// 複数のスレッドで共有しても、キャッシュしない場合と同じ結果になる
+    fn shared_across_threads() {
+        let mut random = RandomHands::new(45);
+        let hands: Vec<Hand> = (0..50).map(|_| random.hand(true)).collect();
+        let cache = AnalysisCache::new(64);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for hand in &hands {
+                        let expected = HandAnalyzer::new(hand).unwrap();
+                        let analyzer = cache.analyze(hand).unwrap();
+                        assert_eq!(analyzer.shanten, expected.shanten);
+                        assert_eq!(analyzer.form, expected.form);
+                        assert_eq!(
+                            cache.winning_tiles(hand).unwrap(),
+                            get_winning_tiles(hand).unwrap()
+                        );
+                    }
+                });
+            }
+        });
+        let stats = cache.stats();
+        assert_eq!(stats.hits + stats.misses, 4 * 50 * 2);
+        assert!(stats.misses >= 50 * 2);
+        assert!(stats.hit_rate() > 0.0);
+        assert_eq!(stats.len, 100);
+    }
+}
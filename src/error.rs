@@ -0,0 +1,317 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::num::ParseIntError;
+
+#[cfg(feature = "std")]
+use crate::board::agent::{CallAction, TurnAction};
+#[cfg(feature = "std")]
+use crate::board::event::Event;
+use crate::hand::ParseHandError;
+use crate::tile::{Tile, TileType};
+
+/// このクレートの処理で起きるエラー
+///
+/// `Display`は原因を英語で表す。
+/// `std`がない時は、牌・手牌・手牌の情報・和了役のエラーだけを使う。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// 牌の番号が範囲外
+    InvalidTile(TileType),
+    /// 手牌の解析中に範囲外の牌の番号が見つかった
+    UnknownTileIndex(TileType),
+    /// 同じ牌でない（`block`は`Same2`などの名前）
+    NotSameTiles {
+        block: &'static str,
+        tiles: Vec<TileType>,
+    },
+    /// 連続した牌でない
+    NotSequentialTiles {
+        block: &'static str,
+        tiles: Vec<TileType>,
+    },
+    /// 順子・塔子に字牌がある
+    HonorInSequence {
+        block: &'static str,
+        tiles: Vec<TileType>,
+    },
+    /// 順子・塔子の牌の種類が異なる
+    MixedSuits {
+        block: &'static str,
+        tiles: Vec<TileType>,
+    },
+    /// 同じ牌が5枚以上になる
+    TooManyCopies(TileType),
+    /// 取り除く牌がない
+    NoTileToRemove(TileType),
+    /// 鳴くための牌が手牌にない
+    NoTileToCall(TileType),
+    /// 暗槓するための牌が足りない
+    NotEnoughTilesForKan(TileType),
+    /// 加槓するための明刻がない
+    NoPonToAddKan(TileType),
+    /// 加槓する牌が手牌にない
+    NoTileToAddKan(TileType),
+    /// 手牌の文字列を読み込めない
+    ParseHand(ParseHandError),
+    /// 数として読み込めない
+    ParseInt(ParseIntError),
+    /// 牌の種類ごとの枚数で表した手牌の枚数が13枚でも14枚でもない
+    WrongNumberOfTiles(u32),
+    /// 捨てる牌が手牌にない
+    NoTileToDiscard(TileType),
+    /// `1m`のような牌の名前として読めない
+    InvalidTileName(String),
+    /// 打ち手の数が局の人数と異なる
+    AgentCountMismatch { agents: usize, players: usize },
+    /// 配牌するには山の牌が足りない
+    TooFewTilesToDeal,
+    /// 山を作るには牌が足りない（王牌の枚数未満）
+    TooFewTilesForWall(usize),
+    /// 山に自摸する牌が残っていない
+    NoTileInWall,
+    /// 嶺上牌が残っていない
+    NoReplacementTile,
+    /// 和了を宣言した席の手牌が和了の形でない
+    NotWinningHand(usize),
+    /// 自摸した牌がない
+    NoDrawnTile(usize),
+    /// 既に立直している席が立直を宣言した
+    AlreadyReady(usize),
+    /// 鳴く対象の捨て牌がない
+    NoDiscardToCall(usize),
+    /// 三人麻雀でないのに北を抜こうとした
+    NotThreePlayerGame,
+    /// 抜く北が手牌にない
+    NoNorthTile(usize),
+    /// 鳴いた後の行動が打牌でない
+    #[cfg(feature = "std")]
+    NotADiscard(TurnAction),
+    /// 他家の打牌に対する行動が鳴きでない
+    #[cfg(feature = "std")]
+    NotACall(CallAction),
+    /// 選べない手番の行動が返ってきた
+    #[cfg(feature = "std")]
+    IllegalAction { seat: usize, action: TurnAction },
+    /// 選べない鳴きが返ってきた
+    #[cfg(feature = "std")]
+    IllegalCall { seat: usize, action: CallAction },
+    /// 配牌を局に反映しようとした
+    DealOnRound,
+    /// 配牌の前に局の出来事を反映しようとした
+    #[cfg(feature = "std")]
+    NoRound(Box<Event>),
+    /// 出来事を反映し直した結果が記録と異なる
+    #[cfg(feature = "std")]
+    ReplayDiverged {
+        expected: Box<Event>,
+        got: Box<Event>,
+    },
+    /// 出来事の1行を読み込めない
+    InvalidEvent(String),
+    /// JSONとして読み込めない（内容は説明）
+    InvalidJson(String),
+    /// 牌譜として読み込めない（内容は説明）
+    InvalidLog(String),
+    /// mjaiのメッセージとして読み込めない（内容は説明）
+    InvalidMjaiMessage(String),
+    /// mjaiの打ち手が想定と異なるメッセージを返した（内容は説明）
+    UnexpectedMessage(String),
+    /// mjaiの打ち手が接続を閉じた
+    ConnectionClosed(usize),
+    /// 入出力に失敗した（種類と説明）
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind, String),
+    /// mjaiの打ち手との通信に失敗した（`source`が原因）
+    Bot { seat: usize, source: Box<Error> },
+}
+
+/// `crate::error::Error`を使う`Result`
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidTile(t) => write!(f, "invalid tile: {}", t),
+            Error::UnknownTileIndex(_) => write!(f, "Unknown tile index found!"),
+            // `Same3`と`Sequential3`は以前からの表示に合わせる
+            Error::NotSameTiles { block, tiles } if *block == "Same3" => {
+                write!(f, "Not same tiles in `{}`: {}!", block, join(tiles))
+            }
+            Error::NotSameTiles { block, tiles } => {
+                write!(f, "Not same tiles in `{}`: {} !", block, join(tiles))
+            }
+            Error::NotSequentialTiles { block, tiles } if *block == "Sequential3" => {
+                write!(f, "Not sequential tiles in `{}`:{} !", block, join(tiles))
+            }
+            Error::NotSequentialTiles { block, tiles } => {
+                write!(f, "Not sequential tiles in `{}`: {} !", block, join(tiles))
+            }
+            Error::HonorInSequence { block, tiles } => {
+                write!(
+                    f,
+                    "Cannot assign Honor tiles to `{}`: {} !",
+                    block,
+                    join(tiles)
+                )
+            }
+            Error::MixedSuits { block, tiles } => {
+                write!(
+                    f,
+                    "Cannot assign different suits to `{}`: {} !",
+                    block,
+                    join(tiles)
+                )
+            }
+            Error::TooManyCopies(t) => write!(f, "More than 4 copies of {}", Tile::new(*t)),
+            Error::NoTileToRemove(t) => write!(f, "No tile to remove: {}", Tile::new(*t)),
+            Error::NoTileToCall(t) => write!(f, "No tile to call in hand: {}", Tile::new(*t)),
+            Error::NotEnoughTilesForKan(t) => {
+                write!(f, "Not enough tiles for a closed kan: {}", Tile::new(*t))
+            }
+            Error::NoPonToAddKan(t) => write!(f, "No pon to add a kan: {}", Tile::new(*t)),
+            Error::NoTileToAddKan(t) => write!(f, "No tile to add a kan: {}", Tile::new(*t)),
+            Error::ParseHand(e) => write!(f, "{}", e),
+            Error::ParseInt(e) => write!(f, "{}", e),
+            Error::WrongNumberOfTiles(len) => write!(f, "Wrong number of tiles: {}", len),
+            Error::NoTileToDiscard(t) => write!(f, "No tile to discard: {}", Tile::new(*t)),
+            Error::InvalidTileName(name) => write!(f, "Invalid tile: {}", name),
+            Error::AgentCountMismatch { agents, players } => write!(
+                f,
+                "The number of agents ({}) differs from the number of players ({})",
+                agents, players
+            ),
+            Error::TooFewTilesToDeal => write!(f, "Too few tiles to deal"),
+            Error::TooFewTilesForWall(len) => write!(f, "Too few tiles for a wall: {}", len),
+            Error::NoTileInWall => write!(f, "No tile left in the wall"),
+            Error::NoReplacementTile => write!(f, "No replacement tile left"),
+            Error::NotWinningHand(seat) => write!(f, "Not a winning hand: seat {}", seat),
+            Error::NoDrawnTile(seat) => write!(f, "No drawn tile: seat {}", seat),
+            Error::AlreadyReady(seat) => write!(f, "Seat {} has already claimed ready", seat),
+            Error::NoDiscardToCall(seat) => write!(f, "No discard to call from seat {}", seat),
+            Error::NotThreePlayerGame => {
+                write!(f, "North tiles can be extracted only in three-player games")
+            }
+            Error::NoNorthTile(seat) => write!(f, "No north tile in the hand of seat {}", seat),
+            #[cfg(feature = "std")]
+            Error::NotADiscard(action) => write!(f, "Not a discard: {:?}", action),
+            #[cfg(feature = "std")]
+            Error::NotACall(action) => write!(f, "Not a call: {:?}", action),
+            #[cfg(feature = "std")]
+            Error::IllegalAction { seat, action } => {
+                write!(f, "Illegal action from seat {}: {:?}", seat, action)
+            }
+            #[cfg(feature = "std")]
+            Error::IllegalCall { seat, action } => {
+                write!(f, "Illegal call from seat {}: {:?}", seat, action)
+            }
+            Error::DealOnRound => write!(f, "A deal cannot be applied to a round"),
+            #[cfg(feature = "std")]
+            Error::NoRound(event) => write!(f, "No round has been dealt: {}", event),
+            #[cfg(feature = "std")]
+            Error::ReplayDiverged { expected, got } => {
+                write!(f, "Replay diverged: expected {}, got {}", expected, got)
+            }
+            Error::InvalidEvent(line) => write!(f, "Invalid event: {}", line),
+            Error::InvalidJson(message)
+            | Error::InvalidLog(message)
+            | Error::InvalidMjaiMessage(message)
+            | Error::UnexpectedMessage(message) => write!(f, "{}", message),
+            Error::ConnectionClosed(seat) => write!(f, "Connection closed by seat {}", seat),
+            #[cfg(feature = "std")]
+            Error::Io(_, message) => write!(f, "{}", message),
+            Error::Bot { seat, .. } => write!(f, "mjai bot at seat {} failed", seat),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::ParseHand(e) => Some(e),
+            Error::ParseInt(e) => Some(e),
+            Error::Bot { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseHandError> for Error {
+    fn from(error: ParseHandError) -> Error {
+        return Error::ParseHand(error);
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(error: ParseIntError) -> Error {
+        return Error::ParseInt(error);
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        return Error::Io(error.kind(), error.to_string());
+    }
+}
+
+/// 牌の番号を`, `でつなぐ
+fn join(tiles: &[TileType]) -> String {
+    let tiles: Vec<String> = tiles.iter().map(|t| t.to_string()).collect();
+    return tiles.join(", ");
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::hand_info::block::{Same2, Same3, Sequential2, Sequential3};
+
+    #[test]
+    /// エラーの内容と表示
+    fn display() {
+        let error = Sequential3::new(Tile::M8, Tile::M9, Tile::P1).unwrap_err();
+        assert_eq!(
+            error,
+            Error::MixedSuits {
+                block: "Sequential3",
+                tiles: vec![Tile::M8, Tile::M9, Tile::P1],
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Cannot assign different suits to `Sequential3`: 7, 8, 9 !"
+        );
+        assert_eq!(
+            Same2::new(Tile::M1, Tile::Z7 + 1).unwrap_err().to_string(),
+            "invalid tile: 34"
+        );
+        assert_eq!(
+            Same2::new(Tile::M1, Tile::M2).unwrap_err().to_string(),
+            "Not same tiles in `Same2`: 0, 1 !"
+        );
+        assert_eq!(
+            Same3::new(Tile::M1, Tile::M1, Tile::M2).unwrap_err().to_string(),
+            "Not same tiles in `Same3`: 0, 0, 1!"
+        );
+        assert_eq!(
+            Sequential2::new(Tile::M1, Tile::M4).unwrap_err().to_string(),
+            "Not sequential tiles in `Sequential2`: 0, 3 !"
+        );
+        assert_eq!(
+            Sequential3::new(Tile::M1, Tile::M2, Tile::M4).unwrap_err().to_string(),
+            "Not sequential tiles in `Sequential3`:0, 1, 3 !"
+        );
+        assert_eq!(
+            Error::UnknownTileIndex(Tile::Z7 + 1).to_string(),
+            "Unknown tile index found!"
+        );
+        assert_eq!(
+            Error::TooManyCopies(Tile::Z5).to_string(),
+            "More than 4 copies of 5z"
+        );
+    }
+}
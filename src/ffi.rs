@@ -0,0 +1,593 @@
+use std::ffi::{c_char, CStr};
+use std::panic::{catch_unwind, UnwindSafe};
+use std::ptr;
+
+use strum::IntoEnumIterator;
+
+use crate::board::payment::{ron_payment, round_up};
+use crate::hand::Hand;
+use crate::hand_info::hand_analyzer::get_winning_tiles;
+use crate::hand_info::shanten::shanten;
+use crate::hand_info::status::Status;
+use crate::settings::{Lang, Settings};
+use crate::tile::*;
+use crate::winning_hand::name::{self, Kind};
+use crate::winning_hand::score;
+
+/// `RmScore`に入る役の数の上限
+pub const RM_MAX_YAKU: usize = 16;
+
+/// 関数の結果（`RmOk`以外の時は、`RmErrorBufferTooSmall`で必要な長さを返す以外は出力を書き換えない）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RmErrorCode {
+    /// 成功
+    RmOk = 0,
+    /// 必要なポインタが`NULL`
+    RmErrorNullPointer = 1,
+    /// 文字列がUTF-8でない
+    RmErrorInvalidUtf8 = 2,
+    /// 手牌の文字列を読み込めない
+    RmErrorParse = 3,
+    /// 引数の値が範囲外（牌・風・言語・役の番号など）
+    RmErrorInvalidArgument = 4,
+    /// 手牌を解析できない
+    RmErrorInvalidHand = 5,
+    /// 書き込む領域が足りない
+    RmErrorBufferTooSmall = 6,
+    /// ライブラリの内部でパニックした
+    RmErrorPanic = 7,
+}
+
+/// 手牌（中身は見せない）
+///
+/// `rm_hand_parse`で作り、呼び出し側が`rm_hand_free`で解放する。
+/// それ以外の関数は、結果を呼び出し側の用意した領域に書き込むので解放するものはない。
+pub struct RmHand {
+    hand: Hand,
+}
+
+/// 手牌の（牌以外の）状態（`Status`に対応する）
+///
+/// すべて`0`にすると、東場の東家・門前・ロン和了になる。
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RmHandStatus {
+    /// 立直したか
+    pub has_claimed_ready: bool,
+    /// 鳴いたか
+    pub has_claimed_open: bool,
+    /// 自摸しているか
+    pub is_self_picked: bool,
+    /// 一発か
+    pub is_one_shot: bool,
+    /// ダブル立直したか
+    pub has_claimed_double_ready: bool,
+    /// 最後の牌（海底牌・河底牌）で和了ったか
+    pub is_last_tile: bool,
+    /// 嶺上牌で和了ったか
+    pub is_dead_wall_draw: bool,
+    /// 搶槓で和了ったか
+    pub is_robbing_a_quad: bool,
+    /// 誰も鳴いていない第一巡の自摸か
+    pub is_first_turn: bool,
+    /// 槓子の数
+    pub kan_count: u32,
+    /// 自風（`0`から順に東・南・西・北）
+    pub player_wind: u8,
+    /// 場風（`0`から順に東・南・西・北）
+    pub prevailing_wind: u8,
+}
+
+/// 成立した役
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RmYaku {
+    /// 役の番号（`Kind`の定義順、`0`が立直）
+    pub kind: u32,
+    /// 翻数（役満は`13`）
+    pub han: u32,
+}
+
+/// 点数計算の結果
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RmScore {
+    /// 和了の形で役があるか（`false`なら他の値はすべて`0`）
+    pub won: bool,
+    /// 翻数（ドラを含む）
+    pub han: u32,
+    /// 符
+    pub fu: u32,
+    /// ドラの枚数
+    pub dora: u32,
+    /// 役満の倍数（役満でなければ`0`）
+    pub yakuman: u32,
+    /// 基本点
+    pub base_points: i32,
+    /// ロン和了の放銃者の支払い
+    pub ron: i32,
+    /// 自摸和了の子の支払い
+    pub tsumo_child: i32,
+    /// 自摸和了の親の支払い（和了者が親なら`0`）
+    pub tsumo_dealer: i32,
+    /// `yaku`に入っている役の数
+    pub yaku_len: usize,
+    /// 成立した役
+    pub yaku: [RmYaku; RM_MAX_YAKU],
+}
+
+impl RmErrorCode {
+    /// すべての値（Cから渡された整数を照らし合わせる）
+    const ALL: [RmErrorCode; 8] = [
+        RmErrorCode::RmOk,
+        RmErrorCode::RmErrorNullPointer,
+        RmErrorCode::RmErrorInvalidUtf8,
+        RmErrorCode::RmErrorParse,
+        RmErrorCode::RmErrorInvalidArgument,
+        RmErrorCode::RmErrorInvalidHand,
+        RmErrorCode::RmErrorBufferTooSmall,
+        RmErrorCode::RmErrorPanic,
+    ];
+
+    /// 整数の値から戻す（定義にない値なら`None`）
+    fn from_raw(code: i32) -> Option<RmErrorCode> {
+        return RmErrorCode::ALL.into_iter().find(|c| *c as i32 == code);
+    }
+}
+
+/// エラーの説明（NUL終端の静的な文字列で、解放しない）
+///
+/// `code`は`RmErrorCode`の値。定義にない値には`"unknown error"`を返す
+/// （Cの列挙型は範囲外の値も持てるので、`RmErrorCode`としては受け取らない）。
+#[no_mangle]
+pub extern "C" fn rm_error_message(code: i32) -> *const c_char {
+    let message: &'static [u8] = match RmErrorCode::from_raw(code) {
+        Some(RmErrorCode::RmOk) => b"ok\0",
+        Some(RmErrorCode::RmErrorNullPointer) => b"null pointer\0",
+        Some(RmErrorCode::RmErrorInvalidUtf8) => b"invalid UTF-8 string\0",
+        Some(RmErrorCode::RmErrorParse) => b"cannot parse hand\0",
+        Some(RmErrorCode::RmErrorInvalidArgument) => b"invalid argument\0",
+        Some(RmErrorCode::RmErrorInvalidHand) => b"invalid hand\0",
+        Some(RmErrorCode::RmErrorBufferTooSmall) => b"buffer too small\0",
+        Some(RmErrorCode::RmErrorPanic) => b"internal panic\0",
+        None => b"unknown error\0",
+    };
+    return message.as_ptr() as *const c_char;
+}
+
+/// 手牌の文字列（`Hand::parse`の形式）を読み込む
+///
+/// 成功すると`*out`に手牌を入れる。不要になったら`rm_hand_free`で解放すること。
+///
+/// # Safety
+///
+/// `text`はNUL終端の文字列、`out`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_parse(text: *const c_char, out: *mut *mut RmHand) -> RmErrorCode {
+    if text.is_null() || out.is_null() {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let text = match CStr::from_ptr(text).to_str() {
+        Ok(text) => text,
+        Err(_) => return RmErrorCode::RmErrorInvalidUtf8,
+    };
+    guard(|| match Hand::parse(text) {
+        Ok(hand) => {
+            *out = Box::into_raw(Box::new(RmHand { hand }));
+            RmErrorCode::RmOk
+        }
+        Err(_) => RmErrorCode::RmErrorParse,
+    })
+}
+
+/// `rm_hand_parse`で作った手牌を解放する（`NULL`なら何もしない）
+///
+/// # Safety
+///
+/// `hand`は`rm_hand_parse`で作り、まだ解放していないものであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_free(hand: *mut RmHand) {
+    if !hand.is_null() {
+        drop(Box::from_raw(hand));
+    }
+}
+
+/// 手牌を`1m2m3m...`の形式の文字列にする
+///
+/// # Safety
+///
+/// `hand`は有効な手牌、`buffer`は`capacity`バイト書き込める領域（`capacity`が`0`なら`NULL`でもよい）、
+/// `len`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_to_string(
+    hand: *const RmHand,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> RmErrorCode {
+    if hand.is_null() || len.is_null() {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let hand = &(*hand).hand;
+    guard(|| write_str(&hand.to_string(), buffer, capacity, len))
+}
+
+/// 向聴数（和了の形なら`-1`）を求める
+///
+/// # Safety
+///
+/// `hand`は有効な手牌、`out`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_shanten(hand: *const RmHand, out: *mut i32) -> RmErrorCode {
+    if hand.is_null() || out.is_null() {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let hand = &(*hand).hand;
+    guard(|| {
+        *out = shanten(hand);
+        RmErrorCode::RmOk
+    })
+}
+
+/// ツモった牌を除いた手牌の和了牌（牌の番号`0`〜`33`）を求める
+///
+/// 和了牌は34種類を超えないので、`capacity`が`34`あれば足りる。
+/// 聴牌していなければ`*len`は`0`になる。
+///
+/// # Safety
+///
+/// `hand`は有効な手牌、`tiles`は`capacity`個書き込める領域、`len`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_waits(
+    hand: *const RmHand,
+    tiles: *mut u8,
+    capacity: usize,
+    len: *mut usize,
+) -> RmErrorCode {
+    if hand.is_null() || len.is_null() || (tiles.is_null() && capacity > 0) {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let hand = &(*hand).hand;
+    guard(|| {
+        let waits = match get_winning_tiles(hand) {
+            Ok(waits) => waits,
+            Err(_) => return RmErrorCode::RmErrorInvalidHand,
+        };
+        if waits.len() > capacity {
+            *len = waits.len();
+            return RmErrorCode::RmErrorBufferTooSmall;
+        }
+        for (i, tile) in waits.iter().enumerate() {
+            *tiles.add(i) = *tile as u8;
+        }
+        *len = waits.len();
+        RmErrorCode::RmOk
+    })
+}
+
+/// ツモった牌で和了った時の役と点数を求める
+///
+/// `dora`はドラ（表示牌ではない）の牌の番号`dora_len`個（`dora_len`が`0`なら`NULL`でもよい）。
+/// 和了の形でないか役がなければ、`out->won`が`false`になる。支払いは四人麻雀で、自風が東なら親とする。
+///
+/// # Safety
+///
+/// `hand`は有効な手牌、`status`は読めるポインタ、`dora`は`dora_len`個読める領域、
+/// `out`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_hand_score(
+    hand: *const RmHand,
+    status: *const RmHandStatus,
+    dora: *const u8,
+    dora_len: usize,
+    out: *mut RmScore,
+) -> RmErrorCode {
+    if hand.is_null() || status.is_null() || out.is_null() || (dora.is_null() && dora_len > 0) {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let hand = &(*hand).hand;
+    let status = *status;
+    let dora: &[u8] = if dora_len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(dora, dora_len)
+    };
+    guard(|| {
+        let Some(status) = to_status(&status) else {
+            return RmErrorCode::RmErrorInvalidArgument;
+        };
+        if dora.iter().any(|&d| d as TileType > Tile::Z7) {
+            return RmErrorCode::RmErrorInvalidArgument;
+        }
+        let dora: Vec<TileType> = dora.iter().map(|&d| d as TileType).collect();
+        let score = match score::calculate(hand, &status, &Settings::new(), &dora, 0) {
+            Ok(score) => score,
+            Err(_) => return RmErrorCode::RmErrorInvalidHand,
+        };
+        let mut result = RmScore::default();
+        if let Some(score) = score {
+            let is_dealer = status.player_wind == Wind::East;
+            let kinds: Vec<Kind> = Kind::iter().collect();
+            result.won = true;
+            result.han = score.han;
+            result.fu = score.fu;
+            result.dora = score.dora;
+            result.yakuman = score.yakuman;
+            result.base_points = score.base_points;
+            result.ron = ron_payment(score.base_points, is_dealer);
+            if is_dealer {
+                result.tsumo_child = round_up(score.base_points * 2);
+            } else {
+                result.tsumo_child = round_up(score.base_points);
+                result.tsumo_dealer = round_up(score.base_points * 2);
+            }
+            for (kind, _, han) in score.winning_hands.iter().take(RM_MAX_YAKU) {
+                result.yaku[result.yaku_len] = RmYaku {
+                    kind: kinds.iter().position(|k| k == kind).unwrap_or(0) as u32,
+                    han: *han,
+                };
+                result.yaku_len += 1;
+            }
+        }
+        *out = result;
+        RmErrorCode::RmOk
+    })
+}
+
+/// 役の名前を書き込む
+///
+/// `lang`は`0`が日本語、`1`が英語。`has_opened`なら喰い下がり役に「（鳴）」が付く。
+///
+/// # Safety
+///
+/// `buffer`は`capacity`バイト書き込める領域（`capacity`が`0`なら`NULL`でもよい）、
+/// `len`は書き込めるポインタであること。
+#[no_mangle]
+pub unsafe extern "C" fn rm_yaku_name(
+    kind: u32,
+    has_opened: bool,
+    lang: u8,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> RmErrorCode {
+    if len.is_null() {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    let lang = match lang {
+        0 => Lang::Ja,
+        1 => Lang::En,
+        _ => return RmErrorCode::RmErrorInvalidArgument,
+    };
+    let Some(kind) = Kind::iter().nth(kind as usize) else {
+        return RmErrorCode::RmErrorInvalidArgument;
+    };
+    write_str(name::get(kind, has_opened, lang), buffer, capacity, len)
+}
+
+/// パニックを`RmErrorPanic`にする
+fn guard<F: FnOnce() -> RmErrorCode + UnwindSafe>(f: F) -> RmErrorCode {
+    catch_unwind(f).unwrap_or(RmErrorCode::RmErrorPanic)
+}
+
+/// 文字列をNUL終端で書き込む（`len`はNULを除いた長さで、足りなければ必要な長さを入れる）
+unsafe fn write_str(s: &str, buffer: *mut c_char, capacity: usize, len: *mut usize) -> RmErrorCode {
+    if s.len() >= capacity {
+        *len = s.len();
+        return RmErrorCode::RmErrorBufferTooSmall;
+    }
+    if buffer.is_null() {
+        return RmErrorCode::RmErrorNullPointer;
+    }
+    ptr::copy_nonoverlapping(s.as_ptr(), buffer as *mut u8, s.len());
+    *buffer.add(s.len()) = 0;
+    *len = s.len();
+    RmErrorCode::RmOk
+}
+
+/// `RmHandStatus`を`Status`にする（風の値が範囲外なら`None`）
+fn to_status(status: &RmHandStatus) -> Option<Status> {
+    let wind = |w: u8| {
+        if w < 4 {
+            Wind::is_tile_type(Tile::Z1 + w as TileType)
+        } else {
+            None
+        }
+    };
+    Some(Status {
+        has_claimed_ready: status.has_claimed_ready,
+        has_claimed_open: status.has_claimed_open,
+        is_self_picked: status.is_self_picked,
+        is_one_shot: status.is_one_shot,
+        has_claimed_double_ready: status.has_claimed_double_ready,
+        is_last_tile: status.is_last_tile,
+        is_dead_wall_draw: status.is_dead_wall_draw,
+        is_robbing_a_quad: status.is_robbing_a_quad,
+        is_first_turn: status.is_first_turn,
+        kan_count: status.kan_count,
+        player_wind: wind(status.player_wind)?,
+        prevailing_wind: wind(status.prevailing_wind)?,
+    })
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    /// 文字列から手牌を作る
+    fn parse(text: &str) -> *mut RmHand {
+        let text = CString::new(text).unwrap();
+        let mut hand: *mut RmHand = ptr::null_mut();
+        assert_eq!(
+            unsafe { rm_hand_parse(text.as_ptr(), &mut hand) },
+            RmErrorCode::RmOk
+        );
+        return hand;
+    }
+
+    #[test]
+    /// 読み込み・向聴数・和了牌
+    fn parse_shanten_waits() {
+        let hand = parse("234m456p678s2388p");
+        let mut shanten = 99;
+        let mut tiles = [0u8; 34];
+        let mut len = 0;
+        unsafe {
+            assert_eq!(rm_hand_shanten(hand, &mut shanten), RmErrorCode::RmOk);
+            assert_eq!(
+                rm_hand_waits(hand, tiles.as_mut_ptr(), 34, &mut len),
+                RmErrorCode::RmOk
+            );
+            assert_eq!(
+                rm_hand_waits(hand, tiles.as_mut_ptr(), 2, &mut len),
+                RmErrorCode::RmErrorBufferTooSmall
+            );
+            let mut buffer = [0 as c_char; 64];
+            let mut text_len = 0;
+            assert_eq!(
+                rm_hand_to_string(hand, buffer.as_mut_ptr(), 64, &mut text_len),
+                RmErrorCode::RmOk
+            );
+            assert_eq!(
+                CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(),
+                "2m3m4m4p5p6p6s7s8s2p3p8p8p"
+            );
+            assert_eq!(text_len, 26);
+            rm_hand_free(hand);
+        }
+        assert_eq!(shanten, 0);
+        assert_eq!(len, 3);
+        assert_eq!(
+            &tiles[..3],
+            &[Tile::P1 as u8, Tile::P4 as u8, Tile::P7 as u8]
+        );
+
+        let text = CString::new("1m2m").unwrap();
+        let mut hand: *mut RmHand = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                rm_hand_parse(text.as_ptr(), &mut hand),
+                RmErrorCode::RmErrorParse
+            );
+            assert_eq!(
+                rm_hand_parse(ptr::null(), &mut hand),
+                RmErrorCode::RmErrorNullPointer
+            );
+        }
+        assert!(hand.is_null());
+    }
+
+    #[test]
+    /// 役と点数
+    fn score() {
+        let hand = parse("234m456p678s2388p 4p");
+        let status = RmHandStatus {
+            is_self_picked: true,
+            player_wind: 1,
+            ..RmHandStatus::default()
+        };
+        let dora = [Tile::M2 as u8];
+        let mut result = RmScore::default();
+        unsafe {
+            assert_eq!(
+                rm_hand_score(hand, &status, dora.as_ptr(), 1, &mut result),
+                RmErrorCode::RmOk
+            );
+            let wrong = RmHandStatus {
+                player_wind: 4,
+                ..status
+            };
+            assert_eq!(
+                rm_hand_score(hand, &wrong, ptr::null(), 0, &mut result),
+                RmErrorCode::RmErrorInvalidArgument
+            );
+            rm_hand_free(hand);
+        }
+        assert!(result.won);
+        assert_eq!((result.han, result.fu, result.dora), (4, 20, 1));
+        assert_eq!((result.tsumo_child, result.tsumo_dealer), (1300, 2600));
+        assert_eq!(result.yaku_len, 3);
+
+        let mut buffer = [0 as c_char; 64];
+        let mut len = 0;
+        unsafe {
+            assert_eq!(
+                rm_yaku_name(
+                    result.yaku[0].kind,
+                    false,
+                    0,
+                    buffer.as_mut_ptr(),
+                    64,
+                    &mut len
+                ),
+                RmErrorCode::RmOk
+            );
+            assert_eq!(
+                CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(),
+                "門前清自摸和"
+            );
+            assert_eq!(
+                rm_yaku_name(
+                    result.yaku[0].kind,
+                    false,
+                    1,
+                    buffer.as_mut_ptr(),
+                    4,
+                    &mut len
+                ),
+                RmErrorCode::RmErrorBufferTooSmall
+            );
+            assert_eq!(len, "Self Pick".len());
+            assert_eq!(
+                rm_yaku_name(
+                    Kind::iter().count() as u32,
+                    false,
+                    0,
+                    buffer.as_mut_ptr(),
+                    64,
+                    &mut len
+                ),
+                RmErrorCode::RmErrorInvalidArgument
+            );
+        }
+    }
+
+    #[test]
+    /// エラーの説明（定義にない値も受け取れる）
+    fn error_message() {
+        let message = |code: i32| unsafe { CStr::from_ptr(rm_error_message(code)) };
+        for code in RmErrorCode::ALL {
+            assert_eq!(RmErrorCode::from_raw(code as i32), Some(code));
+        }
+        assert_eq!(
+            message(RmErrorCode::RmErrorParse as i32).to_str(),
+            Ok("cannot parse hand")
+        );
+        assert_eq!(message(-1).to_str(), Ok("unknown error"));
+        assert_eq!(message(8).to_str(), Ok("unknown error"));
+    }
+
+    #[test]
+    /// ヘッダにすべての関数が載っている
+    fn header() {
+        let header = include_str!("../ffi/riichi_mahjong.h");
+        for name in [
+            "rm_error_message",
+            "rm_hand_parse",
+            "rm_hand_free",
+            "rm_hand_to_string",
+            "rm_hand_shanten",
+            "rm_hand_waits",
+            "rm_hand_score",
+            "rm_yaku_name",
+            "RM_MAX_YAKU 16",
+            "RmHandStatus",
+            "RmScore",
+        ] {
+            assert!(header.contains(name), "{} is missing in the header", name);
+        }
+    }
+}
@@ -1,9 +1,17 @@
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{Error, Result};
 use crate::hand_info::opened::*;
 use crate::tile::*;
-use std::collections::VecDeque;
 
 /// 手牌
-#[derive(Debug)]
+///
+/// 比較は牌の並び順も含めて行う（`sort`してから比べると並び順によらない）。
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     /// 現在の手牌（副露がなければ13枚）
     tiles: Vec<Tile>,
@@ -29,13 +37,151 @@ impl Hand {
         self.drawn
     }
 
+    /// ツモった牌をセットする
+    pub fn set_drawn(&mut self, drawn: Option<Tile>) {
+        self.drawn = drawn;
+    }
+
+    /// 指定した種類の牌を1枚捨てて返す
+    ///
+    /// ツモった牌が指定した種類ならそれを捨て（ツモ切り）、そうでなければ手牌から捨ててツモった牌を手牌に加える。
+    /// 指定した種類の牌がなければ`None`を返す。
+    pub fn discard(&mut self, tile: TileType) -> Option<Tile> {
+        if let Some(t) = self.drawn {
+            if t.get() == tile {
+                self.drawn = None;
+                return Some(t);
+            }
+        }
+        let pos = self.tiles.iter().position(|t| t.get() == tile)?;
+        let result = self.tiles.remove(pos);
+        if let Some(t) = self.drawn.take() {
+            self.tiles.push(t);
+            self.tiles.sort();
+        }
+        return Some(result);
+    }
+
+    /// 指定した牌（赤ドラか否かも区別する）を1枚捨てて返す
+    ///
+    /// ツモった牌の扱いは`discard`と同じ。
+    pub fn discard_tile(&mut self, tile: Tile) -> Option<Tile> {
+        if self.drawn == Some(tile) {
+            self.drawn = None;
+            return Some(tile);
+        }
+        let pos = self.tiles.iter().position(|t| *t == tile)?;
+        let result = self.tiles.remove(pos);
+        if let Some(t) = self.drawn.take() {
+            self.tiles.push(t);
+            self.tiles.sort();
+        }
+        return Some(result);
+    }
+
+    /// 副露とツモった牌を除いた手牌を返す
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// 副露を返す
+    pub fn opened(&self) -> &[OpenTiles] {
+        &self.opened
+    }
+
+    /// 手牌・副露（カンは4枚）・ツモった牌の順に全ての牌を返す
+    pub fn iter(&self) -> impl Iterator<Item = Tile> + '_ {
+        let opened = self.opened.iter().flat_map(|open| {
+            // カンの4枚目は`tiles`に入っていない
+            let fourth = match open.category {
+                OpenType::Kan => Some(Tile::new(open.tiles[0].get())),
+                _ => None,
+            };
+            open.tiles.iter().copied().chain(fourth)
+        });
+        return self.tiles.iter().copied().chain(opened).chain(self.drawn);
+    }
+
+    /// 他家の捨て牌をポン・チー・大明槓する
+    ///
+    /// `tiles`は手牌から出す牌の種類（ポン・チーは2枚、大明槓は3枚）。
+    pub fn call(
+        &mut self,
+        tiles: &[TileType],
+        called: Tile,
+        category: OpenType,
+        from: OpenFrom,
+    ) -> Result<()> {
+        let mut removed: Vec<Tile> = Vec::new();
+        for &t in tiles {
+            match self.tiles.iter().position(|x| x.get() == t) {
+                Some(pos) => removed.push(self.tiles.remove(pos)),
+                None => {
+                    // 取り除いた牌を元に戻す
+                    self.tiles.append(&mut removed);
+                    self.tiles.sort();
+                    return Err(Error::NoTileToCall(t));
+                }
+            }
+        }
+        removed.push(called);
+        self.opened.push(OpenTiles {
+            tiles: OpenTiles::pick_tiles(&removed),
+            category,
+            from,
+            called: Some(called),
+            added: false,
+        });
+        return Ok(());
+    }
+
+    /// 暗槓する（ツモった牌は手牌に加える）
+    pub fn closed_kan(&mut self, tile: TileType) -> Result<()> {
+        let count = self.tiles.iter().filter(|t| t.get() == tile).count()
+            + self.drawn.iter().filter(|t| t.get() == tile).count();
+        if count < 4 {
+            return Err(Error::NotEnoughTilesForKan(tile));
+        }
+        if let Some(t) = self.drawn.take() {
+            self.tiles.push(t);
+            self.tiles.sort();
+        }
+        let mut removed: Vec<Tile> = Vec::new();
+        while let Some(pos) = self.tiles.iter().position(|t| t.get() == tile) {
+            removed.push(self.tiles.remove(pos));
+        }
+        self.opened.push(OpenTiles {
+            tiles: OpenTiles::pick_tiles(&removed),
+            category: OpenType::Kan,
+            from: OpenFrom::Myself,
+            called: None,
+            added: false,
+        });
+        return Ok(());
+    }
+
+    /// ポンしている牌に1枚加えて加槓する
+    pub fn added_kan(&mut self, tile: TileType) -> Result<()> {
+        let pos = self
+            .opened
+            .iter()
+            .position(|o| o.category == OpenType::Pon && o.tiles[0].get() == tile)
+            .ok_or(Error::NoPonToAddKan(tile))?;
+        let added = self.discard(tile).ok_or(Error::NoTileToAddKan(tile))?;
+        let open = &mut self.opened[pos];
+        open.tiles = OpenTiles::pick_tiles(&[open.tiles[0], open.tiles[1], open.tiles[2], added]);
+        self.opened[pos].category = OpenType::Kan;
+        self.opened[pos].added = true;
+        return Ok(());
+    }
+
     /// 手牌をソートする
     pub fn sort(&mut self) {
         self.tiles.sort();
     }
     /// 種類別に各牌の数をカウントする
     pub fn summarize_tiles(&self) -> TileSummarize {
-        let mut result: TileSummarize = [0; Tile::LEN as usize];
+        let mut result: TileSummarize = [0; Tile::LEN];
 
         // 通常の手牌をカウント
         for i in 0..self.tiles.len() {
@@ -79,40 +225,61 @@ impl Hand {
         return result;
     }
 
-    /// 文字列として出力する
+    /// 副露を文字列にする
     ///
-    /// `to_short_string`と違い、こちらは牌の種類を省略せずに`1m2m3m1p2p3p...`と必ず2文字単位で出力する。
-    pub fn to_string(&self) -> String {
-        let mut result = String::new();
-        for i in 0..self.tiles.len() {
-            result.push_str(&self.tiles[i].to_string());
+    /// 鳴いた牌の前に誰から鳴いたかの記号（上家`<`・対面`^`・下家`>`）を、加カンで加えた牌の前に`+`を付ける。
+    /// 暗カンは`[1m1m1m1m]`のように括弧で囲む。誰から鳴いたか不明なら記号は付けない。
+    /// `short`なら牌の種類を最後にだけ付ける（副露は全て同じ種類の牌なので`<345m`のようになる）。
+    pub(crate) fn open_to_str(open: &OpenTiles, short: bool) -> String {
+        let mut tiles: Vec<(Option<char>, Tile)> = open.tiles.iter().map(|t| (None, *t)).collect();
+        let mark = match open.from {
+            OpenFrom::Previous => Some('<'),
+            OpenFrom::Opposite => Some('^'),
+            OpenFrom::Following => Some('>'),
+            OpenFrom::Myself | OpenFrom::Unknown => None,
+        };
+        if mark.is_some() {
+            let pos = open
+                .called
+                .and_then(|c| {
+                    // 赤ドラを鳴いた場合は赤ドラに記号を付ける
+                    tiles
+                        .iter()
+                        .position(|(_, t)| *t == c)
+                        .or_else(|| tiles.iter().position(|(_, t)| t.get() == c.get()))
+                })
+                .unwrap_or(0);
+            tiles[pos].0 = mark;
+        }
+        // カンなら4枚目を追加する
+        if open.category == OpenType::Kan {
+            tiles.push((if open.added { Some('+') } else { None }, open.tiles[0]));
         }
 
-        for i in 0..self.opened.len() {
-            result.push_str(&format!(
-                " {}{}{}",
-                self.opened[i].tiles[0].to_string(),
-                self.opened[i].tiles[1].to_string(),
-                self.opened[i].tiles[2].to_string()
-            ));
-            // カンなら4枚目を追加する
-            if self.opened[i].category == OpenType::Kan {
-                result.push_str(&format!("{}", self.opened[i].tiles[0].to_string(),));
+        let mut result = String::new();
+        for (i, (mark, tile)) in tiles.iter().enumerate() {
+            if let Some(m) = mark {
+                result.push(*m);
+            }
+            let name = tile.to_string();
+            if short && i + 1 < tiles.len() {
+                result.push_str(&name[..1]);
+            } else {
+                result.push_str(&name);
             }
         }
-
-        if let Some(tsumo) = self.drawn {
-            result.push_str(&format!(" {}", tsumo.to_string()));
+        if open.category == OpenType::Kan && open.from == OpenFrom::Myself {
+            return format!("[{}]", result);
         }
         return result;
     }
 
     /// `Vec<Tile>`から連続した牌の種類を圧縮した文字列を返す
     fn make_short_str(mut tiles: Vec<Tile>) -> String {
-        if tiles.len() == 0 {
+        if tiles.is_empty() {
             return String::from("");
         } else if tiles.len() == 1 {
-            return tiles[1].to_string();
+            return tiles[0].to_string();
         }
         tiles.sort();
         let mut result = String::new();
@@ -143,33 +310,40 @@ impl Hand {
         let mut result = Hand::make_short_str(tiles);
 
         for i in 0..self.opened.len() {
-            let mut op_tiles = Vec::from(self.opened[i].tiles);
-            if self.opened[i].category == OpenType::Kan {
-                op_tiles.push(self.opened[i].tiles[0]);
-            }
-            result.push_str(&format!(" {}", Hand::make_short_str(op_tiles)));
+            result.push_str(&format!(" {}", Hand::open_to_str(&self.opened[i], true)));
         }
 
         if let Some(tsumo) = self.drawn {
-            result.push_str(&format!(" {}", tsumo.to_string()));
+            result.push_str(&format!(" {}", tsumo));
         }
         return result;
     }
 
     /// 文字列から`Vec<Tile>`を返す
     fn str_to_tiles(hand_str: &str) -> Vec<Tile> {
-        let mut result: Vec<Tile> = Vec::new();
-        let mut stack: VecDeque<char> = VecDeque::new();
-        let mut itr = hand_str.chars();
-        while let Some(c) = itr.next() {
-            if matches!(c, '1'..='9') {
-                stack.push_back(c);
+        return Hand::str_to_marked_tiles(hand_str)
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+    }
+
+    /// 文字列から、直前に付いた記号（`<`・`^`・`>`・`+`）と牌の組を返す
+    fn str_to_marked_tiles(hand_str: &str) -> Vec<(Option<char>, Tile)> {
+        let mut result: Vec<(Option<char>, Tile)> = Vec::new();
+        let mut stack: VecDeque<(Option<char>, char)> = VecDeque::new();
+        let mut mark: Option<char> = None;
+        let itr = hand_str.chars();
+        for c in itr {
+            if matches!(c, '<' | '^' | '>' | '+') {
+                mark = Some(c);
+            } else if c.is_ascii_digit() {
+                stack.push_back((mark.take(), c));
             } else if matches!(c, 'm' | 'p' | 's' | 'z') {
-                while let Some(t) = stack.pop_front() {
+                while let Some((m, t)) = stack.pop_front() {
                     // 字牌の場合は`8z`と`9z`は存在しない
                     if matches!(c, 'm' | 'p' | 's') || (c == 'z' && matches!(t, '1'..='7')) {
-                        if let Some(t) = Tile::from(&format!("{}{}", t, c)) {
-                            result.push(t);
+                        if let Ok(t) = format!("{}{}", t, c).parse::<Tile>() {
+                            result.push((m, t));
                         }
                     }
                 }
@@ -178,49 +352,242 @@ impl Hand {
         return result;
     }
 
-    pub fn from(hand_str: &str) -> Hand {
+    /// 文字列から手牌を作る
+    ///
+    /// 空白で区切り、手牌・副露（複数可）・ツモった牌の順に書く（例：`123m456p11z <789s ^5z5z5z 1z`）。
+    /// 副露は`to_string`・`to_short_string`と同じ表記で、鳴いた牌の前に誰から鳴いたか
+    /// （上家`<`・対面`^`・下家`>`）を、加カンで加えた牌の前に`+`を付ける（例：`^111+1m`）。
+    /// 暗カンは`[1111m]`のように括弧で囲む。記号のない副露は誰から鳴いたか不明とみなし、
+    /// 3枚なら先頭の2枚が同じ牌かどうかでポンかチーか、4枚ならカンとする。
+    /// `0m`・`0p`・`0s`は赤ドラの五とする。
+    /// 読めない文字や存在しない牌は無視する（誤りを知るには`parse`を使う）。
+    pub fn parse_lossy(hand_str: &str) -> Hand {
         let mut itr = hand_str.split_ascii_whitespace();
         let hand = Hand::str_to_tiles(itr.next().unwrap_or(""));
         let mut opened: Vec<OpenTiles> = Vec::new();
         let mut drawn: Option<Tile> = None;
 
-        while let Some(tile_str) = itr.next() {
-            let tile_vec = Hand::str_to_tiles(tile_str);
-            match tile_vec.len() {
-                1 => {
-                    let t = *tile_vec.get(0).unwrap();
-                    drawn = Some(t);
+        for tile_str in itr {
+            let (closed, body) = match tile_str.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                Some(body) => (true, body),
+                None => (false, tile_str),
+            };
+            let marked = Hand::str_to_marked_tiles(body);
+            if marked.len() == 1 && !closed && marked[0].0.is_none() {
+                drawn = Some(marked[0].1);
+            } else if let Some(open) = Hand::make_open(&marked, closed) {
+                opened.push(open);
+            }
+        }
+        return Hand::new_with_opened(hand, opened, drawn);
+    }
+
+    /// `parse_lossy`の旧名（`From`トレイトと紛らわしいので改名した）
+    #[deprecated(note = "use `Hand::parse_lossy` or `str::parse::<Hand>` instead")]
+    pub fn from(hand_str: &str) -> Hand {
+        return Hand::parse_lossy(hand_str);
+    }
+
+    /// 記号の付いた3枚・4枚の牌から副露を作る（`closed`なら暗カン）
+    fn make_open(marked: &[(Option<char>, Tile)], closed: bool) -> Option<OpenTiles> {
+        let mut tile_vec: Vec<Tile> = marked.iter().map(|(_, t)| *t).collect();
+        tile_vec.sort();
+        let mut from = OpenFrom::Unknown;
+        let mut called: Option<Tile> = None;
+        let mut added = false;
+        for (mark, t) in marked {
+            match mark {
+                Some('<') => (from, called) = (OpenFrom::Previous, Some(*t)),
+                Some('^') => (from, called) = (OpenFrom::Opposite, Some(*t)),
+                Some('>') => (from, called) = (OpenFrom::Following, Some(*t)),
+                Some('+') => added = true,
+                _ => {}
+            }
+        }
+        if closed {
+            (from, called) = (OpenFrom::Myself, None);
+        }
+        let category = match tile_vec.len() {
+            3 if tile_vec[0] == tile_vec[1] => OpenType::Pon,
+            3 => OpenType::Chi,
+            4 => OpenType::Kan,
+            _ => return None,
+        };
+        return Some(OpenTiles {
+            tiles: OpenTiles::pick_tiles(&tile_vec),
+            category,
+            from,
+            called,
+            added: added && category == OpenType::Kan,
+        });
+    }
+
+    /// 文字列から手牌を作る（`parse_lossy`と同じ表記）
+    ///
+    /// `parse_lossy`と違い、読めない文字や存在しない牌、形の正しくない副露があればその位置（何文字目か）と共にエラーを返す。
+    /// 同じ牌が（副露も含めて）5枚以上ある場合と、手牌の枚数が副露の数と合わない場合もエラーとする。
+    /// 手牌と副露の枚数（カンは3枚と数える）の合計は、ツモった牌があれば13枚、なければ13枚か14枚とする。
+    pub fn parse(hand_str: &str) -> core::result::Result<Hand, ParseHandError> {
+        let chars: Vec<char> = hand_str.chars().collect();
+        let error = |kind: ParseHandErrorKind, position: usize| ParseHandError { kind, position };
+
+        // 空白で区切った各部分の開始位置と文字
+        let mut groups: Vec<(usize, &[char])> = Vec::new();
+        let mut start: Option<usize> = None;
+        for i in 0..=chars.len() {
+            let space = i == chars.len() || chars[i].is_ascii_whitespace();
+            match (start, space) {
+                (None, false) => start = Some(i),
+                (Some(s), true) => {
+                    groups.push((s, &chars[s..i]));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        let mut hand: Vec<Tile> = Vec::new();
+        let mut opened: Vec<OpenTiles> = Vec::new();
+        let mut drawn: Option<Tile> = None;
+        // 全ての牌とその位置（5枚目を探すため）
+        let mut all: Vec<(usize, Tile)> = Vec::new();
+
+        for (n, &(pos, group)) in groups.iter().enumerate() {
+            if drawn.is_some() {
+                return Err(error(ParseHandErrorKind::InvalidMeld(group.iter().collect()), pos));
+            }
+            let closed = group.first() == Some(&'[');
+            if closed && (group.len() < 2 || group.last() != Some(&']')) {
+                return Err(error(ParseHandErrorKind::InvalidMeld(group.iter().collect()), pos));
+            }
+            let (offset, body) = if closed {
+                (pos + 1, &group[1..group.len() - 1])
+            } else {
+                (pos, group)
+            };
+            let marked = Hand::parse_marked_tiles(body, offset)?;
+            all.extend(marked.iter().map(|&(p, _, t)| (p, t)));
+            if n == 0 && !closed {
+                // 手牌には記号を付けない（記号は牌の直前にある）
+                if let Some(&(p, Some(m), _)) = marked.iter().find(|(_, m, _)| m.is_some()) {
+                    return Err(error(ParseHandErrorKind::UnexpectedMark(m), p - 1));
                 }
-                3 => {
-                    opened.push(OpenTiles {
-                        tiles: [
-                            *tile_vec.get(0).unwrap(),
-                            *tile_vec.get(1).unwrap(),
-                            *tile_vec.get(2).unwrap(),
-                        ],
-                        category: if *tile_vec.get(0).unwrap() == *tile_vec.get(1).unwrap() {
-                            OpenType::Pon
-                        } else {
-                            OpenType::Chi
-                        },
-                        from: OpenFrom::Unknown,
-                    });
+                hand = marked.into_iter().map(|(_, _, t)| t).collect();
+                continue;
+            }
+            let marked: Vec<(Option<char>, Tile)> =
+                marked.into_iter().map(|(_, m, t)| (m, t)).collect();
+            if marked.len() == 1 && !closed && marked[0].0.is_none() {
+                drawn = Some(marked[0].1);
+                continue;
+            }
+            if !Hand::is_valid_open(&marked, closed) {
+                return Err(error(ParseHandErrorKind::InvalidMeld(group.iter().collect()), pos));
+            }
+            match Hand::make_open(&marked, closed) {
+                Some(open) => opened.push(open),
+                None => {
+                    return Err(error(ParseHandErrorKind::InvalidMeld(group.iter().collect()), pos));
                 }
-                4 => {
-                    opened.push(OpenTiles {
-                        tiles: [
-                            *tile_vec.get(0).unwrap(),
-                            *tile_vec.get(1).unwrap(),
-                            *tile_vec.get(2).unwrap(),
-                        ],
-                        category: OpenType::Kan,
-                        from: OpenFrom::Unknown,
-                    });
+            }
+        }
+
+        let mut counts = [0; Tile::LEN];
+        for (p, t) in &all {
+            counts[t.get() as usize] += 1;
+            if counts[t.get() as usize] > 4 {
+                return Err(error(ParseHandErrorKind::TooManyCopies(*t), *p));
+            }
+        }
+
+        let size = hand.len() + opened.len() * 3;
+        if size + drawn.iter().count() != 14 && !(drawn.is_none() && size == 13) {
+            return Err(error(
+                ParseHandErrorKind::WrongHandSize {
+                    tiles: hand.len(),
+                    melds: opened.len(),
+                },
+                0,
+            ));
+        }
+        return Ok(Hand::new_with_opened(hand, opened, drawn));
+    }
+
+    /// 文字列の一部から、位置と直前に付いた記号と牌の組を返す（`offset`は先頭の位置）
+    fn parse_marked_tiles(
+        chars: &[char],
+        offset: usize,
+    ) -> core::result::Result<Vec<(usize, Option<char>, Tile)>, ParseHandError> {
+        let error = |kind: ParseHandErrorKind, position: usize| ParseHandError { kind, position };
+        let mut result: Vec<(usize, Option<char>, Tile)> = Vec::new();
+        let mut stack: Vec<(usize, Option<char>, char)> = Vec::new();
+        let mut mark: Option<(usize, char)> = None;
+        for (i, &c) in chars.iter().enumerate() {
+            let pos = offset + i;
+            if matches!(c, '<' | '^' | '>' | '+') {
+                if let Some((p, m)) = mark {
+                    return Err(error(ParseHandErrorKind::UnexpectedMark(m), p));
                 }
-                _ => {}
+                mark = Some((pos, c));
+            } else if c.is_ascii_digit() {
+                stack.push((pos, mark.take().map(|(_, m)| m), c));
+            } else if matches!(c, 'm' | 'p' | 's' | 'z') {
+                if let Some((p, m)) = mark {
+                    return Err(error(ParseHandErrorKind::UnexpectedMark(m), p));
+                }
+                if stack.is_empty() {
+                    return Err(error(ParseHandErrorKind::InvalidChar(c), pos));
+                }
+                for (p, m, d) in stack.drain(..) {
+                    let name = format!("{}{}", d, c);
+                    // `0z`・`8z`・`9z`は存在しない
+                    match name.parse::<Tile>() {
+                        Ok(t) => result.push((p, m, t)),
+                        Err(_) => return Err(error(ParseHandErrorKind::InvalidTile(name), p)),
+                    }
+                }
+            } else {
+                return Err(error(ParseHandErrorKind::InvalidChar(c), pos));
             }
         }
-        return Hand::new_with_opened(hand, opened, drawn);
+        if let Some((p, m)) = mark {
+            return Err(error(ParseHandErrorKind::UnexpectedMark(m), p));
+        }
+        if let Some((p, _, _)) = stack.first() {
+            return Err(error(ParseHandErrorKind::MissingSuit, *p));
+        }
+        return Ok(result);
+    }
+
+    /// 記号の付いた牌が副露として正しい形か
+    ///
+    /// チー（上家から）・ポン・カンの形で、誰から鳴いたかの記号は1つまで、`+`はカンのみ1つまで、
+    /// 暗カンには記号を付けない。
+    fn is_valid_open(marked: &[(Option<char>, Tile)], closed: bool) -> bool {
+        let mut kinds: Vec<TileType> = marked.iter().map(|(_, t)| t.get()).collect();
+        kinds.sort();
+        let directions: Vec<char> = marked
+            .iter()
+            .filter_map(|(m, _)| m.filter(|c| *c != '+'))
+            .collect();
+        let adds = marked.iter().filter(|(m, _)| *m == Some('+')).count();
+        if directions.len() > 1 || (closed && (adds > 0 || !directions.is_empty())) {
+            return false;
+        }
+        let same = kinds.iter().all(|k| *k == kinds[0]);
+        match kinds.len() {
+            3 if same => adds == 0,
+            3 => {
+                kinds[0] < Tile::Z1
+                    && kinds[0] / 9 == kinds[2] / 9
+                    && kinds[1] == kinds[0] + 1
+                    && kinds[2] == kinds[0] + 2
+                    && adds == 0
+                    && directions.iter().all(|c| *c == '<')
+            }
+            4 => same && adds <= 1,
+            _ => false,
+        }
     }
 
     pub fn from_summarized(sum: &TileSummarize) -> Hand {
@@ -234,13 +601,151 @@ impl Hand {
         return Hand::new(result, None);
     }
 }
+/// 文字列として出力する
+///
+/// `to_short_string`と違い、こちらは牌の種類を省略せずに`1m2m3m1p2p3p...`と必ず2文字単位で出力する。
+impl core::fmt::Display for Hand {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for tile in &self.tiles {
+            write!(f, "{}", tile)?;
+        }
+        for open in &self.opened {
+            write!(f, " {}", Hand::open_to_str(open, false))?;
+        }
+        if let Some(tsumo) = self.drawn {
+            write!(f, " {}", tsumo)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::str::FromStr for Hand {
+    type Err = ParseHandError;
+
+    fn from_str(s: &str) -> core::result::Result<Hand, ParseHandError> {
+        Hand::parse(s)
+    }
+}
+
+/// 手牌の文字列を読み込めなかった理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseHandErrorKind {
+    /// 牌・記号として読めない文字
+    InvalidChar(char),
+    /// 存在しない牌（`8z`・`0z`など）
+    InvalidTile(String),
+    /// 種類（`m`・`p`・`s`・`z`）が付いていない数字
+    MissingSuit,
+    /// 置けない場所にある記号（手牌の中や、牌の付いていない記号など）
+    UnexpectedMark(char),
+    /// 副露として正しくない形（副露の部分をそのまま持つ）
+    InvalidMeld(String),
+    /// 同じ牌が5枚以上ある（5枚目の牌）
+    TooManyCopies(Tile),
+    /// 手牌の枚数が副露の数と合わない
+    WrongHandSize { tiles: usize, melds: usize },
+}
+
+/// 手牌の文字列を読み込めなかった時のエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHandError {
+    /// 理由
+    pub kind: ParseHandErrorKind,
+    /// 問題のある文字の位置（先頭から何文字目か、0始まり）
+    pub position: usize,
+}
+
+impl core::fmt::Display for ParseHandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match &self.kind {
+            ParseHandErrorKind::InvalidChar(c) => write!(f, "Invalid character '{}'", c)?,
+            ParseHandErrorKind::InvalidTile(t) => write!(f, "Invalid tile: {}", t)?,
+            ParseHandErrorKind::MissingSuit => write!(f, "Missing suit after number")?,
+            ParseHandErrorKind::UnexpectedMark(c) => write!(f, "Unexpected mark '{}'", c)?,
+            ParseHandErrorKind::InvalidMeld(m) => write!(f, "Invalid meld: {}", m)?,
+            ParseHandErrorKind::TooManyCopies(t) => {
+                write!(f, "More than 4 copies of {}", t)?
+            }
+            ParseHandErrorKind::WrongHandSize { tiles, melds } => {
+                write!(f, "Wrong number of tiles: {} tiles with {} melds", tiles, melds)?
+            }
+        }
+        write!(f, " at {}", self.position)
+    }
+}
+
+impl core::error::Error for ParseHandError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
+    use crate::test_util::arb_tiles;
+    use proptest::prelude::*;
+
+    /// 13枚の手牌を作り、`drawn`なら14枚目をツモった牌とし、`pon`なら先頭の対子を5枚目にならない限りポンする
+    fn make_hand(mut tiles: Vec<Tile>, drawn: bool, pon: bool) -> Hand {
+        let last = tiles.pop().unwrap();
+        tiles.sort();
+        let mut hand = Hand::new(tiles, drawn.then_some(last));
+        let t = hand.tiles[0];
+        if pon && hand.tiles[1] == t && hand.summarize_tiles()[t.get() as usize] < 4 {
+            hand.call(&[t.get(), t.get()], t, OpenType::Pon, OpenFrom::Opposite)
+                .unwrap();
+        }
+        return hand;
+    }
+
+    proptest! {
+        #[test]
+        /// `to_short_string`で書き出して`parse_lossy`で読み直すと（手牌を並べ替えた）同じ手牌になる
+        fn short_string_round_trip(tiles in arb_tiles(14), drawn: bool, pon: bool) {
+            let mut hand = make_hand(tiles, drawn, pon);
+            let short = hand.to_short_string();
+            hand.sort();
+            prop_assert_eq!(Hand::parse_lossy(&short), hand, "{}", short);
+        }
+
+        #[test]
+        /// `summarize_tiles`の合計は手牌・副露・ツモった牌の枚数の和になる
+        fn summarize_adds_up_to_hand_size(tiles in arb_tiles(14), drawn: bool, pon: bool) {
+            let hand = make_hand(tiles, drawn, pon);
+            let len = hand.tiles.len() + hand.opened.len() * 3 + hand.drawn.iter().count();
+            prop_assert_eq!(hand.summarize_tiles().iter().sum::<u32>() as usize, len);
+            prop_assert_eq!(len, if drawn { 14 } else { 13 } + hand.opened.len());
+        }
+    }
+
+    #[test]
+    fn call_test() {
+        let mut test = Hand::parse_lossy("1123m456p789s123z");
+        test.call(&[Tile::M1, Tile::M1], Tile::new(Tile::M1), OpenType::Pon, OpenFrom::Opposite)
+            .unwrap();
+        assert_eq!(test.to_string(), "2m3m4p5p6p7s8s9s1z2z3z ^1m1m1m");
+        assert!(test
+            .call(&[Tile::M2, Tile::M4], Tile::new(Tile::M3), OpenType::Chi, OpenFrom::Previous)
+            .is_err());
+        assert_eq!(test.to_string(), "2m3m4p5p6p7s8s9s1z2z3z ^1m1m1m");
+        test.set_drawn(Some(Tile::new(Tile::M1)));
+        test.added_kan(Tile::M1).unwrap();
+        assert_eq!(test.opened[0].category, OpenType::Kan);
+        assert_eq!(test.to_string(), "2m3m4p5p6p7s8s9s1z2z3z ^1m1m1m+1m");
+    }
+
+    #[test]
+    fn closed_kan_test() {
+        let mut test = Hand::parse_lossy("1112m456p789s123z 1m");
+        test.closed_kan(Tile::M1).unwrap();
+        assert_eq!(test.drawn, None);
+        assert_eq!(test.opened[0].from, OpenFrom::Myself);
+        assert_eq!(test.to_string(), "2m4p5p6p7s8s9s1z2z3z [1m1m1m1m]");
+        assert!(test.closed_kan(Tile::M2).is_err());
+    }
+
     #[test]
     fn summarize_test() {
         let test_str = "111m456p789s123z 4z";
-        let test_hand = Hand::from(test_str);
+        let test_hand = Hand::parse_lossy(test_str);
         let test = test_hand.summarize_tiles();
         let answer = [
             3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1,
@@ -284,7 +789,7 @@ mod tests {
     #[test]
     fn from_with_no_opened_test() {
         let test_str = "123m456p789s1115z 5z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(test.tiles[0], Tile::new(Tile::M1));
         assert_eq!(test.drawn, Some(Tile::new(Tile::Z5)));
         assert_eq!(test.to_short_string(), test_str);
@@ -293,7 +798,7 @@ mod tests {
     #[test]
     fn from_with_chi_test() {
         let test_str = "123m456p1115z 789s 5z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(test.tiles[0], Tile::new(Tile::M1));
         assert_eq!(test.opened[0].category, OpenType::Chi);
         assert_eq!(
@@ -312,7 +817,7 @@ mod tests {
     #[test]
     fn from_with_pon_test() {
         let test_str = "123m456p789s5z 111z 5z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(test.tiles[0], Tile::new(Tile::M1));
         assert_eq!(test.opened[0].category, OpenType::Pon);
         assert_eq!(
@@ -331,7 +836,7 @@ mod tests {
     #[test]
     fn from_with_kan_test() {
         let test_str = "123m456p789s5z 1111z 5z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(test.tiles[0], Tile::new(Tile::M1));
         assert_eq!(test.opened[0].category, OpenType::Kan);
         assert_eq!(
@@ -346,4 +851,195 @@ mod tests {
         assert_eq!(test.drawn, Some(Tile::new(Tile::Z5)));
         assert_eq!(test.to_short_string(), test_str);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_test() {
+        let mut test = Hand::parse_lossy("123m4p6p789s1115z 5z");
+        test.tiles[3] = Tile::new_red(Tile::P5);
+        test.call(&[Tile::Z1, Tile::Z1], Tile::new(Tile::Z1), OpenType::Pon, OpenFrom::Previous)
+            .unwrap();
+        let json = serde_json::to_string(&test).unwrap();
+        assert_eq!(
+            json,
+            r#"{"tiles":["1m","2m","3m","0p","6p","7s","8s","9s","1z","5z"],"opened":[{"tiles":["1z","1z","1z"],"category":"Pon","from":"Previous","called":"1z","added":false}],"drawn":"5z"}"#
+        );
+        let back: Hand = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.tiles, test.tiles);
+        assert_eq!(back.opened[0].from, OpenFrom::Previous);
+        assert_eq!(back.drawn, test.drawn);
+        assert!(serde_json::from_str::<Tile>(r#""0z""#).is_err());
+    }
+
+    #[test]
+    fn from_with_marked_opened_test() {
+        let test_str = "1m2p <3s4s5s 4z^4z4z [7z7z7z7z] 2m>2m2m2m 5p^5p5p+5p 1m";
+        let test = Hand::parse_lossy(test_str);
+        assert_eq!(test.opened[0].category, OpenType::Chi);
+        assert_eq!(test.opened[0].from, OpenFrom::Previous);
+        assert_eq!(test.opened[0].called, Some(Tile::new(Tile::S3)));
+        assert_eq!(test.opened[1].category, OpenType::Pon);
+        assert_eq!(test.opened[1].from, OpenFrom::Opposite);
+        assert_eq!(test.opened[2].category, OpenType::Kan);
+        assert_eq!(test.opened[2].from, OpenFrom::Myself);
+        assert_eq!(test.opened[2].called, None);
+        assert_eq!(test.opened[3].category, OpenType::Kan);
+        assert_eq!(test.opened[3].from, OpenFrom::Following);
+        assert!(!test.opened[3].added);
+        assert_eq!(test.opened[4].from, OpenFrom::Opposite);
+        assert!(test.opened[4].added);
+        assert_eq!(test.drawn, Some(Tile::new(Tile::M1)));
+        // 記号は鳴いた牌の前に移る
+        assert_eq!(
+            test.to_string(),
+            "1m2p <3s4s5s ^4z4z4z [7z7z7z7z] >2m2m2m2m ^5p5p5p+5p 1m"
+        );
+        assert_eq!(
+            test.to_short_string(),
+            "1m2p <345s ^444z [7777z] >2222m ^555+5p 1m"
+        );
+    }
+
+    #[test]
+    fn marked_round_trip_test() {
+        let mut test = Hand::parse_lossy("23455m 4z");
+        test.call(&[Tile::M3, Tile::M5], Tile::new(Tile::M4), OpenType::Chi, OpenFrom::Previous)
+            .unwrap();
+        for s in [test.to_string(), test.to_short_string()] {
+            let back = Hand::parse_lossy(&s);
+            assert_eq!(back.to_string(), test.to_string());
+            assert_eq!(back.to_short_string(), test.to_short_string());
+            assert_eq!(back.opened[0].called, Some(Tile::new(Tile::M4)));
+        }
+        assert_eq!(test.to_short_string(), "245m 3<45m 4z");
+    }
+
+    #[test]
+    fn make_short_str_single_test() {
+        assert_eq!(Hand::make_short_str(vec![Tile::new(Tile::P3)]), "3p");
+        let test = Hand::parse_lossy("1m [1111z] [2222z] [3333z] [4444z] 1m");
+        assert_eq!(test.to_short_string(), "1m [1111z] [2222z] [3333z] [4444z] 1m");
+    }
+
+    #[test]
+    fn parse_test() {
+        let test_str = "123m456p789s1z <345s 5z";
+        let test: Hand = test_str.parse().unwrap();
+        assert_eq!(test.to_short_string(), test_str);
+        assert_eq!(test.opened[0].from, OpenFrom::Previous);
+        let test = Hand::parse("123m456p789s1115z").unwrap();
+        assert_eq!(test.tiles.len(), 13);
+        assert!(Hand::parse("123m456p789s11155z").is_ok());
+        assert!(Hand::parse("1m [1111z] [2222z] ^3z3z3z3z 4z4z+4z4z 1m").is_ok());
+    }
+
+    /// 鳴き・カンでも赤ドラは赤ドラのまま残る
+    #[test]
+    fn red_five_in_melds() {
+        let reds = |hand: &Hand| hand.iter().filter(|t| t.is_red_dora()).count();
+
+        let mut test = Hand::parse("0555m123p456s789s 1z").unwrap();
+        test.closed_kan(Tile::M5).unwrap();
+        assert_eq!(reds(&test), 1);
+        assert_eq!(test.to_string(), "1p2p3p4s5s6s7s8s9s1z [5m5m0m5m]");
+        assert_eq!(Hand::parse(&test.to_string()).unwrap().opened(), test.opened());
+
+        let mut test = Hand::parse("055p123m456s789s1z").unwrap();
+        test.call(&[Tile::P5, Tile::P5], Tile::new(Tile::P5), OpenType::Kan, OpenFrom::Previous)
+            .unwrap();
+        assert_eq!(reds(&test), 1);
+
+        let mut test = Hand::parse("55s123m456p789p11z").unwrap();
+        test.call(&[Tile::S5, Tile::S5], Tile::new(Tile::S5), OpenType::Pon, OpenFrom::Opposite)
+            .unwrap();
+        test.set_drawn(Some(Tile::new_red(Tile::S5)));
+        test.added_kan(Tile::S5).unwrap();
+        assert_eq!(reds(&test), 1);
+        assert_eq!(test.opened()[0].tiles[2], Tile::new_red(Tile::S5));
+    }
+
+    /// 赤ドラの五は`0m`・`0p`・`0s`と書き、文字列にして読み直しても赤ドラのまま
+    #[test]
+    fn red_five_test() {
+        let test = Hand::parse("340m456p789s1115z 0s").unwrap();
+        assert_eq!(test.tiles[2], Tile::new_red(Tile::M5));
+        assert_eq!(test.drawn, Some(Tile::new_red(Tile::S5)));
+        assert_eq!(Hand::parse(&test.to_string()).unwrap(), test);
+        assert_eq!(Hand::parse_lossy(&test.to_short_string()), test);
+        assert_eq!(
+            Hand::parse("5555m0m").unwrap_err().kind,
+            ParseHandErrorKind::TooManyCopies(Tile::new_red(Tile::M5))
+        );
+        assert_eq!(
+            Hand::parse("123m0z").unwrap_err().kind,
+            ParseHandErrorKind::InvalidTile("0z".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let err = |s: &str| Hand::parse(s).unwrap_err();
+        assert_eq!(
+            err("123m456p789s1118z 5z"),
+            ParseHandError {
+                kind: ParseHandErrorKind::InvalidTile("8z".to_string()),
+                position: 15,
+            }
+        );
+        assert_eq!(err("123m456x").kind, ParseHandErrorKind::InvalidChar('x'));
+        assert_eq!(err("123m456x").position, 7);
+        assert_eq!(err("123m456p789s111 5z").kind, ParseHandErrorKind::MissingSuit);
+        assert_eq!(err("123m456p789s111 5z").position, 12);
+        assert_eq!(err("1<23m456p789s111z 5z").kind, ParseHandErrorKind::UnexpectedMark('<'));
+        assert_eq!(err("1<23m456p789s111z 5z").position, 1);
+        assert_eq!(err("123m456p1111z 5z <78m 5z").position, 17);
+        // 形の正しくない副露
+        for meld in ["<135s", "^123s", "<123z", "<1m1m", "[<1111m]", "<1m1m+1m", "<1^1m1m", "1122m"] {
+            let e = err(&format!("123m456p1111z {} 5z", meld));
+            assert_eq!(e.kind, ParseHandErrorKind::InvalidMeld(meld.to_string()), "{}", meld);
+            assert_eq!(e.position, 14);
+        }
+        // 副露も含めて5枚目
+        let e = err("1111m456p789s22z ^111m 3z");
+        assert_eq!(e.kind, ParseHandErrorKind::TooManyCopies(Tile::new(Tile::M1)));
+        assert_eq!(e.position, 18);
+        assert_eq!(
+            err("123m456p789s <345s").kind,
+            ParseHandErrorKind::WrongHandSize { tiles: 9, melds: 1 }
+        );
+        assert!(err("123m456p789s1115z 5z 5z").to_string().ends_with("at 21"));
+    }
+
+    #[test]
+    fn display_test() {
+        let hand = Hand::parse_lossy("123m456p789s1z <345s 2z");
+        assert_eq!(hand.to_string(), "1m2m3m4p5p6p7s8s9s1z <3s4s5s 2z");
+        assert_eq!(format!("{}", hand), hand.to_string());
+        let parsed: Hand = hand.to_string().parse().unwrap();
+        assert_eq!(parsed, hand);
+    }
+
+    #[test]
+    fn accessors_test() {
+        let hand = Hand::parse_lossy("123m456p11z <345s [7777z] 2z");
+        assert_eq!(hand.tiles().len(), 8);
+        assert_eq!(hand.opened().len(), 2);
+        assert_eq!(hand.opened()[1].category, OpenType::Kan);
+        let all: Vec<Tile> = hand.iter().collect();
+        assert_eq!(all.len(), 8 + 3 + 4 + 1);
+        assert_eq!(all[8], Tile::new(Tile::S3));
+        assert_eq!(all.iter().filter(|t| t.get() == Tile::Z7).count(), 4);
+        assert_eq!(all.last(), Some(&Tile::new(Tile::Z2)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn eq_hash_test() {
+        let mut set = std::collections::HashSet::new();
+        set.insert(Hand::parse_lossy("123m456p789s1122z"));
+        set.insert(Hand::parse_lossy("123m456p789s1122z"));
+        set.insert(Hand::parse_lossy("123m456p789s1122z 3z"));
+        assert_eq!(set.len(), 2);
+        assert_ne!(Hand::parse_lossy("123m456p789s1122z"), Hand::parse_lossy("123m456p789s1133z"));
+    }
 }
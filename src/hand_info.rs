@@ -1,4 +1,5 @@
 pub mod opened;
 pub mod hand_analyzer;
+pub mod shanten;
 pub mod block;
 pub mod status;
\ No newline at end of file
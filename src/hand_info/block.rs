@@ -1,7 +1,7 @@
-use anyhow::anyhow;
-use anyhow::Result;
-use std::cmp::Ordering;
+use alloc::vec;
+use core::cmp::Ordering;
 
+use crate::error::{Error, Result};
 use crate::tile::*;
 
 /// ブロック（対子、順子、刻子）の振る舞いを定義する
@@ -26,7 +26,7 @@ fn is_proper_tile(tile: TileType) -> Result<()> {
     if matches!(tile, Tile::M1..=Tile::Z7) {
         Ok(())
     } else {
-        Err(anyhow!("invalid tile: {}", tile))
+        Err(Error::InvalidTile(tile))
     }
 }
 
@@ -98,11 +98,12 @@ fn is_same_suit(t1: TileType, t2: TileType) -> Result<bool> {
         Tile::P1..=Tile::P9 => Ok(matches!(t2, Tile::P1..=Tile::P9)),
         Tile::S1..=Tile::S9 => Ok(matches!(t2, Tile::S1..=Tile::S9)),
         Tile::Z1..=Tile::Z7 => Ok(matches!(t2, Tile::Z1..=Tile::Z7)),
-        _ => Err(anyhow!("invalid tile: {}", t1)),
+        _ => Err(Error::InvalidTile(t1)),
     }
 }
 
 #[derive(Debug, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// 対子（同じ2枚）
 pub struct Same2 {
     tiles: [TileType; 2],
@@ -112,7 +113,10 @@ impl Same2 {
         is_proper_tile(tile1)?;
         is_proper_tile(tile2)?;
         if tile1 != tile2 {
-            return Err(anyhow!("Not same tiles in `Same2`: {}, {} !", tile1, tile2));
+            return Err(Error::NotSameTiles {
+                block: "Same2",
+                tiles: vec![tile1, tile2],
+            });
         }
 
         Ok(Same2 {
@@ -179,6 +183,7 @@ impl BlockProperty for Same2 {
 }
 
 #[derive(Debug, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// 刻子（同じ3枚）
 pub struct Same3 {
     tiles: [TileType; 3],
@@ -189,7 +194,10 @@ impl Same3 {
         is_proper_tile(tile2)?;
         is_proper_tile(tile3)?;
         if tile1 != tile2 || tile1 != tile3 {
-            return Err(anyhow!("Not same tiles in `Same3`: {}, {}, {}!", tile1, tile2, tile3));
+            return Err(Error::NotSameTiles {
+                block: "Same3",
+                tiles: vec![tile1, tile2, tile3],
+            });
         }
 
 
@@ -259,6 +267,7 @@ impl BlockProperty for Same3 {
 }
 
 #[derive(Debug, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// 塔子（連続した牌が2枚）もしくは嵌張（順子の真ん中が抜けている2枚）
 pub struct Sequential2 {
     tiles: [TileType; 2],
@@ -269,15 +278,24 @@ impl Sequential2 {
         is_proper_tile(tile2)?;
         // まず連続でなければパニック
         if !(tile2 == tile1 + 1 || tile2 == tile1 + 2) {
-            return Err(anyhow!("Not sequential tiles in `Sequential2`: {}, {} !",tile1, tile2));
+            return Err(Error::NotSequentialTiles {
+                block: "Sequential2",
+                tiles: vec![tile1, tile2],
+            });
         }
 
         // 字牌は順子にならない
         if has_honor(tile1)? || has_honor(tile2)? {
-            return Err(anyhow!("Cannot assign Honor tiles to `Sequential2`: {}, {} !",tile1, tile2));
+            return Err(Error::HonorInSequence {
+                block: "Sequential2",
+                tiles: vec![tile1, tile2],
+            });
         }
         if !is_same_suit(tile1, tile2)? {
-            return Err(anyhow!("Cannot assign different suits to `Sequential2`: {}, {} !",tile1, tile2));
+            return Err(Error::MixedSuits {
+                block: "Sequential2",
+                tiles: vec![tile1, tile2],
+            });
         }
         Ok(Sequential2 {
             tiles: [tile1, tile2],
@@ -340,6 +358,7 @@ impl BlockProperty for Sequential2 {
 }
 
 #[derive(Debug, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// 順子（連続した3枚）
 pub struct Sequential3 {
     tiles: [TileType; 3],
@@ -353,16 +372,25 @@ impl Sequential3 {
 
         // 連続でなければエラー
         if tile2 != tile1 + 1 || tile3 != tile2 + 1 {
-            return Err(anyhow!("Not sequential tiles in `Sequential3`:{}, {}, {} !",tile1, tile2, tile3));
+            return Err(Error::NotSequentialTiles {
+                block: "Sequential3",
+                tiles: vec![tile1, tile2, tile3],
+            });
         }
 
         // 字牌は順子にならない
         if has_honor(tile1)? || has_honor(tile2)? || has_honor(tile3)? {
-            return Err(anyhow!("Cannot assign Honor tiles to `Sequential3`: {}, {}, {} !",tile1, tile2, tile3));
+            return Err(Error::HonorInSequence {
+                block: "Sequential3",
+                tiles: vec![tile1, tile2, tile3],
+            });
         }
 
         if !is_same_suit(tile1, tile2)? || !is_same_suit(tile2, tile3)? {
-            return Err(anyhow!("Cannot assign different suits to `Sequential3`: {}, {}, {} !",tile1, tile2, tile3));
+            return Err(Error::MixedSuits {
+                block: "Sequential3",
+                tiles: vec![tile1, tile2, tile3],
+            });
         }
         Ok(Sequential3 {
             tiles: [tile1, tile2, tile3],
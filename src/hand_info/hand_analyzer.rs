@@ -1,8 +1,7 @@
-use anyhow::Result;
-use anyhow::anyhow;
-
-use std::cmp::*;
+use alloc::vec::Vec;
+use core::cmp::*;
 
+use crate::error::{Error, Result};
 use crate::hand::Hand;
 use crate::hand_info::block::*;
 use crate::tile::*;
@@ -12,7 +11,8 @@ use crate::winning_hand::name::Form;
 ///
 /// 通常形・七対子の場合は面子・対子等の情報もVecに格納される。
 /// 国士無双の場合は（今のところ）向聴数のみが格納される。
-#[derive(Debug, Eq)]
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandAnalyzer {
     /// 向聴数：あと牌を何枚交換すれば聴牌できるかの最小数。聴牌状態が`0`、和了が`-1`。
     pub shanten: i32,
@@ -58,7 +58,7 @@ impl HandAnalyzer {
     ///
     /// // 通常型で和了る
     /// let nm_test_str = "222333444666s6z 6z";
-    /// let nm_test = Hand::from(nm_test_str);
+    /// let nm_test = Hand::parse_lossy(nm_test_str);
     /// let analyzer = HandAnalyzer::new(&nm_test).unwrap();
     /// assert_eq!(
     ///   analyzer.shanten,
@@ -86,7 +86,7 @@ impl HandAnalyzer {
     ///
     /// // 国士無双で和了る
     /// let to_test_str = "19m19p19s1234567z 1m";
-    /// let to_test = Hand::from(to_test_str);
+    /// let to_test = Hand::parse_lossy(to_test_str);
     /// assert_eq!(
     ///   HandAnalyzer::new_by_form(&to_test, Form::ThirteenOrphens).unwrap().shanten,
     ///   -1
@@ -94,7 +94,7 @@ impl HandAnalyzer {
     ///
     /// // 七対子で和了る
     /// let sp_test_str = "1122m3344p5566s7z 7z";
-    /// let sp_test = Hand::from(sp_test_str);
+    /// let sp_test = Hand::parse_lossy(sp_test_str);
     /// assert_eq!(
     ///   HandAnalyzer::new_by_form(&sp_test, Form::SevenPairs).unwrap().shanten,
     ///   -1
@@ -102,7 +102,7 @@ impl HandAnalyzer {
     ///
     /// // 通常型で和了る
     /// let nm_test_str = "1112345678999m 5m";
-    /// let nm_test = Hand::from(nm_test_str);
+    /// let nm_test = Hand::parse_lossy(nm_test_str);
     /// assert_eq!(
     ///   HandAnalyzer::new_by_form(&nm_test, Form::Normal).unwrap().shanten,
     ///   -1
@@ -136,13 +136,11 @@ impl HandAnalyzer {
                 }
             }
         }
-        let num_to_win: i32 = (7 - pair + if kind < 7 { 7 - kind } else { 0 }) as i32;
+        let num_to_win: i32 = (7 - pair + 7_u32.saturating_sub(kind)) as i32;
         let mut single: Vec<TileType> = Vec::new();
         for i in 0..Tile::LEN {
-            if t[i] > 0 {
-                for _ in 0..i {
-                    single.push(i as TileType);
-                }
+            for _ in 0..t[i] {
+                single.push(i as TileType);
             }
         }
         Ok(HandAnalyzer {
@@ -181,7 +179,7 @@ impl HandAnalyzer {
 
         for i in &to_tiles {
             if t[*i as usize] > 0 {
-                kind = kind + 1;
+                kind += 1;
                 if t[*i as usize] >= 2 {
                     pair += 1;
                 }
@@ -339,7 +337,7 @@ impl HandAnalyzer {
                     }
                 }
                 _ => {
-                    return Err(anyhow!("Unknown tile index found!"));
+                    return Err(Error::UnknownTileIndex(i));
                 }
             }
         }
@@ -459,7 +457,7 @@ impl HandAnalyzer {
                     }
                 }
                 _ => {
-                    return Err(anyhow!("Unknown tile index found!"));
+                    return Err(Error::UnknownTileIndex(i));
                 }
             }
         }
@@ -470,6 +468,27 @@ impl HandAnalyzer {
 pub fn has_won(hand: &HandAnalyzer) -> bool {
     hand.shanten == -1
 }
+
+/// 和了牌（あと1枚で和了の形になる牌）を返す
+///
+/// ツモった牌は無視して、それ以外の手牌に対する和了牌を求める。
+/// 手牌で4枚使っている牌であっても、形の上で和了になるなら含まれる。
+pub fn get_winning_tiles(hand: &Hand) -> Result<Vec<TileType>> {
+    let mut result: Vec<TileType> = Vec::new();
+    let mut test_hand = hand.clone();
+    // 聴牌していなければ和了牌はない
+    test_hand.set_drawn(None);
+    if HandAnalyzer::new(&test_hand)?.shanten > 0 {
+        return Ok(result);
+    }
+    for i in Tile::M1..=Tile::Z7 {
+        test_hand.set_drawn(Some(Tile::new(i)));
+        if has_won(&HandAnalyzer::new(&test_hand)?) {
+            result.push(i);
+        }
+    }
+    Ok(result)
+}
 /// 再帰的にシャンテン数が最小のものを探す
 fn count_normal_shanten_recursively(
     idx: TileType,
@@ -488,6 +507,15 @@ fn count_normal_shanten_recursively(
     sequential2_result: &mut Vec<Sequential2>,
     single_result: &mut Vec<TileType>,
 ) -> Result<i32> {
+    // 残りの牌で最小値を更新できなければ探索しない
+    let block3 =
+        independent_same3.len() + independent_sequential3.len() + same3.len() + sequential3.len();
+    let rest: usize = summarized_hand.iter().sum::<u32>() as usize;
+    let has_head = !same2.is_empty();
+    let block2 = same2.len() + sequential2.len() - has_head as usize;
+    if normal_shanten_bound(block3, block2, has_head, rest) >= *shanten_min {
+        return Ok(*shanten_min);
+    }
     count_same_or_sequential_3(
         idx,
         independent_same3,
@@ -588,9 +616,9 @@ fn count_same_or_sequential_3(
         }
 
         //順子カウント
-        if ((i >= Tile::M1 && i <= Tile::M7)
-            || (i >= Tile::P1 && i <= Tile::P7)
-            || (i >= Tile::S1 && i <= Tile::S7))
+        if ((Tile::M1..=Tile::M7).contains(&i)
+            || (Tile::P1..=Tile::P7).contains(&i)
+            || (Tile::S1..=Tile::S7).contains(&i))
             && summarized_hand[i as usize] >= 1
             && summarized_hand[i as usize + 1] >= 1
             && summarized_hand[i as usize + 2] >= 1
@@ -648,7 +676,7 @@ fn count_same_or_sequential_2(
             same2.push(Same2::new(i, i)?);
             summarized_hand[i as usize] -= 2;
             *shanten_min = count_normal_shanten_recursively(
-                idx,
+                i,
                 independent_same3,
                 independent_sequential3,
                 same3,
@@ -666,18 +694,15 @@ fn count_same_or_sequential_2(
             summarized_hand[i as usize] += 2;
             same2.pop();
         }
-        //数牌
-        if i <= Tile::S9 && (i >= Tile::M1 && i <= Tile::M7)
-            || (i >= Tile::P1 && i <= Tile::P7)
-            || (i >= Tile::S1 && i <= Tile::S7)
-        {
+        //数牌（塔子は八*まで、嵌張は七*までが始まりになる）
+        if i <= Tile::S9 && i % 9 <= 7 {
             // 塔子
             if summarized_hand[i as usize] >= 1 && summarized_hand[i as usize + 1] >= 1 {
                 sequential2.push(Sequential2::new(i, i + 1)?);
                 summarized_hand[i as usize] -= 1;
                 summarized_hand[i as usize + 1] -= 1;
                 *shanten_min = count_normal_shanten_recursively(
-                    idx,
+                    i,
                     independent_same3,
                     independent_sequential3,
                     same3,
@@ -697,7 +722,8 @@ fn count_same_or_sequential_2(
                 sequential2.pop();
             }
             //嵌張
-            if summarized_hand[i as usize] >= 1
+            if i % 9 <= 6
+                && summarized_hand[i as usize] >= 1
                 && summarized_hand[i as usize + 1] == 0
                 && summarized_hand[i as usize + 2] >= 1
             {
@@ -705,7 +731,7 @@ fn count_same_or_sequential_2(
                 summarized_hand[i as usize] -= 1;
                 summarized_hand[i as usize + 2] -= 1;
                 *shanten_min = count_normal_shanten_recursively(
-                    idx,
+                    i,
                     independent_same3,
                     independent_sequential3,
                     same3,
@@ -730,30 +756,50 @@ fn count_same_or_sequential_2(
     //return (same2, sequential2);
 }
 
+/// 今の面子・面子候補（雀頭は除く）に残りの`rest`枚を加えて到達できる向聴数の下限
+fn normal_shanten_bound(block3: usize, block2: usize, has_head: bool, rest: usize) -> i32 {
+    let mut bound = i32::MAX;
+    // 残りの牌から面子を`add3`個、面子候補を`add2`個、（なければ）雀頭を作る
+    for add3 in 0..=rest / 3 {
+        for add2 in 0..=(rest - add3 * 3) / 2 {
+            let head = has_head || rest - add3 * 3 - add2 * 2 >= 2;
+            let block3 = min(block3 + add3, 4);
+            let block2 = min(block2 + add2, 4 - block3);
+            bound = min(bound, 8 - (block3 * 2 + block2) as i32 - head as i32);
+        }
+    }
+    return bound;
+}
+
 fn calc_normal_shanten(
-    independent_same3: &Vec<Same3>,
-    independent_sequential3: &Vec<Sequential3>,
-    same3: &Vec<Same3>,
-    sequential3: &Vec<Sequential3>,
-    same2: &Vec<Same2>,
-    sequential2: &Vec<Sequential2>,
+    independent_same3: &[Same3],
+    independent_sequential3: &[Sequential3],
+    same3: &[Same3],
+    sequential3: &[Sequential3],
+    same2: &[Same2],
+    sequential2: &[Sequential2],
 ) -> i32 {
     let block3 =
         independent_same3.len() + independent_sequential3.len() + same3.len() + sequential3.len();
-    let block2 = same2.len() + sequential2.len();
-    return 8 - (block3 * 2 + block2) as i32;
+    // 対子があれば1つを雀頭とみなす
+    let head = if same2.is_empty() { 0 } else { 1 };
+    // 面子と面子候補は合わせて4つまでしか数えない
+    let block2 = min(same2.len() - head + sequential2.len(), 4 - min(block3, 4));
+    return 8 - (block3 * 2 + block2 + head) as i32;
 }
 
 /// ユニットテスト
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
+    use crate::hand_info::shanten::{normal_shanten, seven_pairs_shanten, shanten_of_summary};
 
     #[test]
     /// 七対子を聴牌
     fn zero_shanten_to_seven_pairs() {
         let test_str = "226699m99p228s66z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(
             HandAnalyzer::new_by_form(&test, Form::SevenPairs).unwrap().shanten,
             0
@@ -763,7 +809,7 @@ mod tests {
     /// 同じ牌が3枚ある状態で七対子を聴牌
     fn zero_shanten_to_seven_pairs_2() {
         let test_str = "226699m99p222s66z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(
             HandAnalyzer::new_by_form(&test, Form::SevenPairs).unwrap().shanten,
             0
@@ -773,7 +819,7 @@ mod tests {
     /// 国士無双を聴牌
     fn zero_shanten_to_orphens() {
         let test_str = "19m19p11s1234567z 5m";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(
             HandAnalyzer::new_by_form(&test, Form::ThirteenOrphens).unwrap().shanten,
             0
@@ -784,18 +830,65 @@ mod tests {
     /// 同じ牌が4枚ある状態で七対子は認められない（一向聴とみなす）
     fn seven_pairs_with_4_same_tiles() {
         let test_str = "1122m3344p5555s1z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(
             HandAnalyzer::new_by_form(&test, Form::SevenPairs).unwrap().shanten,
             1
         );
     }
 
+    #[test]
+    /// 七対子で対子にならなかった牌は1枚ずつ`single`に入る
+    fn seven_pairs_single() {
+        let test: Hand = "226699m99p222s66z 1z".parse().unwrap();
+        let analyzer = HandAnalyzer::new_by_form(&test, Form::SevenPairs).unwrap();
+        assert_eq!(analyzer.single, vec![Tile::S2, Tile::Z1]);
+    }
+
+    #[test]
+    /// 雀頭がない場合は4面子1塔子でも和了ではなく聴牌とみなす
+    fn zero_shanten_without_head() {
+        let test_str = "123456789m123p4p 5p";
+        let test = Hand::parse_lossy(test_str);
+        assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, 0);
+    }
+
+    #[test]
+    /// 八*九*の辺張も塔子として数える
+    fn zero_shanten_with_edge_wait() {
+        for test_str in ["123456m456p11z89s", "123456m456s11z89p", "123456p456s11z89m"] {
+            let test = Hand::parse_lossy(test_str);
+            assert_eq!(HandAnalyzer::new(&test).unwrap().shanten, 0, "{}", test_str);
+        }
+    }
+
+    #[test]
+    /// 対子が多すぎる場合は面子候補を4つまでしか数えない
+    fn too_many_pairs_to_normal_form() {
+        let test_str = "1122m3344p5566s7z";
+        let test = Hand::parse_lossy(test_str);
+        assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, 3);
+    }
+
+    #[test]
+    /// 和了牌を求める
+    fn winning_tiles_of_ready_hand() {
+        let test = Hand::parse_lossy("123m456p789s2345z");
+        assert_eq!(get_winning_tiles(&test).unwrap(), Vec::<TileType>::new());
+        let test = Hand::parse_lossy("1112345678999m");
+        assert_eq!(
+            get_winning_tiles(&test).unwrap(),
+            (Tile::M1..=Tile::M9).collect::<Vec<TileType>>()
+        );
+        let test = Hand::parse_lossy("123m456p789s1z 111z 2z");
+        assert_eq!(get_winning_tiles(&test).unwrap(), vec![Tile::Z1]);
+    }
+
     #[test]
     /// 立直で和了った
     fn win_by_ready_hand() {
         let test_str = "123m444p789s1112z 2z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
 
@@ -803,7 +896,7 @@ mod tests {
     /// 自風牌で和了った
     fn win_by_honor_tiles_players_wind() {
         let test_str = "333m456p1789s 333z 1s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
 
@@ -811,21 +904,21 @@ mod tests {
     /// 場風で和了った
     fn win_by_honor_tiles_prevailing_wind() {
         let test_str = "234567m6789s 111z 6s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
     #[test]
     /// 三元牌で和了った
     fn win_by_honor_tiles_dragons() {
         let test_str = "5m123456p888s 777z 5m";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
     #[test]
     /// 断么九で和了った
     fn win_by_all_simples() {
         let test_str = "234m8s 567m 333p 456s 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
 
@@ -833,7 +926,219 @@ mod tests {
     /// 平和で和了った
     fn win_by_no_points() {
         let test_str = "123567m234p6799s 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         assert_eq!(HandAnalyzer::new_by_form(&test, Form::Normal).unwrap().shanten, -1);
     }
+
+    /// 国士無双に使う么九牌
+    const ORPHENS: [TileType; 13] = [
+        Tile::M1,
+        Tile::M9,
+        Tile::P1,
+        Tile::P9,
+        Tile::S1,
+        Tile::S9,
+        Tile::Z1,
+        Tile::Z2,
+        Tile::Z3,
+        Tile::Z4,
+        Tile::Z5,
+        Tile::Z6,
+        Tile::Z7,
+    ];
+
+    /// 総当たりで面子（刻子・順子）だけに分けられるか調べる（検証用の参照実装）
+    fn decompose_into_melds(t: &mut TileSummarize) -> bool {
+        let i = match t.iter().position(|&c| c > 0) {
+            Some(i) => i,
+            None => return true,
+        };
+        if t[i] >= 3 {
+            t[i] -= 3;
+            let ok = decompose_into_melds(t);
+            t[i] += 3;
+            if ok {
+                return true;
+            }
+        }
+        if i < Tile::Z1 as usize && i % 9 <= 6 && t[i + 1] > 0 && t[i + 2] > 0 {
+            t[i] -= 1;
+            t[i + 1] -= 1;
+            t[i + 2] -= 1;
+            let ok = decompose_into_melds(t);
+            t[i] += 1;
+            t[i + 1] += 1;
+            t[i + 2] += 1;
+            if ok {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /// 総当たりで4面子1雀頭の和了の形か調べる
+    fn is_normal_complete(t: &TileSummarize) -> bool {
+        if t.iter().sum::<u32>() != 14 {
+            return false;
+        }
+        let mut t = *t;
+        for i in 0..Tile::LEN {
+            if t[i] >= 2 {
+                t[i] -= 2;
+                let ok = decompose_into_melds(&mut t);
+                t[i] += 2;
+                if ok {
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
+    /// 七対子の和了の形か調べる（同じ牌の4枚は認めない）
+    fn is_seven_pairs_complete(t: &TileSummarize) -> bool {
+        return t.iter().filter(|&&c| c == 2).count() == 7;
+    }
+
+    /// 国士無双の和了の形か調べる
+    fn is_thirteen_orphens_complete(t: &TileSummarize) -> bool {
+        let total: u32 = ORPHENS.iter().map(|&o| t[o as usize]).sum();
+        return total == 14
+            && t.iter().sum::<u32>() == 14
+            && ORPHENS.iter().all(|&o| t[o as usize] >= 1);
+    }
+
+    /// `first`から`len`種類の牌について、合計`total`枚（各4枚まで）の全ての組み合わせを列挙する
+    fn for_each_shape(first: usize, len: usize, total: u32, f: &mut impl FnMut(&TileSummarize)) {
+        fn rec(
+            t: &mut TileSummarize,
+            i: usize,
+            end: usize,
+            left: u32,
+            f: &mut impl FnMut(&TileSummarize),
+        ) {
+            if i == end {
+                if left == 0 {
+                    f(t);
+                }
+                return;
+            }
+            for c in 0..=left.min(4) {
+                t[i] = c;
+                rec(t, i + 1, end, left - c, f);
+            }
+            t[i] = 0;
+        }
+        rec(&mut [0; Tile::LEN], first, first + len, total, f);
+    }
+
+    /// 参照実装で和了の形か調べる
+    fn is_complete(t: &TileSummarize) -> bool {
+        return is_normal_complete(t)
+            || is_seven_pairs_complete(t)
+            || is_thirteen_orphens_complete(t);
+    }
+
+    /// `HandAnalyzer`の和了の判定を和了形ごとに参照実装と突き合わせ、和了の形なら`true`を返す
+    ///
+    /// 通常形の向聴数は`shanten`モジュールの表とも突き合わせる。
+    fn verify_shape(t: &TileSummarize) -> bool {
+        let hand = Hand::from_summarized(t);
+        let normal = HandAnalyzer::new_by_form(&hand, Form::Normal).unwrap();
+        assert_eq!(has_won(&normal), is_normal_complete(t), "{}", hand);
+        assert_eq!(normal.shanten, normal_shanten(t), "{}", hand);
+        let seven_pairs = HandAnalyzer::new_by_form(&hand, Form::SevenPairs).unwrap();
+        assert_eq!(has_won(&seven_pairs), is_seven_pairs_complete(t), "{}", hand);
+        // 対子以外の牌は全て`single`に入る
+        assert_eq!(
+            seven_pairs.same2.len() * 2 + seven_pairs.single.len(),
+            hand.tiles().len(),
+            "{}",
+            hand
+        );
+        let thirteen_orphens = HandAnalyzer::new_by_form(&hand, Form::ThirteenOrphens).unwrap();
+        assert_eq!(
+            has_won(&thirteen_orphens),
+            is_thirteen_orphens_complete(t),
+            "{}",
+            hand
+        );
+        return has_won(&normal) || has_won(&seven_pairs) || has_won(&thirteen_orphens);
+    }
+
+    #[test]
+    /// 萬子だけでできる14枚の全ての形で、和了の判定が総当たりと一致する
+    fn exhaustive_single_suit_shapes() {
+        let mut shapes = 0;
+        let mut complete = 0;
+        for_each_shape(Tile::M1 as usize, 9, 14, &mut |t| {
+            shapes += 1;
+            if verify_shape(t) {
+                complete += 1;
+            }
+        });
+        assert_eq!(shapes, 118800);
+        assert!(complete > 0);
+    }
+
+    #[test]
+    /// 筒子・索子・字牌だけの和了の形は全て和了と判定し、向聴数の表とも一致する
+    ///
+    /// 全ての形を`HandAnalyzer`で調べるのは萬子だけにし、他の種類は和了の形と向聴数の表で調べる。
+    fn exhaustive_complete_shapes_of_other_suits() {
+        for (first, len) in [(Tile::P1, 9), (Tile::S1, 9), (Tile::Z1, 7)] {
+            let mut complete = 0;
+            for_each_shape(first as usize, len, 14, &mut |t| {
+                let expected = is_complete(t);
+                assert_eq!(shanten_of_summary(t) == -1, expected);
+                if expected {
+                    assert!(verify_shape(t));
+                    complete += 1;
+                }
+            });
+            assert!(complete > 1);
+        }
+    }
+
+    #[test]
+    /// 国士無双の和了の形と、そこから1枚入れ替えた全ての形で判定が総当たりと一致する
+    fn exhaustive_thirteen_orphens() {
+        for &pair in &ORPHENS {
+            let mut t: TileSummarize = [0; Tile::LEN];
+            for &o in &ORPHENS {
+                t[o as usize] = 1;
+            }
+            t[pair as usize] += 1;
+            assert!(verify_shape(&t));
+            for out in ORPHENS {
+                for tile in 0..Tile::LEN {
+                    let mut u = t;
+                    u[out as usize] -= 1;
+                    if u[tile] == 4 {
+                        continue;
+                    }
+                    u[tile] += 1;
+                    verify_shape(&u);
+                }
+            }
+        }
+    }
+
+    #[test]
+    /// 七対子の向聴数が`shanten`モジュールの計算と一致し、対子以外の牌を取りこぼさない
+    fn seven_pairs_agrees_with_shanten_module() {
+        for_each_shape(Tile::M1 as usize, 9, 13, &mut |t| {
+            let hand = Hand::from_summarized(t);
+            let analyzer = HandAnalyzer::new_by_form(&hand, Form::SevenPairs).unwrap();
+            assert_eq!(analyzer.shanten, seven_pairs_shanten(t), "{}", hand);
+            let mut single = analyzer.single.clone();
+            for pair in &analyzer.same2 {
+                single.push(pair.get()[0]);
+                single.push(pair.get()[1]);
+            }
+            single.sort_unstable();
+            let tiles: Vec<TileType> = hand.tiles().iter().map(|t| t.get()).collect();
+            assert_eq!(single, tiles, "{}", hand);
+        });
+    }
 }
@@ -1,7 +1,8 @@
 use crate::tile::*;
 
 /// 副露の種類
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OpenType {
     /// チー
     Chi,
@@ -12,7 +13,8 @@ pub enum OpenType {
 }
 
 /// 誰から副露したか
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OpenFrom {
     /// 上家（チー・ポン・明カン）
     Previous,
@@ -27,12 +29,29 @@ pub enum OpenFrom {
 }
 
 /// 副露状態を表す構造体
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenTiles {
-    /// 3枚の牌が入る。カンした時も3枚（4枚目は自明）
+    /// 3枚の牌が入る。カンした時も3枚（4枚目は自明な通常の牌で、赤ドラは`tiles`に残す）
     pub tiles: [Tile; 3],
     /// 副露の種類
     pub category: OpenType,
     /// 誰から副露したか
     pub from: OpenFrom,
+    /// 他家から鳴いた牌（暗カン、もしくは不明なら`None`）
+    pub called: Option<Tile>,
+    /// ポンに1枚加えたカン（加カン）か
+    pub added: bool,
+}
+
+impl OpenTiles {
+    /// 副露の3枚・4枚の牌から`tiles`に入れる3枚を選ぶ
+    ///
+    /// 並べ替えて、カンなら先頭の1枚を省く。赤ドラは同じ種類の通常の牌より後ろに並ぶので、省かれるのは通常の牌になる。
+    pub(crate) fn pick_tiles(tiles: &[Tile]) -> [Tile; 3] {
+        let mut sorted = tiles.to_vec();
+        sorted.sort();
+        let rest = &sorted[sorted.len() - 3..];
+        return [rest[0], rest[1], rest[2]];
+    }
 }
@@ -0,0 +1,596 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::{max, min};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::tile::*;
+
+/// 面子の数の上限（面子と面子候補も合わせてここまでしか数えない）
+const MAX_BLOCKS: usize = 4;
+/// 表に載せる1種類の牌の枚数の合計の上限
+const MAX_TILES: u32 = 14;
+
+/// 1種類の牌（萬子・筒子・索子のどれか、または字牌）の分け方の評価
+///
+/// `[雀頭の有無][面子の数]`に、その時に作れる面子候補（対子・塔子・嵌張）の最大数を入れる。
+/// 面子候補は4つで打ち切り、作れない組み合わせは`-1`にする。
+type Profile = [[i8; MAX_BLOCKS + 1]; 2];
+
+/// 何もない牌姿の評価
+const EMPTY: Profile = {
+    let mut p = [[-1; MAX_BLOCKS + 1]; 2];
+    p[0][0] = 0;
+    p
+};
+
+/// 数牌（9種類）の牌姿ごとの評価の表
+static NUMBER_TABLE: LazyTable = LazyTable::new();
+/// 字牌（7種類）の牌姿ごとの評価の表
+static HONOR_TABLE: LazyTable = LazyTable::new();
+
+/// 最初に引く時に作る表
+///
+/// `std`なしでも使えるように`AtomicPtr`で持つ。複数のスレッドが同時に作り始めた場合は、
+/// 先に入れた方を使い、残りは捨てる。一度入れた表は解放しない。
+struct LazyTable {
+    table: AtomicPtr<Vec<u32>>,
+}
+
+impl LazyTable {
+    const fn new() -> LazyTable {
+        LazyTable {
+            table: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    fn get_or_init(&self, init: impl FnOnce() -> Vec<u32>) -> &[u32] {
+        let mut table = self.table.load(Ordering::Acquire);
+        if table.is_null() {
+            let built = Box::into_raw(Box::new(init()));
+            table = match self.table.compare_exchange(
+                ptr::null_mut(),
+                built,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => built,
+                Err(other) => {
+                    // 他のスレッドが先に入れたので、作った表は捨てる
+                    drop(unsafe { Box::from_raw(built) });
+                    other
+                }
+            };
+        }
+        // 入れた表は書き換えも解放もしないので、`'static`として参照できる
+        return unsafe { &*table };
+    }
+}
+
+/// 手牌の向聴数を表を引いて求める
+///
+/// `HandAnalyzer::new(hand)`の`shanten`と同じ値を返すが、面子の組み合わせは求めない。
+/// 大量の手牌を調べる時に使う。表は最初に呼ばれた時に作る。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::hand::Hand;
+/// use riichi_mahjong_rs::hand_info::shanten::shanten;
+///
+/// assert_eq!(shanten(&Hand::parse_lossy("123m456p789s1122z 2z")), -1);
+/// assert_eq!(shanten(&Hand::parse_lossy("123m456p789s1123z")), 1);
+/// ```
+pub fn shanten(hand: &Hand) -> i32 {
+    return shanten_of_summary(&hand.summarize_tiles());
+}
+
+/// 牌の種類ごとの枚数から向聴数を求める（七対子・国士無双・通常の形の最小）
+///
+/// 各牌は4枚まで、合計は14枚までとする。
+pub fn shanten_of_summary(summary: &TileSummarize) -> i32 {
+    let normal = normal_shanten(summary);
+    let seven_pairs = seven_pairs_shanten(summary);
+    let thirteen_orphens = thirteen_orphens_shanten(summary);
+    return min(normal, min(seven_pairs, thirteen_orphens));
+}
+
+/// 通常の形（4面子1雀頭）への向聴数を求める
+pub fn normal_shanten(summary: &TileSummarize) -> i32 {
+    return normal_shanten_by_keys(&keys_of(summary));
+}
+
+/// 七対子への向聴数を求める（同じ牌の4枚は1つの対子としか数えない）
+pub fn seven_pairs_shanten(summary: &TileSummarize) -> i32 {
+    let pair = summary.iter().filter(|&&c| c >= 2).count() as i32;
+    let kind = summary.iter().filter(|&&c| c >= 1).count() as i32;
+    return seven_pairs_shanten_by_count(pair, kind);
+}
+
+/// 国士無双への向聴数を求める
+pub fn thirteen_orphens_shanten(summary: &TileSummarize) -> i32 {
+    let kind = ORPHENS
+        .iter()
+        .filter(|&&t| summary[t as usize] >= 1)
+        .count() as i32;
+    let pair = ORPHENS.iter().any(|&t| summary[t as usize] >= 2);
+    return thirteen_orphens_shanten_by_count(pair, kind);
+}
+
+/// 国士無双に使う么九牌
+const ORPHENS: [TileType; 13] = [
+    Tile::M1,
+    Tile::M9,
+    Tile::P1,
+    Tile::P9,
+    Tile::S1,
+    Tile::S9,
+    Tile::Z1,
+    Tile::Z2,
+    Tile::Z3,
+    Tile::Z4,
+    Tile::Z5,
+    Tile::Z6,
+    Tile::Z7,
+];
+
+fn seven_pairs_shanten_by_count(pair: i32, kind: i32) -> i32 {
+    return 7 - pair + max(7 - kind, 0) - 1;
+}
+
+fn thirteen_orphens_shanten_by_count(pair: bool, kind: i32) -> i32 {
+    return 14 - kind - pair as i32 - 1;
+}
+
+/// 萬子・筒子・索子・字牌それぞれの表の添字から、通常の形への向聴数を求める
+fn normal_shanten_by_keys(keys: &[usize; 4]) -> i32 {
+    let numbers = NUMBER_TABLE.get_or_init(|| build_table(9, true));
+    let honors = HONOR_TABLE.get_or_init(|| build_table(7, false));
+    let mut total = EMPTY;
+    for key in &keys[..3] {
+        total = combine(&total, &unpack(numbers[*key]));
+    }
+    total = combine(&total, &unpack(honors[keys[3]]));
+
+    let mut result = i32::MAX;
+    for (head, row) in total.iter().enumerate() {
+        for (block3, &block2) in row.iter().enumerate() {
+            if block2 < 0 {
+                continue;
+            }
+            let block2 = min(block2 as usize, MAX_BLOCKS - block3);
+            result = min(result, 8 - (block3 * 2 + block2 + head) as i32);
+        }
+    }
+    return result;
+}
+
+/// 萬子・筒子・索子・字牌それぞれの表の添字
+fn keys_of(summary: &TileSummarize) -> [usize; 4] {
+    return [
+        key_of(&summary[Tile::M1 as usize..=Tile::M9 as usize]),
+        key_of(&summary[Tile::P1 as usize..=Tile::P9 as usize]),
+        key_of(&summary[Tile::S1 as usize..=Tile::S9 as usize]),
+        key_of(&summary[Tile::Z1 as usize..=Tile::Z7 as usize]),
+    ];
+}
+
+/// 1枚ずつ牌を出し入れしながら向聴数を求め続ける
+///
+/// 表の添字と七対子・国士無双の対子・種類の数を持っておき、牌の出し入れのたびに
+/// 定数時間で向聴数を求め直す。局の進行や打ち手が打牌の候補を調べる時に使う。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::hand::Hand;
+/// use riichi_mahjong_rs::hand_info::shanten::ShantenState;
+/// use riichi_mahjong_rs::tile::Tile;
+///
+/// let mut state = ShantenState::new(&Hand::parse_lossy("123m456p789s1123z"));
+/// assert_eq!(state.shanten(), 1);
+/// state.add(Tile::Z1).unwrap();
+/// state.remove(Tile::Z3).unwrap();
+/// assert_eq!(state.shanten(), 0);
+/// assert_eq!(state.acceptance(), vec![(Tile::Z2, 3)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShantenState {
+    /// 牌の種類ごとの枚数
+    summary: TileSummarize,
+    /// 萬子・筒子・索子・字牌それぞれの表の添字
+    keys: [usize; 4],
+    /// 2枚以上ある牌の種類の数
+    pairs: i32,
+    /// 1枚以上ある牌の種類の数
+    kinds: i32,
+    /// 2枚以上ある么九牌の種類の数
+    orphen_pairs: i32,
+    /// 1枚以上ある么九牌の種類の数
+    orphen_kinds: i32,
+    /// 向聴数
+    shanten: i32,
+}
+
+impl ShantenState {
+    /// 手牌（副露・ツモった牌を含む）から作る
+    pub fn new(hand: &Hand) -> ShantenState {
+        return ShantenState::from_summary(&hand.summarize_tiles());
+    }
+
+    /// 牌の種類ごとの枚数から作る（各牌は4枚まで、合計は14枚までとする）
+    pub fn from_summary(summary: &TileSummarize) -> ShantenState {
+        let mut result = ShantenState {
+            summary: *summary,
+            keys: keys_of(summary),
+            pairs: summary.iter().filter(|&&c| c >= 2).count() as i32,
+            kinds: summary.iter().filter(|&&c| c >= 1).count() as i32,
+            orphen_pairs: ORPHENS
+                .iter()
+                .filter(|&&t| summary[t as usize] >= 2)
+                .count() as i32,
+            orphen_kinds: ORPHENS
+                .iter()
+                .filter(|&&t| summary[t as usize] >= 1)
+                .count() as i32,
+            shanten: 0,
+        };
+        result.update();
+        return result;
+    }
+
+    /// 向聴数（聴牌が`0`、和了が`-1`）
+    pub fn shanten(&self) -> i32 {
+        self.shanten
+    }
+
+    /// 牌の種類ごとの枚数
+    pub fn summary(&self) -> &TileSummarize {
+        &self.summary
+    }
+
+    /// 牌を1枚加える（5枚目は加えられない）
+    pub fn add(&mut self, tile: TileType) -> Result<()> {
+        let count = self.count(tile)?;
+        if count >= 4 {
+            return Err(Error::TooManyCopies(tile));
+        }
+        self.set(tile, count + 1);
+        return Ok(());
+    }
+
+    /// 牌を1枚取り除く
+    pub fn remove(&mut self, tile: TileType) -> Result<()> {
+        let count = self.count(tile)?;
+        if count == 0 {
+            return Err(Error::NoTileToRemove(tile));
+        }
+        self.set(tile, count - 1);
+        return Ok(());
+    }
+
+    /// 1枚加えると向聴数が減る牌の種類と、その牌が手牌の外に残っている枚数（`4 - 手牌の枚数`）
+    ///
+    /// 手牌で4枚使っている牌は含まない。
+    pub fn acceptance(&self) -> Vec<(TileType, u32)> {
+        let mut result = Vec::new();
+        let mut test = *self;
+        for t in Tile::M1..=Tile::Z7 {
+            let count = self.summary[t as usize];
+            if count >= 4 || test.add(t).is_err() {
+                continue;
+            }
+            if test.shanten < self.shanten {
+                result.push((t, 4 - count));
+            }
+            test = *self;
+        }
+        return result;
+    }
+
+    fn count(&self, tile: TileType) -> Result<u32> {
+        return self
+            .summary
+            .get(tile as usize)
+            .copied()
+            .ok_or(Error::InvalidTile(tile));
+    }
+
+    /// 牌の枚数を変え、表の添字・対子と種類の数・向聴数を求め直す
+    fn set(&mut self, tile: TileType, count: u32) {
+        let old = self.summary[tile as usize];
+        self.summary[tile as usize] = count;
+        let power = 5_usize.pow(tile % 9);
+        let key = &mut self.keys[tile as usize / 9];
+        *key = *key - old as usize * power + count as usize * power;
+        let pair = (count >= 2) as i32 - (old >= 2) as i32;
+        let kind = (count >= 1) as i32 - (old >= 1) as i32;
+        self.pairs += pair;
+        self.kinds += kind;
+        if ORPHENS.contains(&tile) {
+            self.orphen_pairs += pair;
+            self.orphen_kinds += kind;
+        }
+        self.update();
+    }
+
+    /// 向聴数を求め直す
+    fn update(&mut self) {
+        let normal = normal_shanten_by_keys(&self.keys);
+        let seven_pairs = seven_pairs_shanten_by_count(self.pairs, self.kinds);
+        let thirteen_orphens =
+            thirteen_orphens_shanten_by_count(self.orphen_pairs > 0, self.orphen_kinds);
+        self.shanten = min(normal, min(seven_pairs, thirteen_orphens));
+    }
+}
+
+/// 1種類の牌の枚数の並びを表の添字にする（5進数）
+fn key_of(counts: &[u32]) -> usize {
+    return counts
+        .iter()
+        .rev()
+        .fold(0, |key, &c| key * 5 + min(c, 4) as usize);
+}
+
+/// 評価を表に入れるための32ビットにする（1つの値を3ビットで`面子候補の数+1`とする）
+fn pack(profile: &Profile) -> u32 {
+    let mut result = 0;
+    for (i, t) in profile.iter().flatten().enumerate() {
+        result |= ((*t + 1) as u32) << (i * 3);
+    }
+    return result;
+}
+
+/// 表の値から評価に戻す
+fn unpack(packed: u32) -> Profile {
+    let mut result = [[-1; MAX_BLOCKS + 1]; 2];
+    for (i, t) in result.iter_mut().flatten().enumerate() {
+        *t = ((packed >> (i * 3)) & 0b111) as i8 - 1;
+    }
+    return result;
+}
+
+/// 2つの評価を合わせる（雀頭は全体で1つまで）
+fn combine(a: &Profile, b: &Profile) -> Profile {
+    let mut result = [[-1; MAX_BLOCKS + 1]; 2];
+    for ha in 0..2 {
+        for ma in 0..=MAX_BLOCKS {
+            if a[ha][ma] < 0 {
+                continue;
+            }
+            for hb in 0..2 - ha {
+                for mb in 0..=MAX_BLOCKS - ma {
+                    if b[hb][mb] < 0 {
+                        continue;
+                    }
+                    let t = min(a[ha][ma] + b[hb][mb], MAX_BLOCKS as i8);
+                    let entry = &mut result[ha + hb][ma + mb];
+                    *entry = max(*entry, t);
+                }
+            }
+        }
+    }
+    return result;
+}
+
+/// `len`種類の牌の全ての牌姿の評価の表を作る（`sequences`なら順子・塔子・嵌張も作る）
+///
+/// 一番小さい牌の使い道（浮き牌・刻子・順子・対子・雀頭・塔子・嵌張）で場合分けし、
+/// 残りの牌姿の評価から求める。残りの牌姿の添字は必ず小さいので、添字の順に埋めていく。
+fn build_table(len: usize, sequences: bool) -> Vec<u32> {
+    let size = 5_usize.pow(len as u32);
+    let mut table = vec![0; size];
+    let mut powers = vec![1; len];
+    for i in 1..len {
+        powers[i] = powers[i - 1] * 5;
+    }
+    let mut counts = vec![0_u32; len];
+    for key in 0..size {
+        let mut rest = key;
+        for c in counts.iter_mut() {
+            *c = (rest % 5) as u32;
+            rest /= 5;
+        }
+        if counts.iter().sum::<u32>() > MAX_TILES {
+            continue;
+        }
+        let i = match counts.iter().position(|&c| c > 0) {
+            Some(i) => i,
+            None => {
+                table[key] = pack(&EMPTY);
+                continue;
+            }
+        };
+        // 残りの牌姿の評価をずらして合わせる
+        let mut profile = [[-1; MAX_BLOCKS + 1]; 2];
+        let mut merge = |sub: usize, block3: usize, block2: i8, head: bool| {
+            let sub = unpack(table[key - sub]);
+            for h in 0..2 {
+                let to = if head { h + 1 } else { h };
+                if to > 1 {
+                    continue;
+                }
+                for m in 0..=MAX_BLOCKS - block3 {
+                    if sub[h][m] < 0 {
+                        continue;
+                    }
+                    let t = min(sub[h][m] + block2, MAX_BLOCKS as i8);
+                    let entry = &mut profile[to][m + block3];
+                    *entry = max(*entry, t);
+                }
+            }
+        };
+        let c = counts[i];
+        // 浮き牌
+        merge(powers[i], 0, 0, false);
+        if c >= 3 {
+            // 刻子
+            merge(powers[i] * 3, 1, 0, false);
+        }
+        if c >= 2 {
+            // 対子と雀頭
+            merge(powers[i] * 2, 0, 1, false);
+            merge(powers[i] * 2, 0, 0, true);
+        }
+        if sequences && i + 1 < len && counts[i + 1] > 0 {
+            // 塔子
+            merge(powers[i] + powers[i + 1], 0, 1, false);
+        }
+        if sequences && i + 2 < len && counts[i + 2] > 0 {
+            // 嵌張
+            merge(powers[i] + powers[i + 2], 0, 1, false);
+            if counts[i + 1] > 0 {
+                // 順子
+                merge(powers[i] + powers[i + 1] + powers[i + 2], 1, 0, false);
+            }
+        }
+        table[key] = pack(&profile);
+    }
+    return table;
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand_info::hand_analyzer::HandAnalyzer;
+    #[cfg(feature = "std")]
+    use crate::hand_info::opened::{OpenFrom, OpenType};
+    use crate::test_util::arb_tiles;
+    #[cfg(feature = "std")]
+    use crate::test_util::{tile_set, RandomHands};
+    use proptest::prelude::*;
+    #[cfg(feature = "std")]
+    use rand::seq::SliceRandom;
+
+    #[test]
+    /// 決まった手牌の向聴数
+    fn known_hands() {
+        let cases = [
+            ("123m456p789s1122z 2z", -1),
+            ("19m19p19s1234567z 1m", -1),
+            ("1122m3344p5566s7z 7z", -1),
+            ("123456m456p11z89s", 0),
+            ("1122m3344p5566s7z", 0),
+            ("1111z12m45m78m12p4p", 3),
+            ("147m258p369s1234z", 6),
+        ];
+        for (hand, expected) in cases {
+            assert_eq!(shanten(&Hand::parse_lossy(hand)), expected, "{}", hand);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    /// ランダムな手牌で`HandAnalyzer::new`と同じ向聴数になる
+    fn same_as_hand_analyzer() {
+        let mut random = RandomHands::new(41);
+        for n in 0..3000 {
+            let mut hand = random.hand(n % 2 == 0);
+            hand.sort();
+            let expected = HandAnalyzer::new(&hand).unwrap().shanten;
+            assert_eq!(shanten(&hand), expected, "{}", hand);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    /// 同じ種類の牌に偏った手牌や副露のある手牌でも`HandAnalyzer::new`と同じ向聴数になる
+    fn same_as_hand_analyzer_with_flush_and_melds() {
+        let manzu: Vec<Tile> = tile_set()
+            .into_iter()
+            .filter(|t| t.get() <= Tile::M9 || t.get() >= Tile::Z1)
+            .collect();
+        let mut random = RandomHands::with_tiles(42, manzu);
+        for n in 0..500 {
+            let mut hand = random.hand(true);
+            if n % 3 == 0 {
+                // 手牌の先頭の2枚と同じ種類の牌をポンしたことにする
+                hand.sort();
+                let t = hand.tiles()[0];
+                if hand.tiles().iter().filter(|x| x.get() == t.get()).count() == 2 {
+                    hand.call(&[t.get(), t.get()], t, OpenType::Pon, OpenFrom::Opposite)
+                        .unwrap();
+                    hand.set_drawn(None);
+                }
+            }
+            let expected = HandAnalyzer::new(&hand).unwrap().shanten;
+            assert_eq!(shanten(&hand), expected, "{}", hand);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    /// 牌を出し入れし続けても、作り直した場合と同じ向聴数になる
+    fn incremental_updates() {
+        let mut random = RandomHands::new(43);
+        let mut wall = random.tiles(tile_set().len());
+        let mut hand = Hand::new(wall.drain(..13).collect(), None);
+        let mut state = ShantenState::new(&hand);
+        for tile in wall {
+            state.add(tile.get()).unwrap();
+            hand.set_drawn(Some(tile));
+            assert_eq!(state, ShantenState::new(&hand));
+            assert_eq!(state.shanten(), HandAnalyzer::new(&hand).unwrap().shanten);
+
+            // 手牌の中からランダムに1枚捨てる
+            let tiles: Vec<Tile> = hand.tiles().iter().copied().chain(hand.drawn()).collect();
+            let discard = *tiles.choose(random.rng()).unwrap();
+            let mut next: Vec<Tile> = tiles;
+            let pos = next.iter().position(|t| *t == discard).unwrap();
+            next.remove(pos);
+            hand = Hand::new(next, None);
+            state.remove(discard.get()).unwrap();
+            assert_eq!(state, ShantenState::new(&hand));
+        }
+    }
+
+    #[test]
+    /// 向聴数が減る牌と残り枚数
+    fn acceptance() {
+        let state = ShantenState::new(&Hand::parse_lossy("1112345678999m"));
+        let all: Vec<TileType> = (Tile::M1..=Tile::M9).collect();
+        let tiles: Vec<TileType> = state.acceptance().iter().map(|(t, _)| *t).collect();
+        assert_eq!(tiles, all);
+        assert_eq!(state.acceptance()[0], (Tile::M1, 1));
+        assert_eq!(state.acceptance()[1], (Tile::M2, 3));
+
+        let state = ShantenState::new(&Hand::parse_lossy("19m19p19s1234567z"));
+        assert_eq!(state.acceptance().len(), 13);
+        let mut state = state;
+        assert!(state.remove(Tile::M2).is_err());
+        state.add(Tile::M1).unwrap();
+        assert_eq!(state.shanten(), -1);
+        assert!(state.acceptance().is_empty());
+        for _ in 0..2 {
+            state.add(Tile::M1).unwrap();
+        }
+        assert!(state.add(Tile::M1).is_err());
+    }
+
+    proptest! {
+        #[test]
+        /// 13枚の手牌にどの牌を加えても`HandAnalyzer::new`と同じ向聴数になり、向聴数は増えず、`acceptance`の牌を加えたときだけ減る
+        fn adding_tile_never_increases_shanten(tiles in arb_tiles(13)) {
+            let hand = Hand::new(tiles, None);
+            let state = ShantenState::new(&hand);
+            prop_assert_eq!(state.shanten(), HandAnalyzer::new(&hand).unwrap().shanten, "{}", hand);
+            let useful: Vec<TileType> = state.acceptance().iter().map(|(t, _)| *t).collect();
+            for t in Tile::M1..=Tile::Z7 {
+                let mut next = state;
+                if next.add(t).is_err() {
+                    continue;
+                }
+                let mut drawn = hand.clone();
+                drawn.set_drawn(Some(Tile::new(t)));
+                let expected = HandAnalyzer::new(&drawn).unwrap().shanten;
+                prop_assert_eq!(next.shanten(), expected, "{} + {}", hand, Tile::new(t));
+                prop_assert!(expected <= state.shanten(), "{} + {}", hand, Tile::new(t));
+                prop_assert_eq!(useful.contains(&t), expected < state.shanten(), "{} + {}", hand, Tile::new(t));
+            }
+        }
+    }
+}
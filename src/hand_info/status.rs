@@ -1,6 +1,8 @@
 use crate::tile::{Tile, TileSummarize, TileType, Wind};
 
 /// 手牌の（牌以外の）状態
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status {
     /// 立直したか
     pub has_claimed_ready: bool,
@@ -10,12 +12,30 @@ pub struct Status {
     pub is_self_picked: bool,
     /// 一発が有効な間立てるフラグ
     pub is_one_shot: bool,
+    /// ダブル立直したか
+    pub has_claimed_double_ready: bool,
+    /// 最後の牌（海底牌・河底牌）で和了ったか
+    pub is_last_tile: bool,
+    /// 嶺上牌で和了ったか
+    pub is_dead_wall_draw: bool,
+    /// 搶槓で和了ったか
+    pub is_robbing_a_quad: bool,
+    /// 誰も鳴いていない第一巡の自摸か
+    pub is_first_turn: bool,
+    /// 槓子の数
+    pub kan_count: u32,
     /// 自風
     pub player_wind: Wind,
     /// 場風
     pub prevailing_wind: Wind,
 }
 
+impl Default for Status {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Status {
     pub fn new() -> Status {
         Status {
@@ -23,6 +43,12 @@ impl Status {
             has_claimed_open: false,
             is_self_picked: false,
             is_one_shot: false,
+            has_claimed_double_ready: false,
+            is_last_tile: false,
+            is_dead_wall_draw: false,
+            is_robbing_a_quad: false,
+            is_first_turn: false,
+            kan_count: 0,
             player_wind: Wind::East,
             prevailing_wind: Wind::East,
         }
@@ -44,22 +70,18 @@ pub fn is_two_sided_wait(tile: TileType, counts: &TileSummarize) -> bool {
         tile - Tile::S1 + 1
     };
 
-    // 左側が存在する形 : xx[tile-2][tile-1] + tile かつ123または789にならない
-    if offset >= 3
+    // 左側が存在する形 : [tile-2][tile-1] + tile かつ12に3を待つ辺張にならない（78に9は両面）
+    if offset >= 4
         && counts[(tile - 1) as usize] > 0
         && counts[(tile - 2) as usize] > 0
-        && offset != 3
-        && offset != 9
     {
         return true;
     }
 
-    // 右側が存在する形 : tile + [tile+1][tile+2] かつ123または789にならない
-    if offset <= 7
+    // 右側が存在する形 : tile + [tile+1][tile+2] かつ89に7を待つ辺張にならない（23に1は両面）
+    if offset <= 6
         && counts[(tile + 1) as usize] > 0
         && counts[(tile + 2) as usize] > 0
-        && offset != 1
-        && offset != 7
     {
         return true;
     }
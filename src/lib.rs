@@ -1,3 +1,9 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/// エラー
+pub mod error;
 /// ルールなどの設定
 pub mod settings;
 /// 牌
@@ -9,4 +15,27 @@ pub mod hand_info;
 /// 和了役
 pub mod winning_hand;
 /// 卓
-pub mod board;
\ No newline at end of file
+pub mod board;
+/// 牌譜の読み込み
+#[cfg(feature = "std")]
+pub mod log;
+/// mjaiプロトコル
+#[cfg(feature = "std")]
+pub mod mjai;
+/// SVGでの描画
+#[cfg(feature = "std")]
+pub mod svg;
+/// ビットで詰めた手牌
+pub mod packed_hand;
+/// 手牌の一括評価
+#[cfg(feature = "std")]
+pub mod batch;
+/// 解析結果のキャッシュ
+#[cfg(feature = "std")]
+pub mod cache;
+/// C言語から使うためのインターフェース
+#[cfg(feature = "ffi")]
+pub mod ffi;
+/// テストで使う乱数の手牌
+#[cfg(test)]
+mod test_util;
\ No newline at end of file
@@ -0,0 +1,8 @@
+/// 牌譜の読み書きに使うJSON
+pub(crate) mod json;
+/// 天鳳の牌譜（mjlog形式）
+pub mod mjlog;
+/// tenhou.net/6の牌譜（JSON形式）
+pub mod tenhou6;
+/// 人が読むための牌譜（テキスト）
+pub mod kifu;
\ No newline at end of file
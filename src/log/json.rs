@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+/// 牌譜の読み書きに使う最小限のJSONの値
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// キーと値の組（出現順を保つ）
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// JSONのテキストを読み込む
+    pub(crate) fn parse(text: &str) -> Result<Json> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            pos: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.chars.len() {
+            return Err(Error::InvalidJson(format!(
+                "Unexpected text after JSON at {}",
+                parser.pos
+            )));
+        }
+        Ok(value)
+    }
+
+    /// オブジェクトの指定したキーの値を返す
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Result<&[Json]> {
+        match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(Error::InvalidJson(format!(
+                "Expected a JSON array: {}",
+                self
+            ))),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Result<&str> {
+        match self {
+            Json::String(s) => Ok(s),
+            _ => Err(Error::InvalidJson(format!(
+                "Expected a JSON string: {}",
+                self
+            ))),
+        }
+    }
+
+    pub(crate) fn as_i64(&self) -> Result<i64> {
+        match self {
+            Json::Number(n) if n.fract() == 0.0 => Ok(*n as i64),
+            _ => Err(Error::InvalidJson(format!(
+                "Expected a JSON integer: {}",
+                self
+            ))),
+        }
+    }
+}
+
+impl From<i64> for Json {
+    fn from(n: i64) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+
+/// 空白を含まない1行のJSONにする
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, k)?;
+                    write!(f, ":{}", v)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn next(&mut self) -> Result<char> {
+        let c = *self
+            .chars
+            .get(self.pos)
+            .ok_or_else(|| Error::InvalidJson("Unexpected end of JSON".to_string()))?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, word: &str) -> Result<()> {
+        for c in word.chars() {
+            if self.next()? != c {
+                return Err(Error::InvalidJson(format!(
+                    "Invalid JSON at {}",
+                    self.pos - 1
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        match self.chars.get(self.pos) {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => {
+                self.pos += 1;
+                let mut values: Vec<Json> = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.pos) == Some(&']') {
+                    self.pos += 1;
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.next()? {
+                        ',' => continue,
+                        ']' => return Ok(Json::Array(values)),
+                        _ => {
+                            return Err(Error::InvalidJson(format!(
+                                "Invalid JSON array at {}",
+                                self.pos - 1
+                            )))
+                        }
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                let mut entries: Vec<(String, Json)> = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.pos) == Some(&'}') {
+                    self.pos += 1;
+                    return Ok(Json::Object(entries));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    entries.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.next()? {
+                        ',' => continue,
+                        '}' => return Ok(Json::Object(entries)),
+                        _ => {
+                            return Err(Error::InvalidJson(format!(
+                                "Invalid JSON object at {}",
+                                self.pos - 1
+                            )))
+                        }
+                    }
+                }
+            }
+            Some(_) => self.number(),
+            None => Err(Error::InvalidJson("Unexpected end of JSON".to_string())),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.pos;
+        while self.pos < self.chars.len()
+            && matches!(
+                self.chars[self.pos],
+                '0'..='9' | '-' | '+' | '.' | 'e' | 'E'
+            )
+        {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse::<f64>()
+            .map(Json::Number)
+            .map_err(|_| Error::InvalidJson(format!("Invalid JSON number at {}", start)))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect("\"")?;
+        let mut result = String::new();
+        loop {
+            match self.next()? {
+                '"' => return Ok(result),
+                '\\' => match self.next()? {
+                    'n' => result.push('\n'),
+                    'r' => result.push('\r'),
+                    't' => result.push('\t'),
+                    'b' => result.push('\u{8}'),
+                    'f' => result.push('\u{c}'),
+                    'u' => {
+                        let mut code = self.hex4()?;
+                        // サロゲートペア
+                        if (0xD800..0xDC00).contains(&code) {
+                            self.expect("\\u")?;
+                            let low = self.hex4()?.wrapping_sub(0xDC00);
+                            if low >= 0x400 {
+                                return Err(Error::InvalidJson(format!(
+                                    "Invalid JSON escape at {}",
+                                    self.pos
+                                )));
+                            }
+                            code = 0x10000 + ((code - 0xD800) << 10) + low;
+                        }
+                        result.push(char::from_u32(code).ok_or_else(|| {
+                            Error::InvalidJson(format!("Invalid JSON escape at {}", self.pos))
+                        })?);
+                    }
+                    c => result.push(c),
+                },
+                c => result.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self.next()?.to_digit(16).ok_or_else(|| {
+                Error::InvalidJson(format!("Invalid JSON escape at {}", self.pos - 1))
+            })?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 読み込んだ値を書き出すと空白のない同じJSONになる
+    fn round_trip() {
+        let text = r#"{"a": [1, -2.5, true, null], "b": "x\"あ🀄\n", "c": {}}"#;
+        let value = Json::parse(text).unwrap();
+        assert_eq!(value.get("b").unwrap().as_str().unwrap(), "x\"あ🀄\n");
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap()[0]
+                .as_i64()
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            value.to_string(),
+            r#"{"a":[1,-2.5,true,null],"b":"x\"あ🀄\n","c":{}}"#
+        );
+        assert!(Json::parse("[1, 2").is_err());
+        assert!(Json::parse("[1] 2").is_err());
+        assert!(Json::parse(r#""\ud83c""#).is_err());
+    }
+}
@@ -0,0 +1,527 @@
+use crate::board::event::{self, Event, Replay};
+use crate::board::round::Round;
+use crate::board::wall::{dora_from_indicator, Wall};
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::hand_info::opened::{OpenFrom, OpenTiles, OpenType};
+use crate::settings::{Lang, Settings};
+use crate::tile::*;
+use crate::winning_hand::score::{self, Score};
+
+/// 記録した出来事から、検討用の文字列の牌譜を作る
+///
+/// 局ごとに局・本場・供託・ドラ表示牌・配牌を書き、各席の自摸・打牌・鳴きを手番ごとに1行ずつ並べる
+/// （牌と副露は`Hand::to_short_string`と同じ表記）。和了は役・符・翻を、最後に点数の移動を書く。
+/// 言語は`settings.display_lang`に従う。`names`は席順の対局者の名前。
+pub fn from_events(events: &[Event], settings: &Settings, names: &[String]) -> Result<String> {
+    let mut writer = Writer {
+        lang: settings.display_lang,
+        names: names.to_vec(),
+        lines: Vec::new(),
+        turn: None,
+        dead_wall_draw: false,
+        added_kan: None,
+    };
+    let mut replay = Replay::new(settings);
+    for e in events {
+        if let Event::Deal { .. } = e {
+            replay.apply(e)?;
+            let (round, wall) = match (replay.round.as_ref(), replay.wall.as_ref()) {
+                (Some(round), Some(wall)) => (round, wall),
+                _ => return Err(Error::InvalidLog("No round".to_string())),
+            };
+            writer.start_round(round, wall);
+            continue;
+        }
+        let (round, wall) = match (replay.round.as_ref(), replay.wall.as_ref()) {
+            (Some(round), Some(wall)) => (round, wall),
+            _ => return Err(Error::NoRound(Box::new(e.clone()))),
+        };
+        // 和了の役は和了る前の状態から求め直す
+        let score = match e {
+            Event::Win { seat, from, .. } => {
+                writer.evaluate(round, wall, settings, *seat, *from)?
+            }
+            _ => None,
+        };
+        writer.record(e, round, score.as_ref())?;
+        replay.apply(e)?;
+        if let (Event::EndRound { score_changes, .. }, Some(round)) = (e, replay.round.as_ref()) {
+            writer.end_round(round, score_changes);
+        }
+    }
+    let mut result = writer.lines.join("\n");
+    result.push('\n');
+    Ok(result)
+}
+
+/// 牌譜を1行ずつ書く
+struct Writer {
+    lang: Lang,
+    names: Vec<String>,
+    lines: Vec<String>,
+    /// 書きかけの手番の行（席と内容）
+    turn: Option<(usize, Vec<String>)>,
+    /// 直前の自摸が嶺上牌か（嶺上開花を求める）
+    dead_wall_draw: bool,
+    /// 直前の加槓の席と牌（搶槓を求める）
+    added_kan: Option<(usize, TileType)>,
+}
+
+impl Writer {
+    fn name(&self, seat: usize) -> String {
+        match self.names.get(seat) {
+            Some(name) => name.clone(),
+            None => match self.lang {
+                Lang::Ja => format!("{}家", seat),
+                Lang::En => format!("Seat {}", seat),
+            },
+        }
+    }
+
+    /// 書きかけの手番の行を書き終える
+    fn flush(&mut self) {
+        if let Some((seat, words)) = self.turn.take() {
+            self.lines
+                .push(format!("{}: {}", self.name(seat), words.join(" ")));
+        }
+    }
+
+    /// 手番の行に書き足す（`new_turn`なら新しい行にする）
+    fn push(&mut self, seat: usize, word: String, new_turn: bool) {
+        match self.turn.as_mut() {
+            Some((s, words)) if *s == seat && !new_turn => words.push(word),
+            _ => {
+                self.flush();
+                self.turn = Some((seat, vec![word]));
+            }
+        }
+    }
+
+    fn line(&mut self, line: String) {
+        self.flush();
+        self.lines.push(line);
+    }
+
+    fn start_round(&mut self, round: &Round, wall: &Wall) {
+        if !self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        let indicators: Vec<String> = wall
+            .dora_indicators()
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        let dealer = self.name(round.dealer);
+        let header = match self.lang {
+            Lang::Ja => format!(
+                "{}{}局 {}本場 供託{} 親:{} ドラ表示牌:{}",
+                wind_name(round.prevailing_wind, self.lang),
+                round.dealer + 1,
+                round.honba,
+                round.deposits,
+                dealer,
+                indicators.join(","),
+            ),
+            Lang::En => format!(
+                "{} {}, {} honba, {} riichi sticks, dealer: {}, dora indicator: {}",
+                wind_name(round.prevailing_wind, self.lang),
+                round.dealer + 1,
+                round.honba,
+                round.deposits,
+                dealer,
+                indicators.join(","),
+            ),
+        };
+        self.line(header);
+        for (seat, player) in round.players.iter().enumerate() {
+            let line = match self.lang {
+                Lang::Ja => format!(
+                    "  {}（{}） {}点 配牌:{}",
+                    self.name(seat),
+                    wind_name(round.player_wind(seat), self.lang),
+                    player.score,
+                    player.hand.to_short_string(),
+                ),
+                Lang::En => format!(
+                    "  {} ({}) {} points, hand: {}",
+                    self.name(seat),
+                    wind_name(round.player_wind(seat), self.lang),
+                    player.score,
+                    player.hand.to_short_string(),
+                ),
+            };
+            self.line(line);
+        }
+    }
+
+    /// 出来事を反映する前の局の状態から、出来事を書き加える
+    fn record(&mut self, e: &Event, round: &Round, score: Option<&Score>) -> Result<()> {
+        let players = round.players.len();
+        if !matches!(e, Event::Win { .. }) {
+            self.added_kan = None;
+        }
+        let ja = matches!(self.lang, Lang::Ja);
+        match e {
+            Event::Draw { seat, tile } => {
+                self.dead_wall_draw = false;
+                let word = if ja { "自摸" } else { "draw" };
+                self.push(*seat, format!("{} {}", word, tile), true);
+            }
+            Event::DeadWallDraw { seat, tile } => {
+                self.dead_wall_draw = true;
+                let word = if ja { "嶺上" } else { "replacement" };
+                self.push(*seat, format!("{} {}", word, tile), false);
+            }
+            Event::Discard { seat, tile, ready } => {
+                let tsumogiri = round.players[*seat].hand.drawn() == Some(*tile);
+                let mut word = match (ja, tsumogiri) {
+                    (true, true) => "ツモ切り",
+                    (true, false) => "打",
+                    (false, true) => "tsumogiri",
+                    (false, false) => "discard",
+                }
+                .to_string();
+                if *ready {
+                    word = format!("{} {}", if ja { "立直" } else { "riichi" }, word);
+                }
+                self.push(*seat, format!("{} {}", word, tile), false);
+            }
+            Event::Call {
+                seat,
+                from,
+                category,
+                tiles,
+                called,
+            } => {
+                let mut own: Vec<Tile> = tiles.iter().map(|t| Tile::new(*t)).collect();
+                own.push(*called);
+                let open = OpenTiles {
+                    tiles: OpenTiles::pick_tiles(&own),
+                    category: *category,
+                    from: event::open_from(*seat, *from, players),
+                    called: Some(*called),
+                    added: false,
+                };
+                let word = match (ja, category) {
+                    (true, OpenType::Chi) => "チー",
+                    (true, OpenType::Pon) => "ポン",
+                    (true, OpenType::Kan) => "大明槓",
+                    (false, OpenType::Chi) => "chi",
+                    (false, OpenType::Pon) => "pon",
+                    (false, OpenType::Kan) => "kan",
+                };
+                let meld = Hand::open_to_str(&open, true);
+                self.push(*seat, format!("{} {}", word, meld), true);
+            }
+            Event::ClosedKan { seat, tile } => {
+                let t = Tile::new(*tile);
+                let open = OpenTiles {
+                    tiles: [t; 3],
+                    category: OpenType::Kan,
+                    from: OpenFrom::Myself,
+                    called: None,
+                    added: false,
+                };
+                let word = if ja { "暗槓" } else { "closed kan" };
+                let meld = Hand::open_to_str(&open, true);
+                self.push(*seat, format!("{} {}", word, meld), false);
+            }
+            Event::AddedKan { seat, tile } => {
+                let pon = round.players[*seat]
+                    .hand
+                    .opened()
+                    .iter()
+                    .find(|o| o.category == OpenType::Pon && o.tiles[0].get() == *tile)
+                    .ok_or_else(|| Error::InvalidLog("No pon to add a kan".to_string()))?;
+                let open = OpenTiles {
+                    category: OpenType::Kan,
+                    added: true,
+                    ..*pon
+                };
+                self.added_kan = Some((*seat, *tile));
+                let word = if ja { "加槓" } else { "added kan" };
+                let meld = Hand::open_to_str(&open, true);
+                self.push(*seat, format!("{} {}", word, meld), false);
+            }
+            Event::North { seat, tile } => {
+                self.dead_wall_draw = true;
+                let word = if ja {
+                    "北抜き 嶺上"
+                } else {
+                    "north, replacement"
+                };
+                self.push(*seat, format!("{} {}", word, tile), false);
+            }
+            Event::Dora { indicator } => {
+                let word = if ja {
+                    "新ドラ表示牌"
+                } else {
+                    "new dora indicator"
+                };
+                self.line(format!("  {}:{}", word, indicator));
+            }
+            Event::Win {
+                seat,
+                from,
+                han,
+                fu,
+                yakuman,
+            } => {
+                let winner = self.name(*seat);
+                let head = match (from, ja) {
+                    (Some(d), true) => format!("和了 {} ロン（{}から）", winner, self.name(*d)),
+                    (None, true) => format!("和了 {} 自摸", winner),
+                    (Some(d), false) => format!("Win: {} by ron from {}", winner, self.name(*d)),
+                    (None, false) => format!("Win: {} by tsumo", winner),
+                };
+                let total = match (*yakuman, ja) {
+                    (0, true) => format!("{}符 {}飜", fu, han),
+                    (0, false) => format!("{} fu {} han", fu, han),
+                    (1, true) => "役満".to_string(),
+                    (1, false) => "yakuman".to_string(),
+                    (n, true) => format!("{}倍役満", n),
+                    (n, false) => format!("{}x yakuman", n),
+                };
+                self.line(format!("{} {}", head, total));
+                if let Some(score) = score {
+                    for (_, name, han) in &score.winning_hands {
+                        self.line(format!("  {} {}", name, self.han(*han)));
+                    }
+                    if score.dora > 0 {
+                        let dora = if ja { "ドラ" } else { "Dora" };
+                        self.line(format!("  {} {}", dora, self.han(score.dora)));
+                    }
+                }
+            }
+            Event::ExhaustiveDraw {
+                ready,
+                nagashi_mangan,
+            } => {
+                let ready: Vec<String> = (0..players)
+                    .filter(|&s| ready.get(s) == Some(&true))
+                    .map(|s| self.name(s))
+                    .collect();
+                let line = match (ja, ready.is_empty()) {
+                    (true, true) => "流局 全員不聴".to_string(),
+                    (true, false) => format!("流局 聴牌:{}", ready.join(",")),
+                    (false, true) => "Exhaustive draw, no one tenpai".to_string(),
+                    (false, false) => format!("Exhaustive draw, tenpai: {}", ready.join(",")),
+                };
+                self.line(line);
+                for s in nagashi_mangan {
+                    let word = if ja { "流し満貫" } else { "Nagashi mangan" };
+                    let line = format!("  {} {}", word, self.name(*s));
+                    self.line(line);
+                }
+            }
+            Event::Ready { .. } | Event::EndRound { .. } | Event::Deal { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// 役の翻数（役満は倍数）
+    fn han(&self, han: u32) -> String {
+        match (han >= 13, self.lang) {
+            (true, Lang::Ja) if han >= 26 => format!("{}倍役満", han / 13),
+            (true, Lang::Ja) => "役満".to_string(),
+            (true, Lang::En) if han >= 26 => format!("{}x yakuman", han / 13),
+            (true, Lang::En) => "yakuman".to_string(),
+            (false, Lang::Ja) => format!("{}飜", han),
+            (false, Lang::En) => format!("{} han", han),
+        }
+    }
+
+    /// 点数の移動と局の後の持ち点を書く
+    fn end_round(&mut self, round: &Round, score_changes: &[i32]) {
+        let changes: Vec<String> = score_changes
+            .iter()
+            .enumerate()
+            .map(|(seat, change)| {
+                format!(
+                    "{} {:+} ({})",
+                    self.name(seat),
+                    change,
+                    round.players[seat].score
+                )
+            })
+            .collect();
+        let word = match self.lang {
+            Lang::Ja => "収支",
+            Lang::En => "Score changes",
+        };
+        self.line(format!("{}: {}", word, changes.join(", ")));
+    }
+
+    /// 和了った時の役を求め直す（求められなければ`None`）
+    fn evaluate(
+        &self,
+        round: &Round,
+        wall: &Wall,
+        settings: &Settings,
+        seat: usize,
+        from: Option<usize>,
+    ) -> Result<Option<Score>> {
+        let players = round.players.len();
+        let player = &round.players[seat];
+        let mut hand = player.hand.clone();
+        let mut status = player.status.clone();
+        match from {
+            None => {
+                status.is_self_picked = true;
+                status.is_dead_wall_draw = self.dead_wall_draw;
+                status.is_last_tile = !self.dead_wall_draw && wall.remaining() == 0;
+            }
+            Some(discarder) => {
+                // 加槓の直後なら加えた牌、そうでなければ最後の捨て牌
+                let robbed = self
+                    .added_kan
+                    .filter(|(s, _)| *s == discarder)
+                    .map(|(_, t)| t);
+                let tile = match robbed {
+                    Some(t) => Tile::new(t),
+                    None => match round.players[discarder].river.last() {
+                        Some(d) => d.tile,
+                        None => return Ok(None),
+                    },
+                };
+                let robbed = robbed.is_some();
+                hand.set_drawn(Some(tile));
+                status.is_self_picked = false;
+                status.is_robbing_a_quad = robbed;
+                status.is_last_tile = !robbed && wall.remaining() == 0;
+            }
+        }
+        let mut indicators: Vec<Tile> = wall.dora_indicators().to_vec();
+        if status.has_claimed_ready {
+            indicators.extend_from_slice(wall.ura_dora_indicators());
+        }
+        let dora: Vec<TileType> = indicators
+            .iter()
+            .map(|t| dora_from_indicator(t.get(), players))
+            .collect();
+        score::calculate(&hand, &status, settings, &dora, player.north_bonus)
+    }
+}
+
+/// 風の名前
+fn wind_name(wind: Wind, lang: Lang) -> &'static str {
+    match (wind, lang) {
+        (Wind::East, Lang::Ja) => "東",
+        (Wind::South, Lang::Ja) => "南",
+        (Wind::West, Lang::Ja) => "西",
+        (Wind::North, Lang::Ja) => "北",
+        (Wind::East, Lang::En) => "East",
+        (Wind::South, Lang::En) => "South",
+        (Wind::West, Lang::En) => "West",
+        (Wind::North, Lang::En) => "North",
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::agent::Agent;
+    use crate::board::bot::{GreedyAgent, RandomAgent};
+    use crate::board::engine::{play_game, GameRecord};
+    use crate::board::game::Game;
+    use crate::settings::GameLength;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// `greedy`なら和了が出るように、親の席だけは聴牌を目指す打ち手にする
+    fn play(settings: &Settings, seed: u64, greedy: bool) -> GameRecord {
+        let players = settings.game_mode.players();
+        let mut game = Game::new(settings);
+        let mut agents: Vec<Box<dyn Agent>> = (0..players)
+            .map(|i| match i {
+                0 if greedy => Box::new(GreedyAgent::new()) as Box<dyn Agent>,
+                _ => Box::new(RandomAgent::new(i as u64 + seed)) as Box<dyn Agent>,
+            })
+            .collect();
+        let mut rng = StdRng::seed_from_u64(seed);
+        return play_game(&mut game, &mut agents, settings, &mut rng).unwrap();
+    }
+
+    #[test]
+    /// 局ごとの見出しと、和了の役の翻数の合計が記録と一致する
+    fn export_game() {
+        for mut settings in [Settings::new(), Settings::new_three_players()] {
+            settings.game_length = GameLength::EastOnly;
+            settings.display_lang = Lang::Ja;
+            let players = settings.game_mode.players();
+            let names: Vec<String> = (0..players).map(|i| format!("bot{}", i)).collect();
+            let record = play(&settings, 0, true);
+            let kifu = from_events(&record.events, &settings, &names).unwrap();
+            let deals = record
+                .events
+                .iter()
+                .filter(|e| matches!(e, Event::Deal { .. }))
+                .count();
+            let headers = kifu.lines().filter(|l| l.contains("本場 供託")).count();
+            assert_eq!(headers, deals, "{}", kifu);
+            assert_eq!(
+                kifu.lines().filter(|l| l.starts_with("収支:")).count(),
+                deals
+            );
+
+            let wins: Vec<(u32, u32)> = record
+                .events
+                .iter()
+                .filter_map(|e| match e {
+                    Event::Win { han, yakuman, .. } => Some((*han, *yakuman)),
+                    _ => None,
+                })
+                .collect();
+            let lines: Vec<&str> = kifu.lines().collect();
+            let starts: Vec<usize> = (0..lines.len())
+                .filter(|&i| lines[i].starts_with("和了 "))
+                .collect();
+            assert_eq!(starts.len(), wins.len());
+            assert!(!wins.is_empty());
+            for (&i, &(han, yakuman)) in starts.iter().zip(wins.iter()) {
+                let yaku: Vec<&str> = lines[i + 1..]
+                    .iter()
+                    .take_while(|l| l.starts_with("  "))
+                    .copied()
+                    .collect();
+                assert!(!yaku.is_empty(), "{}", lines[i]);
+                if yakuman == 0 {
+                    let total: u32 = yaku
+                        .iter()
+                        .map(|l| {
+                            let count = l.rsplit(' ').next().unwrap();
+                            count.trim_end_matches('飜').parse::<u32>().unwrap()
+                        })
+                        .sum();
+                    assert_eq!(total, han, "{:?}", yaku);
+                }
+            }
+        }
+    }
+
+    #[test]
+    /// 表示言語を切り替えると見出しや打牌の表記が変わる
+    fn localized() {
+        let mut settings = Settings::new();
+        settings.game_length = GameLength::EastOnly;
+        let record = play(&settings, 7, false);
+        let names: Vec<String> = (0..4).map(|i| format!("bot{}", i)).collect();
+
+        settings.display_lang = Lang::Ja;
+        let ja = from_events(&record.events, &settings, &names).unwrap();
+        assert!(ja.starts_with("東1局 0本場 供託0 親:bot0 ドラ表示牌:"));
+        assert!(ja.contains("  bot1（南） 25000点 配牌:"));
+        assert!(ja.contains("bot0: 自摸 "));
+        assert!(ja.contains(" 打 ") || ja.contains(" ツモ切り "));
+
+        settings.display_lang = Lang::En;
+        let en = from_events(&record.events, &settings, &names).unwrap();
+        assert!(en.starts_with("East 1, 0 honba, 0 riichi sticks, dealer: bot0, dora indicator: "));
+        assert!(en.contains("  bot1 (South) 25000 points, hand: "));
+        assert!(en.contains("bot0: draw "));
+        assert!(en.contains("Score changes: "));
+        assert_eq!(ja.lines().count(), en.lines().count());
+    }
+}
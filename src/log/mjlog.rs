@@ -0,0 +1,836 @@
+use crate::board::event;
+use crate::board::round::{Player, Round};
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::hand_info::opened::{OpenFrom, OpenType};
+use crate::hand_info::status::Status;
+use crate::tile::*;
+
+/// GOタグの`type`で三人麻雀を表すビット
+const TYPE_THREE_PLAYERS: u32 = 0x10;
+/// GOタグの`type`で赤ドラなしを表すビット
+const TYPE_NO_RED: u32 = 0x02;
+
+/// 天鳳の牌譜（展開済みのmjlog形式のXML）
+#[derive(Debug, Clone, PartialEq)]
+pub struct MjLog {
+    /// 対局者の名前（席順）
+    pub names: Vec<String>,
+    /// 対局のルール（GOタグの`type`）
+    pub game_type: u32,
+    /// 人数
+    pub players: usize,
+    /// 局（行われた順）
+    pub rounds: Vec<MjLogRound>,
+}
+
+/// 牌譜の中の1局
+#[derive(Debug, Clone, PartialEq)]
+pub struct MjLogRound {
+    /// 場風
+    pub prevailing_wind: Wind,
+    /// 親の席
+    pub dealer: usize,
+    /// 本場
+    pub honba: u32,
+    /// 供託されている立直棒の本数
+    pub deposits: u32,
+    /// 局の開始時の持ち点（席順）
+    pub scores: Vec<i32>,
+    /// 配牌（席順）
+    pub hands: Vec<Vec<Tile>>,
+    /// 最初のドラ表示牌
+    pub dora_indicator: Tile,
+    /// 局の中の行動（記録された順）
+    pub actions: Vec<Action>,
+}
+
+/// 牌譜に記録された行動
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Action {
+    /// 自摸（嶺上牌を含む）
+    Draw { seat: usize, tile: Tile },
+    /// 打牌（`ready`なら立直宣言牌）
+    Discard {
+        seat: usize,
+        tile: Tile,
+        ready: bool,
+    },
+    /// 鳴き・槓・北抜き
+    Call { seat: usize, meld: Meld },
+    /// 立直の成立（立直棒の供託）
+    Ready { seat: usize },
+    /// ドラ表示牌をめくる
+    Dora { indicator: Tile },
+    /// 和了
+    Win(Win),
+    /// 和了のない局の終了（荒牌平局と途中流局）
+    DrawnRound(DrawnRound),
+}
+
+/// 副露（天鳳の`m`属性を展開したもの）
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Meld {
+    /// チー
+    Chi { tiles: [Tile; 3], called: Tile },
+    /// ポン
+    Pon {
+        tiles: [Tile; 3],
+        called: Tile,
+        from: OpenFrom,
+    },
+    /// 大明槓
+    OpenKan {
+        tiles: [Tile; 4],
+        called: Tile,
+        from: OpenFrom,
+    },
+    /// 暗槓
+    ClosedKan { tiles: [Tile; 4] },
+    /// 加槓（`from`はポンした相手）
+    AddedKan {
+        tiles: [Tile; 4],
+        added: Tile,
+        from: OpenFrom,
+    },
+    /// 北抜き
+    North { tile: Tile },
+}
+
+/// 和了（AGARIタグ）
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Win {
+    /// 和了った席
+    pub seat: usize,
+    /// 放銃した席（自摸なら`None`）
+    pub from: Option<usize>,
+    /// 和了時の手牌（副露を除き、和了牌を含む）
+    pub hand: Vec<Tile>,
+    /// 和了牌
+    pub winning_tile: Tile,
+    /// 符
+    pub fu: u32,
+    /// 和了点
+    pub points: i32,
+    /// 天鳳の役の番号と翻数
+    pub yaku: Vec<(u32, u32)>,
+    /// 天鳳の役満の番号
+    pub yakuman: Vec<u32>,
+    /// ドラ表示牌
+    pub dora_indicators: Vec<Tile>,
+    /// 裏ドラ表示牌
+    pub ura_dora_indicators: Vec<Tile>,
+    /// 点数の移動（席順）
+    pub score_changes: Vec<i32>,
+}
+
+/// 和了のない局の終了（RYUUKYOKUタグ）
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DrawnRound {
+    /// 途中流局の種類（`yao9`など。荒牌平局なら`None`）
+    pub kind: Option<String>,
+    /// 手牌を公開した（聴牌していた）か（席順）
+    pub ready: Vec<bool>,
+    /// 点数の移動（席順）
+    pub score_changes: Vec<i32>,
+}
+
+/// 判断の時点の状態
+#[derive(Debug, Clone)]
+pub struct DecisionPoint {
+    /// 判断した席
+    pub seat: usize,
+    /// 判断の直前の手牌（ロンの場合は和了牌を`drawn`に入れる）
+    pub hand: Hand,
+    /// 判断の直前の状態（和了の場合は自摸・海底・嶺上・搶槓も設定する）
+    pub status: Status,
+    /// 行った行動
+    pub action: Action,
+}
+
+impl MjLog {
+    /// mjlog形式のXMLを読み込む
+    pub fn parse(xml: &str) -> Result<MjLog> {
+        let mut log = MjLog {
+            names: Vec::new(),
+            game_type: 0,
+            players: 4,
+            rounds: Vec::new(),
+        };
+        // 立直宣言した席（次の打牌が宣言牌）
+        let mut declaring: Option<usize> = None;
+        // 最後に打牌した席（鳴かれた相手）
+        let mut last_discarder: Option<usize> = None;
+        // 各席のポンの種類と相手（加槓の相手を求める）
+        let mut pons: Vec<Vec<(TileType, OpenFrom)>> = Vec::new();
+
+        for tag in parse_tags(xml)? {
+            let attr = |name: &str| tag.attr(name);
+            match tag.name.as_str() {
+                "GO" => {
+                    if !log.rounds.is_empty() {
+                        return Err(Error::InvalidLog("GO appears after INIT".to_string()));
+                    }
+                    log.game_type = attr("type").unwrap_or("0").parse()?;
+                    if log.game_type & TYPE_THREE_PLAYERS != 0 {
+                        log.players = 3;
+                    }
+                }
+                "UN" => {
+                    if log.names.is_empty() {
+                        log.names = (0..4)
+                            .filter_map(|i| attr(&format!("n{}", i)))
+                            .map(percent_decode)
+                            .collect::<Result<Vec<String>>>()?;
+                        if log.names.len() == 4 && log.names[3].is_empty() {
+                            log.names.pop();
+                        }
+                    }
+                }
+                "INIT" => {
+                    let seed = parse_numbers::<u32>(attr("seed").unwrap_or(""))?;
+                    if seed.len() < 6 {
+                        return Err(Error::InvalidLog("Invalid INIT seed".to_string()));
+                    }
+                    let scores = parse_numbers::<i32>(attr("ten").unwrap_or(""))?;
+                    if scores.len() < log.players {
+                        return Err(Error::InvalidLog("Invalid INIT ten".to_string()));
+                    }
+                    let scores = scores
+                        .iter()
+                        .take(log.players)
+                        .map(|s| s.checked_mul(100))
+                        .collect::<Option<Vec<i32>>>()
+                        .ok_or_else(|| Error::InvalidLog("Invalid INIT ten".to_string()))?;
+                    let mut hands: Vec<Vec<Tile>> = Vec::new();
+                    for seat in 0..log.players {
+                        let ids =
+                            parse_numbers::<u32>(attr(&format!("hai{}", seat)).unwrap_or(""))?;
+                        let mut hand = ids
+                            .iter()
+                            .map(|&id| log.tile(id))
+                            .collect::<Result<Vec<Tile>>>()?;
+                        hand.sort();
+                        hands.push(hand);
+                    }
+                    let dealer = parse_seat(Some(attr("oya").unwrap_or("0")), log.players)?;
+                    log.rounds.push(MjLogRound {
+                        prevailing_wind: match seed[0] / 4 {
+                            0 => Wind::East,
+                            1 => Wind::South,
+                            2 => Wind::West,
+                            _ => Wind::North,
+                        },
+                        dealer,
+                        honba: seed[1],
+                        deposits: seed[2],
+                        scores,
+                        hands,
+                        dora_indicator: log.tile(seed[5])?,
+                        actions: Vec::new(),
+                    });
+                    declaring = None;
+                    last_discarder = None;
+                    pons = vec![Vec::new(); log.players];
+                }
+                "N" => {
+                    let seat = parse_seat(attr("who"), log.players)?;
+                    let code: u32 = attr("m").unwrap_or("").parse()?;
+                    let mut meld = log.decode_meld(code)?;
+                    // INITより前に鳴くことはできない（`pons`もまだない）
+                    log.current_round()?;
+                    match &mut meld {
+                        Meld::Chi { .. } | Meld::Pon { .. } | Meld::OpenKan { .. } => {
+                            let discarder = last_discarder.ok_or_else(|| {
+                                Error::InvalidLog(format!("No discard to call: m={}", code))
+                            })?;
+                            let open_from = event::open_from(seat, discarder, log.players);
+                            if let Meld::Pon { tiles, from, .. } = &mut meld {
+                                *from = open_from;
+                                pons[seat].push((tiles[0].get(), open_from));
+                            } else if let Meld::OpenKan { from, .. } = &mut meld {
+                                *from = open_from;
+                            }
+                        }
+                        Meld::AddedKan { tiles, from, .. } => {
+                            if let Some((_, f)) =
+                                pons[seat].iter().find(|(t, _)| *t == tiles[0].get())
+                            {
+                                *from = *f;
+                            }
+                        }
+                        Meld::ClosedKan { .. } | Meld::North { .. } => {}
+                    }
+                    log.current_round()?
+                        .actions
+                        .push(Action::Call { seat, meld });
+                }
+                "REACH" => {
+                    let seat = parse_seat(attr("who"), log.players)?;
+                    if attr("step") == Some("1") {
+                        declaring = Some(seat);
+                    } else {
+                        log.current_round()?.actions.push(Action::Ready { seat });
+                    }
+                }
+                "DORA" => {
+                    let indicator = log.tile(attr("hai").unwrap_or("").parse()?)?;
+                    log.current_round()?
+                        .actions
+                        .push(Action::Dora { indicator });
+                }
+                "AGARI" => {
+                    let win = log.parse_win(&tag)?;
+                    log.current_round()?.actions.push(Action::Win(win));
+                }
+                "RYUUKYOKU" => {
+                    let drawn = DrawnRound {
+                        kind: attr("type").map(String::from),
+                        ready: (0..log.players)
+                            .map(|i| attr(&format!("hai{}", i)).is_some())
+                            .collect(),
+                        score_changes: score_changes(attr("sc").unwrap_or(""), log.players)?,
+                    };
+                    log.current_round()?.actions.push(Action::DrawnRound(drawn));
+                }
+                name => {
+                    // 自摸（T・U・V・W）と打牌（D・E・F・G）は牌の番号が続く
+                    let mut chars = name.chars();
+                    let (head, id) = match (chars.next(), chars.as_str().parse::<u32>()) {
+                        (Some(head), Ok(id)) => (head, id),
+                        _ => continue,
+                    };
+                    let seat = match "TUVW".find(head).or_else(|| "DEFG".find(head)) {
+                        Some(seat) => seat,
+                        None => continue,
+                    };
+                    if seat >= log.players {
+                        return Err(Error::InvalidLog(format!("Invalid seat: {}", name)));
+                    }
+                    let tile = log.tile(id)?;
+                    if "TUVW".contains(head) {
+                        log.current_round()?
+                            .actions
+                            .push(Action::Draw { seat, tile });
+                    } else {
+                        let ready = declaring == Some(seat);
+                        if ready {
+                            declaring = None;
+                        }
+                        last_discarder = Some(seat);
+                        log.current_round()?
+                            .actions
+                            .push(Action::Discard { seat, tile, ready });
+                    }
+                }
+            }
+        }
+        Ok(log)
+    }
+
+    /// 天鳳の牌の番号（0〜135）を牌にする
+    ///
+    /// 赤ドラありのルールでは、各五の最初の1枚（16・52・88）が赤ドラになる。
+    pub fn tile(&self, id: u32) -> Result<Tile> {
+        if id >= 136 {
+            return Err(Error::InvalidLog(format!("Invalid tile id: {}", id)));
+        }
+        let tile_type = id / 4;
+        if self.game_type & TYPE_NO_RED == 0 && matches!(id, 16 | 52 | 88) {
+            return Ok(Tile::new_red(tile_type));
+        }
+        Ok(Tile::new(tile_type))
+    }
+
+    /// 天鳳の副露の`m`属性を展開する
+    ///
+    /// 鳴いた相手は天鳳の四人麻雀の定義（`m`の下位2ビット）に従う。
+    pub fn decode_meld(&self, m: u32) -> Result<Meld> {
+        let from = match m & 3 {
+            1 => OpenFrom::Following,
+            2 => OpenFrom::Opposite,
+            3 => OpenFrom::Previous,
+            _ => OpenFrom::Myself,
+        };
+        if m & 0x4 != 0 {
+            // チー
+            let t = (m & 0xFC00) >> 10;
+            let called = (t % 3) as usize;
+            let t = t / 3;
+            let base = t / 7 * 9 + t % 7;
+            let mut tiles = [Tile::new(0); 3];
+            for i in 0..3 {
+                let offset = (m >> (3 + 2 * i)) & 3;
+                tiles[i as usize] = self.tile((base + i) * 4 + offset)?;
+            }
+            return Ok(Meld::Chi {
+                tiles,
+                called: tiles[called],
+            });
+        }
+        if m & 0x18 != 0 {
+            // ポン・加槓
+            let t = (m & 0xFE00) >> 9;
+            let called = (t % 3) as usize;
+            let kind = t / 3;
+            let unused = (m & 0x60) >> 5;
+            let mut three: Vec<Tile> = Vec::new();
+            for i in 0..4 {
+                if i != unused {
+                    three.push(self.tile(kind * 4 + i)?);
+                }
+            }
+            let tiles = [three[0], three[1], three[2]];
+            if m & 0x8 != 0 {
+                return Ok(Meld::Pon {
+                    tiles,
+                    called: tiles[called],
+                    from,
+                });
+            }
+            let added = self.tile(kind * 4 + unused)?;
+            return Ok(Meld::AddedKan {
+                tiles: [tiles[0], tiles[1], tiles[2], added],
+                added,
+                from,
+            });
+        }
+        if m & 0x20 != 0 {
+            return Ok(Meld::North {
+                tile: Tile::new(Tile::Z4),
+            });
+        }
+        // 大明槓・暗槓
+        let id = (m & 0xFF00) >> 8;
+        let kind = id / 4;
+        let mut tiles = [Tile::new(0); 4];
+        for i in 0..4 {
+            tiles[i as usize] = self.tile(kind * 4 + i)?;
+        }
+        if from == OpenFrom::Myself {
+            return Ok(Meld::ClosedKan { tiles });
+        }
+        Ok(Meld::OpenKan {
+            tiles,
+            called: self.tile(id)?,
+            from,
+        })
+    }
+
+    fn current_round(&mut self) -> Result<&mut MjLogRound> {
+        self.rounds
+            .last_mut()
+            .ok_or_else(|| Error::InvalidLog("A round element appears before INIT".to_string()))
+    }
+
+    fn parse_win(&self, tag: &Tag) -> Result<Win> {
+        let tiles = |name: &str| -> Result<Vec<Tile>> {
+            parse_numbers::<u32>(tag.attr(name).unwrap_or(""))?
+                .iter()
+                .map(|&id| self.tile(id))
+                .collect()
+        };
+        let pairs = |name: &str| -> Result<Vec<(u32, u32)>> {
+            let numbers = parse_numbers::<u32>(tag.attr(name).unwrap_or(""))?;
+            Ok(numbers
+                .chunks(2)
+                .map(|c| (c[0], *c.get(1).unwrap_or(&0)))
+                .collect())
+        };
+        let seat = parse_seat(tag.attr("who"), self.players)?;
+        let from = parse_seat(tag.attr("fromWho"), self.players)?;
+        let ten = parse_numbers::<i32>(tag.attr("ten").unwrap_or(""))?;
+        Ok(Win {
+            seat,
+            from: if from == seat { None } else { Some(from) },
+            hand: tiles("hai")?,
+            winning_tile: self.tile(tag.attr("machi").unwrap_or("").parse()?)?,
+            fu: *ten.first().unwrap_or(&0) as u32,
+            points: *ten.get(1).unwrap_or(&0),
+            yaku: pairs("yaku")?,
+            yakuman: parse_numbers::<u32>(tag.attr("yakuman").unwrap_or(""))?,
+            dora_indicators: tiles("doraHai")?,
+            ura_dora_indicators: tiles("doraHaiUra")?,
+            score_changes: score_changes(tag.attr("sc").unwrap_or(""), self.players)?,
+        })
+    }
+}
+
+impl MjLogRound {
+    /// 配牌時点の局を作る
+    pub fn round(&self) -> Round {
+        let players = self
+            .hands
+            .iter()
+            .zip(&self.scores)
+            .map(|(hand, score)| Player::new(Hand::new(hand.clone(), None), *score))
+            .collect();
+        Round::new(
+            self.prevailing_wind,
+            self.dealer,
+            self.honba,
+            self.deposits,
+            players,
+        )
+    }
+
+    /// 各プレイヤーが判断した直前の手牌と状態を順に再現する
+    ///
+    /// 打牌・鳴き・槓・北抜き・和了が判断にあたる。
+    /// 鳴きで手牌から出す牌は種類のみで選ぶので、同じ種類の赤ドラと通常の牌を持っている場合はどちらが残るかが記録と異なることがある。
+    pub fn decision_points(&self) -> Result<Vec<DecisionPoint>> {
+        let mut round = self.round();
+        let players = self.hands.len();
+        let total = if players == 3 { 108 } else { 136 };
+        let mut remaining = total - 14 - 13 * players;
+        let mut last_discarder: Option<usize> = None;
+        let mut after_kan = false;
+        let mut after_added_kan = false;
+        let mut result: Vec<DecisionPoint> = Vec::new();
+
+        for action in &self.actions {
+            let seat = match action {
+                Action::Discard { seat, .. } | Action::Call { seat, .. } => *seat,
+                Action::Win(win) => win.seat,
+                Action::Draw { seat, tile } => {
+                    round.players[*seat].hand.set_drawn(Some(*tile));
+                    remaining = remaining.saturating_sub(1);
+                    after_added_kan = false;
+                    continue;
+                }
+                Action::Ready { seat } => {
+                    event::ready(&mut round, *seat)?;
+                    continue;
+                }
+                Action::Dora { .. } | Action::DrawnRound(_) => continue,
+            };
+            let mut point = DecisionPoint {
+                seat,
+                hand: round.players[seat].hand.clone(),
+                status: round.players[seat].status.clone(),
+                action: action.clone(),
+            };
+            match action {
+                Action::Discard { tile, ready, .. } => {
+                    event::discard_tile(&mut round, seat, *tile, *ready)?;
+                    last_discarder = Some(seat);
+                    after_kan = false;
+                }
+                Action::Call { meld, .. } => {
+                    after_kan = true;
+                    match meld {
+                        Meld::Chi { tiles, called } | Meld::Pon { tiles, called, .. } => {
+                            let own = own_tiles(tiles, *called);
+                            let category = match meld {
+                                Meld::Chi { .. } => OpenType::Chi,
+                                _ => OpenType::Pon,
+                            };
+                            let from = last_discarder.ok_or_else(|| {
+                                Error::InvalidLog("No discard to call".to_string())
+                            })?;
+                            event::call(&mut round, seat, from, category, &own)?;
+                            after_kan = false;
+                        }
+                        Meld::OpenKan { tiles, called, .. } => {
+                            let own = own_tiles(tiles, *called);
+                            let from = last_discarder.ok_or_else(|| {
+                                Error::InvalidLog("No discard to call".to_string())
+                            })?;
+                            event::call(&mut round, seat, from, OpenType::Kan, &own)?;
+                        }
+                        Meld::ClosedKan { tiles } => {
+                            event::closed_kan(&mut round, seat, tiles[0].get())?;
+                        }
+                        Meld::AddedKan { tiles, .. } => {
+                            event::added_kan(&mut round, seat, tiles[0].get())?;
+                            after_added_kan = true;
+                        }
+                        Meld::North { .. } => {
+                            let player = &mut round.players[seat];
+                            player.hand.discard(Tile::Z4).ok_or_else(|| {
+                                Error::InvalidLog("No north tile to extract".to_string())
+                            })?;
+                            player.north_bonus += 1;
+                        }
+                    }
+                }
+                Action::Win(win) => {
+                    let is_self_picked = win.from.is_none();
+                    if !is_self_picked {
+                        point.hand.set_drawn(Some(win.winning_tile));
+                    }
+                    point.status.is_self_picked = is_self_picked;
+                    point.status.is_dead_wall_draw = is_self_picked && after_kan;
+                    point.status.is_robbing_a_quad = !is_self_picked && after_added_kan;
+                    point.status.is_last_tile = remaining == 0
+                        && !point.status.is_dead_wall_draw
+                        && !point.status.is_robbing_a_quad;
+                }
+                _ => {}
+            }
+            result.push(point);
+        }
+        Ok(result)
+    }
+}
+
+/// 席の番号を読み込む（人数の範囲外ならエラー）
+fn parse_seat(value: Option<&str>, players: usize) -> Result<usize> {
+    let seat: usize = value.unwrap_or("").parse()?;
+    if seat >= players {
+        return Err(Error::InvalidLog(format!("Invalid seat: {}", seat)));
+    }
+    Ok(seat)
+}
+
+/// 鳴いた面子から手牌から出した牌の種類を求める
+fn own_tiles(tiles: &[Tile], called: Tile) -> Vec<TileType> {
+    let mut result: Vec<TileType> = tiles.iter().map(|t| t.get()).collect();
+    if let Some(pos) = tiles.iter().position(|t| *t == called) {
+        result.remove(pos);
+    }
+    result
+}
+
+/// XMLの要素
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// XMLを開始タグ（空要素を含む）の列に分解する（閉じタグ・宣言は読み飛ばす）
+fn parse_tags(xml: &str) -> Result<Vec<Tag>> {
+    let mut result: Vec<Tag> = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        let end = rest[start..]
+            .find('>')
+            .ok_or_else(|| Error::InvalidLog("Unclosed tag in XML".to_string()))?
+            + start;
+        let body = rest[start + 1..end].trim_end_matches('/').trim();
+        rest = &rest[end + 1..];
+        if body.starts_with('/') || body.starts_with('?') || body.starts_with('!') {
+            continue;
+        }
+        let (name, mut attrs_str) = match body.find(char::is_whitespace) {
+            Some(pos) => (&body[..pos], &body[pos..]),
+            None => (body, ""),
+        };
+        let mut attrs: Vec<(String, String)> = Vec::new();
+        while let Some(eq) = attrs_str.find('=') {
+            let key = attrs_str[..eq].trim().to_string();
+            let after = &attrs_str[eq + 1..];
+            let open = after
+                .find('"')
+                .ok_or_else(|| Error::InvalidLog(format!("Invalid attribute: {}", key)))?;
+            let close = after[open + 1..]
+                .find('"')
+                .ok_or_else(|| Error::InvalidLog(format!("Invalid attribute: {}", key)))?
+                + open
+                + 1;
+            attrs.push((key, after[open + 1..close].to_string()));
+            attrs_str = &after[close + 1..];
+        }
+        result.push(Tag {
+            name: name.to_string(),
+            attrs,
+        });
+    }
+    Ok(result)
+}
+
+/// カンマ区切りの数値を読み込む
+fn parse_numbers<T: std::str::FromStr>(s: &str) -> Result<Vec<T>> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    s.split(',')
+        .map(|x| {
+            x.trim()
+                .parse::<T>()
+                .map_err(|_| Error::InvalidLog(format!("Invalid number: {}", x)))
+        })
+        .collect()
+}
+
+/// `sc`属性（100点単位の持ち点と移動の組）から点数の移動を求める
+fn score_changes(sc: &str, players: usize) -> Result<Vec<i32>> {
+    let numbers = parse_numbers::<f64>(sc)?;
+    Ok((0..players)
+        .map(|i| {
+            numbers
+                .get(i * 2 + 1)
+                .map_or(0, |x| (x * 100.0).round() as i32)
+        })
+        .collect())
+}
+
+/// URLエンコードされた名前を復元する
+fn percent_decode(s: &str) -> Result<String> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex: String = iter.by_ref().take(2).map(|c| c as char).collect();
+            bytes.push(
+                u8::from_str_radix(&hex, 16)
+                    .map_err(|_| Error::InvalidLog(format!("Invalid name: {}", s)))?,
+            );
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| Error::InvalidLog(format!("Invalid name: {}", s)))
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Settings;
+    use crate::winning_hand::name::Kind;
+    use crate::winning_hand::score;
+
+    const SAMPLE: &str = include_str!("../../testdata/sample.mjlog");
+
+    #[test]
+    /// 牌譜全体を読み込む
+    fn parse_sample() {
+        let log = MjLog::parse(SAMPLE).unwrap();
+        assert_eq!(log.players, 4);
+        assert_eq!(log.names, vec!["あ", "B", "C", "D"]);
+        assert_eq!(log.rounds.len(), 2);
+
+        let first = &log.rounds[0];
+        assert_eq!(first.scores, vec![25000; 4]);
+        assert_eq!(first.dora_indicator, Tile::new(Tile::Z7));
+        // 赤五筒
+        assert!(first.hands[0].contains(&Tile::new_red(Tile::P5)));
+        assert!(first.actions.contains(&Action::Dora {
+            indicator: Tile::new(Tile::Z3)
+        }));
+        match first.actions.last() {
+            Some(Action::Win(win)) => {
+                assert_eq!((win.seat, win.from), (0, Some(1)));
+                assert_eq!(win.winning_tile, Tile::new(Tile::P4));
+                assert_eq!((win.fu, win.points), (30, 12000));
+                assert_eq!(win.score_changes, vec![13000, -12000, 0, 0]);
+                assert_eq!(win.ura_dora_indicators.len(), 2);
+            }
+            action => panic!("Unexpected action: {:?}", action),
+        }
+
+        let second = &log.rounds[1];
+        assert_eq!((second.honba, second.scores[0]), (1, 37000));
+        match second.actions.last() {
+            Some(Action::DrawnRound(drawn)) => {
+                assert_eq!(drawn.kind.as_deref(), Some("yao9"));
+                assert_eq!(drawn.ready, vec![true, false, false, false]);
+            }
+            action => panic!("Unexpected action: {:?}", action),
+        }
+    }
+
+    #[test]
+    /// 副露の`m`属性
+    fn decode_meld() {
+        let log = MjLog::parse(SAMPLE).unwrap();
+        let t = |id: u32| log.tile(id).unwrap();
+        // 上家の白をポン
+        assert_eq!(
+            log.decode_meld(47723).unwrap(),
+            Meld::Pon {
+                tiles: [t(124), t(125), t(126)],
+                called: t(124),
+                from: OpenFrom::Previous,
+            }
+        );
+        // 九萬をチー
+        assert_eq!(
+            log.decode_meld(20487).unwrap(),
+            Meld::Chi {
+                tiles: [t(24), t(28), t(32)],
+                called: t(32),
+            }
+        );
+        assert_eq!(
+            log.decode_meld(18432).unwrap(),
+            Meld::ClosedKan {
+                tiles: [t(72), t(73), t(74), t(75)],
+            }
+        );
+        // 加槓は対象のポンと同じ位置に加える牌が入る
+        assert_eq!(
+            log.decode_meld(47723 ^ 0x18).unwrap(),
+            Meld::AddedKan {
+                tiles: [t(124), t(125), t(126), t(127)],
+                added: t(127),
+                from: OpenFrom::Previous,
+            }
+        );
+    }
+
+    #[test]
+    /// 人数の範囲外の席はエラーになる
+    fn invalid_seats() {
+        for (from, to) in [
+            (r#"<N who="1" m="47723"/>"#, r#"<N who="9" m="47723"/>"#),
+            (r#"who="0" fromWho="1""#, r#"who="7" fromWho="1""#),
+            (r#"who="0" fromWho="1""#, r#"who="0" fromWho="4""#),
+            (r#"<REACH who="0" step="1"/>"#, r#"<REACH who="4" step="1"/>"#),
+            (r#"ten="250,250,250,250" oya="0""#, r#"ten="250,250,250,250" oya="5""#),
+            (r#"<GO type="169""#, r#"<GO type="185""#),
+        ] {
+            let xml = SAMPLE.replacen(from, to, 1);
+            assert!(
+                matches!(MjLog::parse(&xml), Err(Error::InvalidLog(_))),
+                "{}",
+                to
+            );
+        }
+    }
+
+    #[test]
+    /// 判断の時点の手牌と状態を再現する
+    fn decision_points() {
+        let log = MjLog::parse(SAMPLE).unwrap();
+        let points = log.rounds[0].decision_points().unwrap();
+
+        // 西家が九萬をチーする直前の手牌
+        let chi = points
+            .iter()
+            .find(|p| matches!(p.action, Action::Call { seat: 2, .. }))
+            .unwrap();
+        assert_eq!(chi.hand.to_string(), "6m6m7m8m4s5s6s1z2z2z3z3z4z");
+
+        // 暗槓は自分から
+        let kan = points
+            .iter()
+            .position(|p| matches!(p.action, Action::Call { seat: 3, .. }))
+            .unwrap();
+        assert_eq!(points[kan + 1].hand.opened()[0].from, OpenFrom::Myself);
+
+        // 立直一発・平和のロン和了
+        let win = points.last().unwrap();
+        assert_eq!(win.seat, 0);
+        assert!(win.status.has_claimed_ready && win.status.is_one_shot);
+        let score = score::calculate(&win.hand, &win.status, &Settings::new(), &[], 0)
+            .unwrap()
+            .unwrap();
+        let kinds: Vec<Kind> = score.winning_hands.iter().map(|(k, _, _)| *k).collect();
+        assert_eq!(
+            kinds,
+            vec![Kind::ReadyHand, Kind::OneShot, Kind::NoPointsHand]
+        );
+        assert_eq!(score.fu, 30);
+    }
+}
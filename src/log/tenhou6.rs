@@ -0,0 +1,1340 @@
+use crate::board::event::{self, Event, Replay};
+use crate::board::payment::{base_points, ron_payment, tsumo_payments, yakuman_base_points};
+use crate::board::round::Round;
+use crate::error::{Error, Result};
+use crate::hand_info::opened::{OpenFrom, OpenType};
+use crate::log::json::Json;
+use crate::log::mjlog::{self, Action, DrawnRound, Meld, MjLog, MjLogRound};
+use crate::settings::{GameLength, Lang, Settings};
+use crate::tile::*;
+use crate::winning_hand::announcement::limit_name;
+
+/// 牌譜の1局に並ぶ席の数（三人麻雀でも4席分を書く）
+const SEATS: usize = 4;
+/// ツモ切りを表す打牌
+const TSUMOGIRI: i64 = 60;
+/// 大明槓の後の打牌の欄を埋める値
+const SKIP: i64 = 0;
+
+/// tenhou.net/6の牌譜ビューアで使われるJSON形式の牌譜
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tenhou6 {
+    /// 表題
+    pub title: Vec<String>,
+    /// 対局者の名前（席順）
+    pub names: Vec<String>,
+    /// ルールの表示名（`般南喰赤`など）
+    pub rule: String,
+    /// 赤ドラありか
+    pub red: bool,
+    /// 局（行われた順）
+    pub rounds: Vec<Tenhou6Round>,
+}
+
+/// 牌譜の中の1局
+///
+/// 自摸・鳴きと打牌・槓は席ごとに別々の列に記録され、順番は列同士を突き合わせて求める。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tenhou6Round {
+    /// 場風
+    pub prevailing_wind: Wind,
+    /// 親の席
+    pub dealer: usize,
+    /// 本場
+    pub honba: u32,
+    /// 供託されている立直棒の本数
+    pub deposits: u32,
+    /// 局の開始時の持ち点（席順）
+    pub scores: Vec<i32>,
+    /// ドラ表示牌（めくられた順）
+    pub dora_indicators: Vec<Tile>,
+    /// 裏ドラ表示牌（立直者が和了った時のみ）
+    pub ura_dora_indicators: Vec<Tile>,
+    /// 配牌（席順）
+    pub hands: Vec<Vec<Tile>>,
+    /// 自摸とポン・チー・大明槓（席順）
+    pub takes: Vec<Vec<Take>>,
+    /// 打牌と暗槓・加槓・北抜き（席順）
+    pub discards: Vec<Vec<Discard>>,
+    /// 局の結果
+    pub result: Outcome,
+}
+
+/// 手牌に入る牌
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Take {
+    /// 自摸（嶺上牌を含む）
+    Tile(Tile),
+    /// ポン・チー・大明槓
+    Call(Meld),
+}
+
+/// 手牌から出す牌
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Discard {
+    /// 手出し（`ready`なら立直宣言牌）
+    Tile { tile: Tile, ready: bool },
+    /// ツモ切り（`ready`なら立直宣言牌）
+    Tsumogiri { ready: bool },
+    /// 暗槓・加槓・北抜き
+    Call(Meld),
+    /// 大明槓の後の空欄
+    Skip,
+}
+
+/// 局の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    /// 結果の名前（`和了`・`流局`・`九種九牌`など）
+    pub name: String,
+    /// 点数の移動（和了ごと。流局なら1つ以下）
+    pub score_changes: Vec<Vec<i32>>,
+    /// 和了
+    pub wins: Vec<WinInfo>,
+}
+
+/// 和了の情報
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WinInfo {
+    /// 和了った席
+    pub seat: usize,
+    /// 放銃した席（自摸なら和了った席）
+    pub from: usize,
+    /// 責任払いの席（なければ和了った席）
+    pub liable: usize,
+    /// 符・翻・点数の表記（`30符1飜1000点`・`満貫8000点`など）
+    pub description: String,
+    /// 役の表記（`立直(1飜)`など）
+    pub yaku: Vec<String>,
+}
+
+impl Tenhou6 {
+    /// JSON形式の牌譜を読み込む
+    pub fn parse(json: &str) -> Result<Tenhou6> {
+        let root = Json::parse(json)?;
+        let strings = |key: &str| -> Result<Vec<String>> {
+            match root.get(key) {
+                Some(value) => value
+                    .as_array()?
+                    .iter()
+                    .map(|v| v.as_str().map(String::from))
+                    .collect(),
+                None => Ok(Vec::new()),
+            }
+        };
+        let rule = root.get("rule");
+        let mut result = Tenhou6 {
+            title: strings("title")?,
+            names: strings("name")?,
+            rule: match rule.and_then(|r| r.get("disp")) {
+                Some(disp) => disp.as_str()?.to_string(),
+                None => String::new(),
+            },
+            red: match rule.and_then(|r| r.get("aka")) {
+                Some(aka) => aka.as_i64()? > 0,
+                None => false,
+            },
+            rounds: Vec::new(),
+        };
+        let log = root
+            .get("log")
+            .ok_or_else(|| Error::InvalidLog("No log in tenhou.net/6 JSON".to_string()))?;
+        for round in log.as_array()? {
+            result.rounds.push(Tenhou6Round::parse(round)?);
+        }
+        let players = result.players();
+        result.names.truncate(players);
+        Ok(result)
+    }
+
+    /// JSON形式の牌譜にする
+    pub fn to_json(&self) -> String {
+        let mut names: Vec<Json> = self.names.iter().map(|n| Json::from(n.as_str())).collect();
+        names.resize(SEATS, Json::from(""));
+        let root = Json::Object(vec![
+            (
+                "title".to_string(),
+                Json::Array(self.title.iter().map(|t| Json::from(t.as_str())).collect()),
+            ),
+            ("name".to_string(), Json::Array(names)),
+            (
+                "rule".to_string(),
+                Json::Object(vec![
+                    ("disp".to_string(), Json::from(self.rule.as_str())),
+                    ("aka".to_string(), Json::from(self.red as i64)),
+                ]),
+            ),
+            (
+                "log".to_string(),
+                Json::Array(self.rounds.iter().map(|r| r.to_json()).collect()),
+            ),
+        ]);
+        root.to_string()
+    }
+
+    /// 人数
+    pub fn players(&self) -> usize {
+        self.rounds.first().map_or(SEATS, |r| r.hands.len())
+    }
+
+    /// 天鳳の牌譜（mjlog形式）として読んだ場合と同じ形にする
+    ///
+    /// 役は名前しか記録されていないため、和了の`yaku`・`yakuman`は空になる。
+    /// 槓ドラは槓の直後にめくったものとする。
+    pub fn to_mjlog(&self) -> Result<MjLog> {
+        let players = self.players();
+        let mut game_type = 0x01;
+        if !self.red {
+            game_type |= 0x02;
+        }
+        if self.rule.contains('南') {
+            game_type |= 0x08;
+        }
+        if players == 3 {
+            game_type |= 0x10;
+        }
+        Ok(MjLog {
+            names: self.names.clone(),
+            game_type,
+            players,
+            rounds: self
+                .rounds
+                .iter()
+                .map(|r| r.to_mjlog_round())
+                .collect::<Result<Vec<MjLogRound>>>()?,
+        })
+    }
+
+    /// 局の進行で記録した出来事から牌譜を作る
+    ///
+    /// 記録には役が含まれないため、和了の`yaku`は空になる。立直棒の供託は点数の移動に含めない。
+    pub fn from_events(events: &[Event], settings: &Settings, names: &[String]) -> Result<Tenhou6> {
+        let players = settings.game_mode.players();
+        let mut rule = String::new();
+        if players == 3 {
+            rule.push('三');
+        }
+        rule.push('般');
+        rule.push(match settings.game_length {
+            GameLength::EastOnly => '東',
+            GameLength::EastSouth => '南',
+        });
+        rule.push('喰');
+        let mut result = Tenhou6 {
+            title: vec![String::new(), String::new()],
+            names: names.to_vec(),
+            rule,
+            red: false,
+            rounds: Vec::new(),
+        };
+        let mut replay = Replay::new(settings);
+        let mut recorder: Option<Recorder> = None;
+        for e in events {
+            if let Event::Deal { .. } = e {
+                replay.apply(e)?;
+                let round = replay
+                    .round
+                    .as_ref()
+                    .ok_or_else(|| Error::InvalidLog("No round".to_string()))?;
+                recorder = Some(Recorder::new(round));
+                continue;
+            }
+            let (recorder, round) = match (recorder.as_mut(), replay.round.as_ref()) {
+                (Some(recorder), Some(round)) => (recorder, round),
+                _ => return Err(Error::NoRound(Box::new(e.clone()))),
+            };
+            recorder.record(e, round, settings)?;
+            replay.apply(e)?;
+            if let Event::EndRound { .. } = e {
+                let wall = replay
+                    .wall
+                    .as_ref()
+                    .ok_or_else(|| Error::InvalidLog("No wall".to_string()))?;
+                recorder.round.dora_indicators = wall.dora_indicators().to_vec();
+                if recorder.ura_dora {
+                    recorder.round.ura_dora_indicators = wall.ura_dora_indicators().to_vec();
+                }
+                result.rounds.push(recorder.round.clone());
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Tenhou6Round {
+    fn parse(json: &Json) -> Result<Tenhou6Round> {
+        let values = json.as_array()?;
+        if values.len() < 4 + 3 * SEATS + 1 {
+            return Err(Error::InvalidLog(format!(
+                "Too few entries in a round: {}",
+                values.len()
+            )));
+        }
+        let numbers = |value: &Json| -> Result<Vec<i64>> {
+            value.as_array()?.iter().map(|v| v.as_i64()).collect()
+        };
+        let tiles = |value: &Json| -> Result<Vec<Tile>> {
+            numbers(value)?.into_iter().map(tile_from_code).collect()
+        };
+        let info = numbers(&values[0])?;
+        if info.len() < 3 {
+            return Err(Error::InvalidLog(format!(
+                "Invalid round information: {}",
+                values[0]
+            )));
+        }
+        let mut hands: Vec<Vec<Tile>> = Vec::new();
+        let mut takes: Vec<Vec<Take>> = Vec::new();
+        let mut discards: Vec<Vec<Discard>> = Vec::new();
+        for seat in 0..SEATS {
+            let hand = tiles(&values[4 + seat * 3])?;
+            // 三人麻雀では4席目が空になる
+            if hand.is_empty() {
+                break;
+            }
+            hands.push(hand);
+            takes.push(
+                values[5 + seat * 3]
+                    .as_array()?
+                    .iter()
+                    .map(parse_take)
+                    .collect::<Result<Vec<Take>>>()?,
+            );
+            discards.push(
+                values[6 + seat * 3]
+                    .as_array()?
+                    .iter()
+                    .map(parse_discard)
+                    .collect::<Result<Vec<Discard>>>()?,
+            );
+        }
+        let players = hands.len();
+        let mut scores: Vec<i32> = numbers(&values[1])?.iter().map(|&s| s as i32).collect();
+        if scores.len() < players {
+            return Err(Error::InvalidLog(format!("Too few scores: {}", values[1])));
+        }
+        scores.truncate(players);
+        if info[0] < 0 {
+            return Err(Error::InvalidLog(format!(
+                "Invalid round information: {}",
+                values[0]
+            )));
+        }
+        Ok(Tenhou6Round {
+            prevailing_wind: match info[0] / 4 {
+                0 => Wind::East,
+                1 => Wind::South,
+                2 => Wind::West,
+                _ => Wind::North,
+            },
+            dealer: seat(info[0] % 4, players)?,
+            honba: info[1] as u32,
+            deposits: info[2] as u32,
+            scores,
+            dora_indicators: tiles(&values[2])?,
+            ura_dora_indicators: tiles(&values[3])?,
+            hands,
+            takes,
+            discards,
+            result: Outcome::parse(&values[4 + 3 * SEATS], players)?,
+        })
+    }
+
+    fn to_json(&self) -> Json {
+        let tiles =
+            |tiles: &[Tile]| Json::Array(tiles.iter().map(|t| tile_to_code(*t).into()).collect());
+        let wind = match self.prevailing_wind {
+            Wind::East => 0,
+            Wind::South => 1,
+            Wind::West => 2,
+            Wind::North => 3,
+        };
+        let mut scores: Vec<Json> = self.scores.iter().map(|&s| Json::from(s as i64)).collect();
+        scores.resize(SEATS, Json::from(0));
+        let mut result = vec![
+            Json::Array(vec![
+                Json::from(wind * 4 + self.dealer as i64),
+                Json::from(self.honba as i64),
+                Json::from(self.deposits as i64),
+            ]),
+            Json::Array(scores),
+            tiles(&self.dora_indicators),
+            tiles(&self.ura_dora_indicators),
+        ];
+        for seat in 0..SEATS {
+            match self.hands.get(seat) {
+                Some(hand) => {
+                    result.push(tiles(hand));
+                    result.push(Json::Array(
+                        self.takes[seat].iter().map(take_to_json).collect(),
+                    ));
+                    result.push(Json::Array(
+                        self.discards[seat].iter().map(discard_to_json).collect(),
+                    ));
+                }
+                None => {
+                    for _ in 0..3 {
+                        result.push(Json::Array(Vec::new()));
+                    }
+                }
+            }
+        }
+        result.push(self.result.to_json());
+        Json::Array(result)
+    }
+
+    /// 天鳳の牌譜（mjlog形式）の局と同じ形にする
+    ///
+    /// 各席の列を、打牌を鳴いた席（ポン・大明槓をチーより優先する）、いなければ下家の順にたどって並べる。
+    pub fn to_mjlog_round(&self) -> Result<MjLogRound> {
+        let players = self.hands.len();
+        let mut actions: Vec<Action> = Vec::new();
+        let mut next_take = vec![0; players];
+        let mut next_discard = vec![0; players];
+        // 和了時の手牌を求めるための、副露を除いた手の中の牌
+        let mut closed: Vec<Vec<Tile>> = self.hands.clone();
+        let mut last_draw: Vec<Option<Tile>> = vec![None; players];
+        let mut last_discard: Option<Tile> = None;
+        let mut doras = self.dora_indicators.iter().skip(1);
+        let mut pending_ready: Option<usize> = None;
+        let mut seat = self.dealer;
+
+        while let Some(take) = self.takes[seat].get(next_take[seat]) {
+            next_take[seat] += 1;
+            if let Some(s) = pending_ready.take() {
+                actions.push(Action::Ready { seat: s });
+            }
+            match take {
+                Take::Tile(tile) => {
+                    closed[seat].push(*tile);
+                    last_draw[seat] = Some(*tile);
+                    actions.push(Action::Draw { seat, tile: *tile });
+                }
+                Take::Call(meld) => {
+                    remove_called(&mut closed[seat], meld)?;
+                    last_draw[seat] = None;
+                    actions.push(Action::Call { seat, meld: *meld });
+                    if let Meld::OpenKan { .. } = meld {
+                        if let Some(indicator) = doras.next() {
+                            actions.push(Action::Dora {
+                                indicator: *indicator,
+                            });
+                        }
+                        if self.discards[seat].get(next_discard[seat]) == Some(&Discard::Skip) {
+                            next_discard[seat] += 1;
+                        }
+                        continue;
+                    }
+                }
+            }
+            let discard = match self.discards[seat].get(next_discard[seat]) {
+                Some(discard) => *discard,
+                None => break,
+            };
+            next_discard[seat] += 1;
+            let (tile, ready) = match discard {
+                Discard::Tile { tile, ready } => (tile, ready),
+                Discard::Tsumogiri { ready } => (
+                    last_draw[seat]
+                        .ok_or_else(|| Error::InvalidLog("No drawn tile to discard".to_string()))?,
+                    ready,
+                ),
+                Discard::Call(meld) => {
+                    remove_called(&mut closed[seat], &meld)?;
+                    actions.push(Action::Call { seat, meld });
+                    match meld {
+                        Meld::ClosedKan { .. } | Meld::AddedKan { .. } => {
+                            if let Some(indicator) = doras.next() {
+                                actions.push(Action::Dora {
+                                    indicator: *indicator,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                    if let Meld::AddedKan { added, .. } = meld {
+                        last_discard = Some(added);
+                    }
+                    continue;
+                }
+                Discard::Skip => {
+                    return Err(Error::InvalidLog("Unexpected empty discard".to_string()))
+                }
+            };
+            remove_tile(&mut closed[seat], tile)?;
+            actions.push(Action::Discard { seat, tile, ready });
+            last_discard = Some(tile);
+            if ready {
+                pending_ready = Some(seat);
+            }
+            seat = self.next_seat(seat, tile, &next_take);
+        }
+
+        for (i, win) in self.result.wins.iter().enumerate() {
+            let (hand, winning_tile) = if win.from == win.seat {
+                let tile = last_draw[win.seat]
+                    .ok_or_else(|| Error::InvalidLog("No drawn tile to win".to_string()))?;
+                (closed[win.seat].clone(), tile)
+            } else {
+                let tile = last_discard
+                    .ok_or_else(|| Error::InvalidLog("No discarded tile to win".to_string()))?;
+                let mut hand = closed[win.seat].clone();
+                hand.push(tile);
+                (hand, tile)
+            };
+            let mut hand = hand;
+            hand.sort();
+            let (fu, points) = parse_description(&win.description, players);
+            actions.push(Action::Win(mjlog::Win {
+                seat: win.seat,
+                from: if win.from == win.seat {
+                    None
+                } else {
+                    Some(win.from)
+                },
+                hand,
+                winning_tile,
+                fu,
+                points,
+                yaku: Vec::new(),
+                yakuman: Vec::new(),
+                dora_indicators: self.dora_indicators.clone(),
+                ura_dora_indicators: self.ura_dora_indicators.clone(),
+                score_changes: self
+                    .result
+                    .score_changes
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_default(),
+            }));
+        }
+        if self.result.wins.is_empty() {
+            let score_changes = self
+                .result
+                .score_changes
+                .first()
+                .cloned()
+                .unwrap_or_else(|| vec![0; players]);
+            let kind = match self.result.name.as_str() {
+                "九種九牌" => Some("yao9"),
+                "四風連打" => Some("kaze4"),
+                "四家立直" => Some("reach4"),
+                "四槓散了" => Some("kan4"),
+                "三家和了" => Some("ron3"),
+                "流し満貫" => Some("nm"),
+                _ => None,
+            };
+            // 聴牌していたかは記録されないので点数の移動から推定する
+            let ready = match self.result.name.as_str() {
+                "全員聴牌" => vec![true; players],
+                _ => score_changes.iter().map(|&s| s > 0).collect(),
+            };
+            actions.push(Action::DrawnRound(DrawnRound {
+                kind: kind.map(String::from),
+                ready,
+                score_changes,
+            }));
+        }
+
+        Ok(MjLogRound {
+            prevailing_wind: self.prevailing_wind,
+            dealer: self.dealer,
+            honba: self.honba,
+            deposits: self.deposits,
+            scores: self.scores.clone(),
+            hands: self.hands.clone(),
+            dora_indicator: *self
+                .dora_indicators
+                .first()
+                .ok_or_else(|| Error::InvalidLog("No dora indicator".to_string()))?,
+            actions,
+        })
+    }
+
+    /// 席`discarder`の打牌の後に手番になる席を求める
+    fn next_seat(&self, discarder: usize, tile: Tile, next_take: &[usize]) -> usize {
+        let players = self.hands.len();
+        let called = |seat: usize| match self.takes[seat].get(next_take[seat]) {
+            Some(Take::Call(meld)) => Some(*meld),
+            _ => None,
+        };
+        for d in 1..players {
+            let seat = (discarder + d) % players;
+            match called(seat) {
+                Some(Meld::Pon {
+                    called: c, from, ..
+                })
+                | Some(Meld::OpenKan {
+                    called: c, from, ..
+                }) if c == tile && seat_from(seat, from, players) == discarder => return seat,
+                _ => {}
+            }
+        }
+        // チーは下家しかできないので、鳴かれなかった場合と同じ席になる
+        (discarder + 1) % players
+    }
+}
+
+impl Outcome {
+    fn parse(json: &Json, players: usize) -> Result<Outcome> {
+        let values = json.as_array()?;
+        let name = values
+            .first()
+            .ok_or_else(|| Error::InvalidLog("Empty round result".to_string()))?
+            .as_str()?
+            .to_string();
+        let deltas = |value: &Json| -> Result<Vec<i32>> {
+            let mut result: Vec<i32> = value
+                .as_array()?
+                .iter()
+                .map(|v| v.as_i64().map(|n| n as i32))
+                .collect::<Result<Vec<i32>>>()?;
+            result.truncate(players);
+            Ok(result)
+        };
+        let mut result = Outcome {
+            name,
+            score_changes: Vec::new(),
+            wins: Vec::new(),
+        };
+        if result.name == "和了" {
+            for pair in values[1..].chunks(2) {
+                result.score_changes.push(deltas(&pair[0])?);
+                let info = pair
+                    .get(1)
+                    .ok_or_else(|| Error::InvalidLog("No win information".to_string()))?
+                    .as_array()?;
+                if info.len() < 4 {
+                    return Err(Error::InvalidLog(format!(
+                        "Invalid win information: {}",
+                        pair[1]
+                    )));
+                }
+                result.wins.push(WinInfo {
+                    seat: seat(info[0].as_i64()?, players)?,
+                    from: seat(info[1].as_i64()?, players)?,
+                    liable: seat(info[2].as_i64()?, players)?,
+                    description: info[3].as_str()?.to_string(),
+                    yaku: info[4..]
+                        .iter()
+                        .map(|y| y.as_str().map(String::from))
+                        .collect::<Result<Vec<String>>>()?,
+                });
+            }
+        } else if let Some(value) = values.get(1) {
+            result.score_changes.push(deltas(value)?);
+        }
+        Ok(result)
+    }
+
+    fn to_json(&self) -> Json {
+        let deltas = |changes: &[i32]| {
+            let mut result: Vec<Json> = changes.iter().map(|&c| Json::from(c as i64)).collect();
+            result.resize(SEATS, Json::from(0));
+            Json::Array(result)
+        };
+        let mut result = vec![Json::from(self.name.as_str())];
+        for (i, changes) in self.score_changes.iter().enumerate() {
+            result.push(deltas(changes));
+            if let Some(win) = self.wins.get(i) {
+                let mut info = vec![
+                    Json::from(win.seat as i64),
+                    Json::from(win.from as i64),
+                    Json::from(win.liable as i64),
+                    Json::from(win.description.as_str()),
+                ];
+                info.extend(win.yaku.iter().map(|y| Json::from(y.as_str())));
+                result.push(Json::Array(info));
+            }
+        }
+        Json::Array(result)
+    }
+}
+
+/// 記録した出来事から1局分の牌譜を作る
+struct Recorder {
+    round: Tenhou6Round,
+    /// 立直していた和了者がいたか（裏ドラを記録する）
+    ura_dora: bool,
+}
+
+impl Recorder {
+    fn new(round: &Round) -> Recorder {
+        let players = round.players.len();
+        Recorder {
+            round: Tenhou6Round {
+                prevailing_wind: round.prevailing_wind,
+                dealer: round.dealer,
+                honba: round.honba,
+                deposits: round.deposits,
+                scores: round.players.iter().map(|p| p.score).collect(),
+                dora_indicators: Vec::new(),
+                ura_dora_indicators: Vec::new(),
+                hands: round
+                    .players
+                    .iter()
+                    .map(|p| p.hand.tiles().to_vec())
+                    .collect(),
+                takes: vec![Vec::new(); players],
+                discards: vec![Vec::new(); players],
+                result: Outcome {
+                    name: String::new(),
+                    score_changes: Vec::new(),
+                    wins: Vec::new(),
+                },
+            },
+            ura_dora: false,
+        }
+    }
+
+    /// 出来事を反映する前の局の状態から、出来事を牌譜に書き加える
+    fn record(&mut self, e: &Event, round: &Round, settings: &Settings) -> Result<()> {
+        let players = round.players.len();
+        let find = |seat: usize, tile_type: TileType, count: usize| -> Vec<Tile> {
+            let hand = &round.players[seat].hand;
+            hand.tiles()
+                .iter()
+                .chain(hand.drawn().iter())
+                .filter(|t| t.get() == tile_type)
+                .take(count)
+                .copied()
+                .collect()
+        };
+        match e {
+            Event::Draw { seat, tile } | Event::DeadWallDraw { seat, tile } => {
+                self.round.takes[*seat].push(Take::Tile(*tile));
+            }
+            Event::Discard { seat, tile, ready } => {
+                let ready = *ready;
+                self.round.discards[*seat].push(
+                    if round.players[*seat].hand.drawn() == Some(*tile) {
+                        Discard::Tsumogiri { ready }
+                    } else {
+                        Discard::Tile { tile: *tile, ready }
+                    },
+                );
+            }
+            Event::Call {
+                seat,
+                from,
+                category,
+                tiles,
+                called,
+            } => {
+                let hand = round.players[*seat].hand.tiles();
+                let mut own: Vec<Tile> = Vec::new();
+                let mut used = vec![false; hand.len()];
+                for &t in tiles {
+                    let pos = (0..hand.len())
+                        .find(|&i| !used[i] && hand[i].get() == t)
+                        .ok_or_else(|| Error::InvalidLog("No tile to call in hand".to_string()))?;
+                    used[pos] = true;
+                    own.push(hand[pos]);
+                }
+                let open_from = event::open_from(*seat, *from, players);
+                let meld = match category {
+                    OpenType::Chi => {
+                        let mut chi = [*called, own[0], own[1]];
+                        chi.sort();
+                        Meld::Chi {
+                            tiles: chi,
+                            called: *called,
+                        }
+                    }
+                    OpenType::Pon => Meld::Pon {
+                        tiles: [*called, own[0], own[1]],
+                        called: *called,
+                        from: open_from,
+                    },
+                    OpenType::Kan => Meld::OpenKan {
+                        tiles: [*called, own[0], own[1], own[2]],
+                        called: *called,
+                        from: open_from,
+                    },
+                };
+                self.round.takes[*seat].push(Take::Call(meld));
+                if *category == OpenType::Kan {
+                    self.round.discards[*seat].push(Discard::Skip);
+                }
+            }
+            Event::ClosedKan { seat, tile } => {
+                let tiles = find(*seat, *tile, 4);
+                if tiles.len() < 4 {
+                    return Err(Error::InvalidLog(
+                        "Not enough tiles for a closed kan".to_string(),
+                    ));
+                }
+                self.round.discards[*seat].push(Discard::Call(Meld::ClosedKan {
+                    tiles: [tiles[0], tiles[1], tiles[2], tiles[3]],
+                }));
+            }
+            Event::AddedKan { seat, tile } => {
+                let hand = &round.players[*seat].hand;
+                let pon = hand
+                    .opened()
+                    .iter()
+                    .find(|o| o.category == OpenType::Pon && o.tiles[0].get() == *tile)
+                    .ok_or_else(|| Error::InvalidLog("No pon to add a kan".to_string()))?;
+                // `Hand::added_kan`と同じく、ツモった牌を優先して加える
+                let added = match hand.drawn() {
+                    Some(t) if t.get() == *tile => t,
+                    _ => *find(*seat, *tile, 1)
+                        .first()
+                        .ok_or_else(|| Error::InvalidLog("No tile to add a kan".to_string()))?,
+                };
+                self.round.discards[*seat].push(Discard::Call(Meld::AddedKan {
+                    tiles: [pon.tiles[0], pon.tiles[1], pon.tiles[2], added],
+                    added,
+                    from: pon.from,
+                }));
+            }
+            Event::North { seat, tile } => {
+                let north = *find(*seat, Tile::Z4, 1)
+                    .first()
+                    .ok_or_else(|| Error::InvalidLog("No north tile to extract".to_string()))?;
+                self.round.discards[*seat].push(Discard::Call(Meld::North { tile: north }));
+                self.round.takes[*seat].push(Take::Tile(*tile));
+            }
+            Event::Win {
+                seat,
+                from,
+                han,
+                fu,
+                yakuman,
+            } => {
+                if round.players[*seat].status.has_claimed_ready {
+                    self.ura_dora = true;
+                }
+                let base = if *yakuman > 0 {
+                    yakuman_base_points(*yakuman)
+                } else {
+                    base_points(*han, *fu)
+                };
+                let is_dealer = *seat == round.dealer;
+                let first = self.round.result.wins.is_empty();
+                let mut changes = vec![0; players];
+                let points = match from {
+                    Some(discarder) => {
+                        let payment = ron_payment(base, is_dealer);
+                        let mut total = payment;
+                        if first {
+                            total += round.honba as i32 * 300;
+                        }
+                        changes[*discarder] -= total;
+                        changes[*seat] += total;
+                        format!("{}点", payment)
+                    }
+                    None => {
+                        let payments = tsumo_payments(
+                            base,
+                            *seat,
+                            round.dealer,
+                            players,
+                            settings.three_player_tsumo,
+                        );
+                        for (payer, payment) in payments.iter().enumerate() {
+                            if payer != *seat {
+                                let payment = payment + round.honba as i32 * 100;
+                                changes[payer] -= payment;
+                                changes[*seat] += payment;
+                            }
+                        }
+                        let dealer_payment = payments[round.dealer];
+                        let child_payment = (0..players)
+                            .filter(|&p| p != *seat && p != round.dealer)
+                            .map(|p| payments[p])
+                            .next()
+                            .unwrap_or(0);
+                        if is_dealer {
+                            format!("{}点∀", child_payment)
+                        } else {
+                            format!("{}-{}点", child_payment, dealer_payment)
+                        }
+                    }
+                };
+                if first {
+                    changes[*seat] += round.deposits as i32 * 1000;
+                }
+                let description = match limit_name(base, *yakuman, Lang::Ja) {
+                    Some(limit) => format!("{}{}", limit, points),
+                    None => format!("{}符{}飜{}", fu, han, points),
+                };
+                self.round.result.name = "和了".to_string();
+                self.round.result.score_changes.push(changes);
+                self.round.result.wins.push(WinInfo {
+                    seat: *seat,
+                    from: from.unwrap_or(*seat),
+                    liable: *seat,
+                    description,
+                    yaku: Vec::new(),
+                });
+            }
+            Event::ExhaustiveDraw { nagashi_mangan, .. } => {
+                self.round.result.name = if nagashi_mangan.is_empty() {
+                    "流局"
+                } else {
+                    "流し満貫"
+                }
+                .to_string();
+            }
+            Event::EndRound { score_changes, .. } => {
+                if self.round.result.wins.is_empty() {
+                    self.round.result.score_changes = vec![score_changes.clone()];
+                }
+            }
+            Event::Deal { .. } | Event::Ready { .. } | Event::Dora { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// 牌の番号（萬子11〜19・筒子21〜29・索子31〜39・字牌41〜47・赤五51〜53）を牌にする
+fn tile_from_code(code: i64) -> Result<Tile> {
+    let (suit, number) = (code / 10, code % 10);
+    match (suit, number) {
+        (1..=3, 1..=9) => Ok(Tile::new(((suit - 1) * 9 + number - 1) as TileType)),
+        (4, 1..=7) => Ok(Tile::new(Tile::Z1 + number as TileType - 1)),
+        (5, 1..=3) => Ok(Tile::new_red(((number - 1) * 9 + 4) as TileType)),
+        _ => Err(Error::InvalidLog(format!("Invalid tile code: {}", code))),
+    }
+}
+
+/// 牌を牌の番号にする
+fn tile_to_code(tile: Tile) -> i64 {
+    let t = tile.get() as i64;
+    if tile.is_red_dora() {
+        return 51 + t / 9;
+    }
+    if t >= Tile::Z1 as i64 {
+        return 41 + t - Tile::Z1 as i64;
+    }
+    (t / 9 + 1) * 10 + t % 9 + 1
+}
+
+/// 鳴きの表記（`c123`など）を、文字の付いた牌の位置と牌の列に分ける
+fn split_call(s: &str) -> Result<(char, usize, Vec<Tile>)> {
+    let mut marker: Option<(char, usize)> = None;
+    let mut tiles: Vec<Tile> = Vec::new();
+    let chars: Vec<char> = s.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_ascii_alphabetic() {
+            marker = Some((chars[i], tiles.len()));
+            i += 1;
+            continue;
+        }
+        let code: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        tiles
+            .push(tile_from_code(code.parse().map_err(|_| {
+                Error::InvalidLog(format!("Invalid call: {}", s))
+            })?)?);
+        i += 2;
+    }
+    let (c, pos) = marker.ok_or_else(|| Error::InvalidLog(format!("Invalid call: {}", s)))?;
+    if pos >= tiles.len() {
+        return Err(Error::InvalidLog(format!("Invalid call: {}", s)));
+    }
+    Ok((c, pos, tiles))
+}
+
+/// 文字の付いた牌の位置から鳴いた相手を求める（上家・対面・下家の順）
+fn from_position(pos: usize, positions: [usize; 3]) -> Result<OpenFrom> {
+    match positions.iter().position(|&p| p == pos) {
+        Some(0) => Ok(OpenFrom::Previous),
+        Some(1) => Ok(OpenFrom::Opposite),
+        Some(2) => Ok(OpenFrom::Following),
+        _ => Err(Error::InvalidLog(format!("Invalid call position: {}", pos))),
+    }
+}
+
+/// 鳴いた相手から文字を付ける位置を求める
+fn position_from(from: OpenFrom, positions: [usize; 3]) -> usize {
+    match from {
+        OpenFrom::Previous | OpenFrom::Myself | OpenFrom::Unknown => positions[0],
+        OpenFrom::Opposite => positions[1],
+        OpenFrom::Following => positions[2],
+    }
+}
+
+/// ポン・加槓の文字の位置
+const PON_POSITIONS: [usize; 3] = [0, 1, 2];
+/// 大明槓の文字の位置
+const KAN_POSITIONS: [usize; 3] = [0, 1, 3];
+
+/// 席の番号（人数の範囲外ならエラー）
+fn seat(value: i64, players: usize) -> Result<usize> {
+    if value < 0 || value as usize >= players {
+        return Err(Error::InvalidLog(format!("Invalid seat: {}", value)));
+    }
+    Ok(value as usize)
+}
+
+fn parse_meld(s: &str) -> Result<Meld> {
+    let (c, pos, tiles) = split_call(s)?;
+    let len = match c {
+        'c' | 'p' => 3,
+        'm' | 'a' | 'k' => 4,
+        'f' => 1,
+        _ => return Err(Error::InvalidLog(format!("Invalid call: {}", s))),
+    };
+    if tiles.len() != len {
+        return Err(Error::InvalidLog(format!("Invalid call: {}", s)));
+    }
+    let called = tiles[pos];
+    let meld = match c {
+        'c' => {
+            let mut chi = [tiles[0], tiles[1], tiles[2]];
+            chi.sort();
+            Meld::Chi { tiles: chi, called }
+        }
+        'p' => Meld::Pon {
+            tiles: [tiles[0], tiles[1], tiles[2]],
+            called,
+            from: from_position(pos, PON_POSITIONS)?,
+        },
+        'm' => Meld::OpenKan {
+            tiles: [tiles[0], tiles[1], tiles[2], tiles[3]],
+            called,
+            from: from_position(pos, KAN_POSITIONS)?,
+        },
+        'a' => Meld::ClosedKan {
+            tiles: [tiles[0], tiles[1], tiles[2], tiles[3]],
+        },
+        'k' => {
+            let mut pon: Vec<Tile> = tiles.clone();
+            pon.remove(pos);
+            Meld::AddedKan {
+                tiles: [pon[0], pon[1], pon[2], called],
+                added: called,
+                from: from_position(pos, PON_POSITIONS)?,
+            }
+        }
+        _ => Meld::North { tile: called },
+    };
+    Ok(meld)
+}
+
+/// 鳴きを表記にする
+fn meld_to_string(meld: &Meld) -> String {
+    let code = |t: &Tile| tile_to_code(*t).to_string();
+    // 鳴いた牌を除いた牌の列の`pos`番目に、文字と鳴いた牌を入れる
+    let insert = |tiles: &[Tile], called: Tile, c: char, pos: usize| {
+        let mut own: Vec<Tile> = tiles.to_vec();
+        if let Some(p) = own.iter().position(|t| *t == called) {
+            own.remove(p);
+        }
+        let mut result = String::new();
+        for (i, t) in own.iter().enumerate() {
+            if i == pos {
+                result.push(c);
+                result.push_str(&code(&called));
+            }
+            result.push_str(&code(t));
+        }
+        if pos >= own.len() {
+            result.push(c);
+            result.push_str(&code(&called));
+        }
+        result
+    };
+    match meld {
+        Meld::Chi { tiles, called } => insert(tiles, *called, 'c', 0),
+        Meld::Pon {
+            tiles,
+            called,
+            from,
+        } => insert(tiles, *called, 'p', position_from(*from, PON_POSITIONS)),
+        Meld::OpenKan {
+            tiles,
+            called,
+            from,
+        } => insert(tiles, *called, 'm', position_from(*from, KAN_POSITIONS)),
+        Meld::ClosedKan { tiles } => insert(tiles, tiles[3], 'a', 3),
+        Meld::AddedKan { tiles, added, from } => {
+            insert(tiles, *added, 'k', position_from(*from, PON_POSITIONS))
+        }
+        Meld::North { tile } => format!("f{}", code(tile)),
+    }
+}
+
+fn parse_take(json: &Json) -> Result<Take> {
+    match json {
+        Json::String(s) => Ok(Take::Call(parse_meld(s)?)),
+        _ => Ok(Take::Tile(tile_from_code(json.as_i64()?)?)),
+    }
+}
+
+fn take_to_json(take: &Take) -> Json {
+    match take {
+        Take::Tile(tile) => Json::from(tile_to_code(*tile)),
+        Take::Call(meld) => Json::String(meld_to_string(meld)),
+    }
+}
+
+fn parse_discard(json: &Json) -> Result<Discard> {
+    let (code, ready) = match json {
+        Json::String(s) => match s.strip_prefix('r') {
+            Some(code) => (
+                code.parse::<i64>()
+                    .map_err(|_| Error::InvalidLog(format!("Invalid discard: {}", s)))?,
+                true,
+            ),
+            None => return Ok(Discard::Call(parse_meld(s)?)),
+        },
+        _ => (json.as_i64()?, false),
+    };
+    match code {
+        TSUMOGIRI => Ok(Discard::Tsumogiri { ready }),
+        SKIP => Ok(Discard::Skip),
+        _ => Ok(Discard::Tile {
+            tile: tile_from_code(code)?,
+            ready,
+        }),
+    }
+}
+
+fn discard_to_json(discard: &Discard) -> Json {
+    let (code, ready) = match discard {
+        Discard::Tile { tile, ready } => (tile_to_code(*tile), *ready),
+        Discard::Tsumogiri { ready } => (TSUMOGIRI, *ready),
+        Discard::Call(meld) => return Json::String(meld_to_string(meld)),
+        Discard::Skip => (SKIP, false),
+    };
+    if ready {
+        Json::String(format!("r{}", code))
+    } else {
+        Json::from(code)
+    }
+}
+
+/// 鳴いた席から見た相手の席を求める
+fn seat_from(seat: usize, from: OpenFrom, players: usize) -> usize {
+    match from {
+        OpenFrom::Previous => (seat + players - 1) % players,
+        OpenFrom::Opposite => (seat + 2) % players,
+        OpenFrom::Following => (seat + 1) % players,
+        OpenFrom::Myself | OpenFrom::Unknown => seat,
+    }
+}
+
+/// 手の中の牌から1枚取り除く
+fn remove_tile(tiles: &mut Vec<Tile>, tile: Tile) -> Result<()> {
+    let pos = tiles
+        .iter()
+        .position(|t| *t == tile)
+        .ok_or_else(|| Error::InvalidLog(format!("No tile in hand: {}", tile)))?;
+    tiles.remove(pos);
+    Ok(())
+}
+
+/// 鳴き・槓で手の中から出した牌を取り除く
+fn remove_called(tiles: &mut Vec<Tile>, meld: &Meld) -> Result<()> {
+    let (all, called): (Vec<Tile>, Option<Tile>) = match meld {
+        Meld::Chi { tiles, called } | Meld::Pon { tiles, called, .. } => {
+            (tiles.to_vec(), Some(*called))
+        }
+        Meld::OpenKan { tiles, called, .. } => (tiles.to_vec(), Some(*called)),
+        Meld::ClosedKan { tiles } => (tiles.to_vec(), None),
+        Meld::AddedKan { added, .. } => (vec![*added], None),
+        Meld::North { tile } => (vec![*tile], None),
+    };
+    let mut own = all;
+    if let Some(called) = called {
+        if let Some(p) = own.iter().position(|t| *t == called) {
+            own.remove(p);
+        }
+    }
+    for t in own {
+        remove_tile(tiles, t)?;
+    }
+    Ok(())
+}
+
+/// 符・翻・点数の表記から符と和了点を求める（符がなければ`0`）
+fn parse_description(description: &str, players: usize) -> (u32, i32) {
+    let number_before = |s: &str, suffix: char| -> Option<i32> {
+        let end = s.find(suffix)?;
+        let digits: String = s[..end]
+            .chars()
+            .rev()
+            .take_while(|c| c.is_ascii_digit() || *c == '-')
+            .collect::<Vec<char>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let parts: Vec<i32> = digits.split('-').filter_map(|p| p.parse().ok()).collect();
+        match parts.as_slice() {
+            [a, b] => Some(a * (players as i32 - 2) + b),
+            [a] if s.contains('∀') => Some(a * (players as i32 - 1)),
+            [a] => Some(*a),
+            _ => None,
+        }
+    };
+    let fu = number_before(description, '符').unwrap_or(0) as u32;
+    let points = number_before(description, '点').unwrap_or(0);
+    (fu, points)
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::agent::Agent;
+    use crate::board::bot::RandomAgent;
+    use crate::board::engine::play_game;
+    use crate::board::game::Game;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const SAMPLE: &str = include_str!("../../testdata/sample.json");
+    const SAMPLE_MJLOG: &str = include_str!("../../testdata/sample.mjlog");
+
+    #[test]
+    /// 牌譜を読み込み、書き出したものを読み直しても同じになる
+    fn parse_sample() {
+        let log = Tenhou6::parse(SAMPLE).unwrap();
+        assert_eq!(log.players(), 4);
+        assert_eq!(log.names, vec!["あ", "B", "C", "D"]);
+        assert!(log.red);
+        assert_eq!(log.rounds.len(), 2);
+        let first = &log.rounds[0];
+        assert!(first.hands[0].contains(&Tile::new_red(Tile::P5)));
+        assert_eq!(
+            first.takes[1][0],
+            Take::Call(Meld::Pon {
+                tiles: [Tile::new(Tile::Z5); 3],
+                called: Tile::new(Tile::Z5),
+                from: OpenFrom::Previous,
+            })
+        );
+        assert_eq!(
+            first.discards[0][1],
+            Discard::Tile {
+                tile: Tile::new(Tile::Z7),
+                ready: true
+            }
+        );
+        assert_eq!(first.discards[1][1], Discard::Tsumogiri { ready: false });
+        assert_eq!(first.result.wins[0].description, "満貫12000点");
+        assert_eq!(log.rounds[1].result.name, "九種九牌");
+
+        assert_eq!(Tenhou6::parse(&log.to_json()).unwrap(), log);
+    }
+
+    #[test]
+    /// 人数の範囲外の席・負の値はエラーになる
+    fn invalid_seats() {
+        let invalid = |json: &str| matches!(Tenhou6::parse(json), Err(Error::InvalidLog(_)));
+        assert!(invalid(&SAMPLE.replacen("[0, 1, 0, \"満貫", "[9, 1, 0, \"満貫", 1)));
+        assert!(invalid(&SAMPLE.replacen("[0, 1, 0, \"満貫", "[0, -1, 0, \"満貫", 1)));
+        assert!(invalid(&SAMPLE.replacen("[0, 0, 0]", "[-4, 0, 0]", 1)));
+        // 三人麻雀で親が4席目
+        let three = SAMPLE.replacen("[17, 18, 18, 18, 18, 19, 19, 19, 21, 21, 21, 22, 22]", "[]", 1);
+        assert!(Tenhou6::parse(&three).is_ok());
+        assert!(invalid(&three.replacen("[0, 1, 0]", "[3, 1, 0]", 1)));
+    }
+
+    #[test]
+    /// 鳴きの表記
+    fn call_strings() {
+        for s in [
+            "c191718",
+            "p454545",
+            "45p4545",
+            "4545p45",
+            "m39393939",
+            "39m393939",
+            "393939m39",
+            "313131a31",
+            "k45454545",
+            "4545k4545",
+            "f44",
+            "c522426",
+        ] {
+            assert_eq!(meld_to_string(&parse_meld(s).unwrap()), s);
+        }
+        assert_eq!(
+            parse_meld("4545p45").unwrap(),
+            Meld::Pon {
+                tiles: [Tile::new(Tile::Z5); 3],
+                called: Tile::new(Tile::Z5),
+                from: OpenFrom::Following,
+            }
+        );
+        assert!(parse_meld("x454545").is_err());
+        assert!(parse_meld("p4545").is_err());
+    }
+
+    #[test]
+    /// mjlog形式の同じ牌譜と同じ手順・手牌が再現される
+    fn same_as_mjlog() {
+        let log = Tenhou6::parse(SAMPLE).unwrap().to_mjlog().unwrap();
+        let mjlog = MjLog::parse(SAMPLE_MJLOG).unwrap();
+        let points = log.rounds[0].decision_points().unwrap();
+        let expected = mjlog.rounds[0].decision_points().unwrap();
+        assert_eq!(points.len(), expected.len());
+        for (p, e) in points.iter().zip(&expected) {
+            assert_eq!(p.seat, e.seat);
+            assert_eq!(p.hand.to_string(), e.hand.to_string());
+        }
+        let win = points.last().unwrap();
+        assert!(win.status.is_one_shot);
+        match &win.action {
+            Action::Win(w) => {
+                assert_eq!(w.winning_tile, Tile::new(Tile::P4));
+                assert_eq!((w.fu, w.points), (0, 12000));
+            }
+            action => panic!("Unexpected action: {:?}", action),
+        }
+    }
+
+    #[test]
+    /// 対局の記録を書き出して読み直すと、同じ局と点数の移動が再現される
+    fn export_game() {
+        for mut settings in [Settings::new(), Settings::new_three_players()] {
+            settings.game_length = GameLength::EastOnly;
+            let players = settings.game_mode.players();
+            let mut game = Game::new(&settings);
+            let mut agents: Vec<Box<dyn Agent>> = (0..players)
+                .map(|i| Box::new(RandomAgent::new(i as u64 + 20)) as Box<dyn Agent>)
+                .collect();
+            let mut rng = StdRng::seed_from_u64(4);
+            let record = play_game(&mut game, &mut agents, &settings, &mut rng).unwrap();
+            let names: Vec<String> = (0..players).map(|i| format!("bot{}", i)).collect();
+
+            let log = Tenhou6::from_events(&record.events, &settings, &names).unwrap();
+            let deals = record
+                .events
+                .iter()
+                .filter(|e| matches!(e, Event::Deal { .. }))
+                .count();
+            assert_eq!(log.rounds.len(), deals);
+            let parsed = Tenhou6::parse(&log.to_json()).unwrap();
+            assert_eq!(parsed, log);
+
+            // 各局の点数の移動の合計が記録と一致する
+            let end_rounds: Vec<&Vec<i32>> = record
+                .events
+                .iter()
+                .filter_map(|e| match e {
+                    Event::EndRound { score_changes, .. } => Some(score_changes),
+                    _ => None,
+                })
+                .collect();
+            for (round, expected) in log.rounds.iter().zip(end_rounds) {
+                let mut total = vec![0; players];
+                for changes in &round.result.score_changes {
+                    for (t, c) in total.iter_mut().zip(changes) {
+                        *t += c;
+                    }
+                }
+                assert_eq!(&total, expected);
+            }
+
+            // 手順をたどり直せる
+            for round in parsed.to_mjlog().unwrap().rounds {
+                round.decision_points().unwrap();
+            }
+        }
+    }
+}
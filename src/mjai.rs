@@ -0,0 +1,4 @@
+/// mjaiのメッセージ
+pub mod message;
+/// mjaiの打ち手と対局するサーバー
+pub mod server;
@@ -0,0 +1,741 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::event::open_from;
+use crate::error::{Error, Result};
+use crate::hand_info::opened::{OpenFrom, OpenTiles, OpenType};
+use crate::log::json::Json;
+use crate::tile::*;
+
+/// mjaiのメッセージ（1行のJSON）
+///
+/// 伏せられた牌（他家の自摸・配牌）は`None`で表す。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// 接続の開始（サーバーから）
+    Hello,
+    /// 参加（打ち手から）
+    Join { name: String, room: String },
+    /// 対局の開始（`id`は受け取った打ち手の席）
+    StartGame { id: usize, names: Vec<String> },
+    /// 局の開始（`kyoku`は1始まり）
+    StartKyoku {
+        bakaze: Wind,
+        kyoku: usize,
+        honba: u32,
+        kyotaku: u32,
+        oya: usize,
+        dora_marker: Tile,
+        scores: Vec<i32>,
+        tehais: Vec<Vec<Option<Tile>>>,
+    },
+    /// 自摸
+    Tsumo { actor: usize, pai: Option<Tile> },
+    /// 打牌
+    Dahai {
+        actor: usize,
+        pai: Tile,
+        tsumogiri: bool,
+    },
+    /// チー（`consumed`は手牌から出した牌）
+    Chi {
+        actor: usize,
+        target: usize,
+        pai: Tile,
+        consumed: Vec<Tile>,
+    },
+    /// ポン
+    Pon {
+        actor: usize,
+        target: usize,
+        pai: Tile,
+        consumed: Vec<Tile>,
+    },
+    /// 大明槓
+    Daiminkan {
+        actor: usize,
+        target: usize,
+        pai: Tile,
+        consumed: Vec<Tile>,
+    },
+    /// 暗槓
+    Ankan { actor: usize, consumed: Vec<Tile> },
+    /// 加槓（`consumed`はポンしていた牌）
+    Kakan {
+        actor: usize,
+        pai: Tile,
+        consumed: Vec<Tile>,
+    },
+    /// 北抜き
+    Nukidora { actor: usize, pai: Tile },
+    /// 新しいドラ表示牌
+    Dora { dora_marker: Tile },
+    /// 立直の宣言
+    Reach { actor: usize },
+    /// 立直の成立
+    ReachAccepted { actor: usize },
+    /// 和了（`deltas`・`scores`は空なら省略する）
+    Hora {
+        actor: usize,
+        target: usize,
+        pai: Tile,
+        fu: u32,
+        fan: u32,
+        deltas: Vec<i32>,
+        scores: Vec<i32>,
+    },
+    /// 流局
+    Ryukyoku {
+        reason: String,
+        tenpais: Vec<bool>,
+        deltas: Vec<i32>,
+        scores: Vec<i32>,
+    },
+    /// 局の終了
+    EndKyoku,
+    /// 対局の終了
+    EndGame,
+    /// 何もしない
+    None,
+    /// エラー
+    Error { message: String },
+}
+
+/// 牌をmjaiの表記（`5m`・`5mr`・`E`など）にする
+pub fn tile_to_mjai(tile: Tile) -> String {
+    let t = tile.get();
+    if t >= Tile::Z1 {
+        return ["E", "S", "W", "N", "P", "F", "C"][(t - Tile::Z1) as usize].to_string();
+    }
+    let suit = ['m', 'p', 's'][(t / 9) as usize];
+    let red = if tile.is_red_dora() { "r" } else { "" };
+    format!("{}{}{}", t % 9 + 1, suit, red)
+}
+
+/// mjaiの表記を牌にする
+pub fn tile_from_mjai(s: &str) -> Result<Tile> {
+    if let Some(pos) = ["E", "S", "W", "N", "P", "F", "C"]
+        .iter()
+        .position(|h| *h == s)
+    {
+        return Ok(Tile::new(Tile::Z1 + pos as TileType));
+    }
+    let invalid = || Error::InvalidMjaiMessage(format!("Invalid mjai tile: {}", s));
+    let (body, red) = match s.strip_suffix('r') {
+        Some(body) => (body, true),
+        None => (s, false),
+    };
+    let mut chars = body.chars();
+    let number = chars
+        .next()
+        .and_then(|c| c.to_digit(10))
+        .ok_or_else(invalid)?;
+    let suit = match chars.next() {
+        Some('m') => 0,
+        Some('p') => 1,
+        Some('s') => 2,
+        _ => return Err(invalid()),
+    };
+    if chars.next().is_some() || !(1..=9).contains(&number) || (red && number != 5) {
+        return Err(invalid());
+    }
+    let tile_type = suit * 9 + number - 1;
+    Ok(if red {
+        Tile::new_red(tile_type)
+    } else {
+        Tile::new(tile_type)
+    })
+}
+
+impl Message {
+    /// 鳴き・槓のメッセージを副露にする（`players`は人数）
+    ///
+    /// 加槓は鳴いた相手が分からないので`OpenFrom::Unknown`になる。
+    /// 席が人数の範囲外のときや、自分から鳴いているときは`None`を返す。
+    pub fn to_open_tiles(&self, players: usize) -> Option<OpenTiles> {
+        let (tiles, category, from, called, added) = match self {
+            Message::Chi {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Message::Pon {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Message::Daiminkan {
+                actor,
+                target,
+                pai,
+                consumed,
+            } => {
+                if *actor >= players || *target >= players || actor == target {
+                    return None;
+                }
+                let category = match self {
+                    Message::Chi { .. } => OpenType::Chi,
+                    Message::Pon { .. } => OpenType::Pon,
+                    _ => OpenType::Kan,
+                };
+                let mut tiles = consumed.clone();
+                tiles.push(*pai);
+                let from = open_from(*actor, *target, players);
+                (tiles, category, from, Some(*pai), false)
+            }
+            Message::Ankan { actor, consumed } => {
+                if *actor >= players {
+                    return None;
+                }
+                (consumed.clone(), OpenType::Kan, OpenFrom::Myself, None, false)
+            }
+            Message::Kakan {
+                actor,
+                pai,
+                consumed,
+            } => {
+                if *actor >= players {
+                    return None;
+                }
+                let mut tiles = consumed.clone();
+                tiles.push(*pai);
+                (tiles, OpenType::Kan, OpenFrom::Unknown, None, true)
+            }
+            _ => return None,
+        };
+        if tiles.len() < 3 {
+            return None;
+        }
+        Some(OpenTiles {
+            tiles: OpenTiles::pick_tiles(&tiles),
+            category,
+            from,
+            called,
+            added,
+        })
+    }
+
+    /// 副露と鳴いた牌からポン・チー・大明槓のメッセージを作る（`actor`は鳴いた席）
+    ///
+    /// 大明槓の4枚目は`called`と同じ種類の通常の牌とする。
+    /// `actor`が人数の範囲外のときは`None`を返す。
+    pub fn from_open_tiles(
+        actor: usize,
+        players: usize,
+        open: &OpenTiles,
+        called: Tile,
+    ) -> Option<Message> {
+        if actor >= players {
+            return None;
+        }
+        let target = match open.from {
+            OpenFrom::Previous => (actor + players - 1) % players,
+            OpenFrom::Following => (actor + 1) % players,
+            OpenFrom::Opposite => (actor + 2) % players,
+            OpenFrom::Myself | OpenFrom::Unknown => return None,
+        };
+        let mut consumed = open.tiles.to_vec();
+        let pos = consumed.iter().position(|t| *t == called)?;
+        consumed.remove(pos);
+        Some(match open.category {
+            OpenType::Chi => Message::Chi {
+                actor,
+                target,
+                pai: called,
+                consumed,
+            },
+            OpenType::Pon => Message::Pon {
+                actor,
+                target,
+                pai: called,
+                consumed,
+            },
+            OpenType::Kan => {
+                consumed.push(Tile::new(called.get()));
+                Message::Daiminkan {
+                    actor,
+                    target,
+                    pai: called,
+                    consumed,
+                }
+            }
+        })
+    }
+
+    /// 1行のJSONを読み込む
+    pub fn parse(line: &str) -> Result<Message> {
+        let json = Json::parse(line)?;
+        let field = |name: &str| {
+            json.get(name).ok_or_else(|| {
+                Error::InvalidMjaiMessage(format!("No {} in mjai message: {}", name, line))
+            })
+        };
+        let number = |name: &str| -> Result<i64> { field(name)?.as_i64() };
+        let seat = |name: &str| -> Result<usize> {
+            let n = number(name)?;
+            if n < 0 {
+                return Err(Error::InvalidMjaiMessage(format!("Invalid {}: {}", name, n)));
+            }
+            Ok(n as usize)
+        };
+        let tile = |name: &str| -> Result<Tile> { tile_from_mjai(field(name)?.as_str()?) };
+        let tiles = |name: &str| -> Result<Vec<Tile>> {
+            field(name)?
+                .as_array()?
+                .iter()
+                .map(|t| tile_from_mjai(t.as_str()?))
+                .collect()
+        };
+        let integers = |name: &str| -> Result<Vec<i32>> {
+            match json.get(name) {
+                Some(value) => value
+                    .as_array()?
+                    .iter()
+                    .map(|v| v.as_i64().map(|n| n as i32))
+                    .collect(),
+                None => Ok(Vec::new()),
+            }
+        };
+        let string = |name: &str| -> Result<String> {
+            match json.get(name) {
+                Some(value) => Ok(value.as_str()?.to_string()),
+                None => Ok(String::new()),
+            }
+        };
+        let hidden = |value: &Json| -> Result<Option<Tile>> {
+            match value.as_str()? {
+                "?" => Ok(None),
+                s => tile_from_mjai(s).map(Some),
+            }
+        };
+
+        let message = match field("type")?.as_str()? {
+            "hello" => Message::Hello,
+            "join" => Message::Join {
+                name: string("name")?,
+                room: string("room")?,
+            },
+            "start_game" => Message::StartGame {
+                id: seat("id")?,
+                names: match json.get("names") {
+                    Some(names) => names
+                        .as_array()?
+                        .iter()
+                        .map(|n| n.as_str().map(String::from))
+                        .collect::<Result<Vec<String>>>()?,
+                    None => Vec::new(),
+                },
+            },
+            "start_kyoku" => Message::StartKyoku {
+                bakaze: match field("bakaze")?.as_str()? {
+                    "E" => Wind::East,
+                    "S" => Wind::South,
+                    "W" => Wind::West,
+                    "N" => Wind::North,
+                    s => return Err(Error::InvalidMjaiMessage(format!("Invalid bakaze: {}", s))),
+                },
+                kyoku: seat("kyoku")?,
+                honba: number("honba")? as u32,
+                kyotaku: number("kyotaku")? as u32,
+                oya: seat("oya")?,
+                dora_marker: tile("dora_marker")?,
+                scores: integers("scores")?,
+                tehais: field("tehais")?
+                    .as_array()?
+                    .iter()
+                    .map(|hand| hand.as_array()?.iter().map(hidden).collect())
+                    .collect::<Result<Vec<Vec<Option<Tile>>>>>()?,
+            },
+            "tsumo" => Message::Tsumo {
+                actor: seat("actor")?,
+                pai: hidden(field("pai")?)?,
+            },
+            "dahai" => Message::Dahai {
+                actor: seat("actor")?,
+                pai: tile("pai")?,
+                tsumogiri: json.get("tsumogiri") == Some(&Json::Bool(true)),
+            },
+            "chi" => Message::Chi {
+                actor: seat("actor")?,
+                target: seat("target")?,
+                pai: tile("pai")?,
+                consumed: tiles("consumed")?,
+            },
+            "pon" => Message::Pon {
+                actor: seat("actor")?,
+                target: seat("target")?,
+                pai: tile("pai")?,
+                consumed: tiles("consumed")?,
+            },
+            "daiminkan" => Message::Daiminkan {
+                actor: seat("actor")?,
+                target: seat("target")?,
+                pai: tile("pai")?,
+                consumed: tiles("consumed")?,
+            },
+            "ankan" => Message::Ankan {
+                actor: seat("actor")?,
+                consumed: tiles("consumed")?,
+            },
+            "kakan" => Message::Kakan {
+                actor: seat("actor")?,
+                pai: tile("pai")?,
+                consumed: tiles("consumed")?,
+            },
+            "nukidora" => Message::Nukidora {
+                actor: seat("actor")?,
+                pai: tile("pai")?,
+            },
+            "dora" => Message::Dora {
+                dora_marker: tile("dora_marker")?,
+            },
+            "reach" => Message::Reach {
+                actor: seat("actor")?,
+            },
+            "reach_accepted" => Message::ReachAccepted {
+                actor: seat("actor")?,
+            },
+            "hora" => Message::Hora {
+                actor: seat("actor")?,
+                target: seat("target")?,
+                pai: tile("pai")?,
+                fu: json.get("fu").map_or(Ok(0), |v| v.as_i64())? as u32,
+                fan: json.get("fan").map_or(Ok(0), |v| v.as_i64())? as u32,
+                deltas: integers("deltas")?,
+                scores: integers("scores")?,
+            },
+            "ryukyoku" => Message::Ryukyoku {
+                reason: string("reason")?,
+                tenpais: match json.get("tenpais") {
+                    Some(tenpais) => tenpais
+                        .as_array()?
+                        .iter()
+                        .map(|t| Ok(*t == Json::Bool(true)))
+                        .collect::<Result<Vec<bool>>>()?,
+                    None => Vec::new(),
+                },
+                deltas: integers("deltas")?,
+                scores: integers("scores")?,
+            },
+            "end_kyoku" => Message::EndKyoku,
+            "end_game" => Message::EndGame,
+            "none" => Message::None,
+            "error" => Message::Error {
+                message: string("message")?,
+            },
+            t => return Err(Error::InvalidMjaiMessage(format!("Unknown mjai message type: {}", t))),
+        };
+        Ok(message)
+    }
+
+    fn to_json(&self) -> Json {
+        let tile = |t: &Tile| Json::String(tile_to_mjai(*t));
+        let tiles = |ts: &[Tile]| Json::Array(ts.iter().map(tile).collect());
+        let hidden = |t: &Option<Tile>| match t {
+            Some(t) => tile(t),
+            None => Json::from("?"),
+        };
+        let seat = |s: &usize| Json::from(*s as i64);
+        let integers = |ns: &[i32]| Json::Array(ns.iter().map(|&n| Json::from(n as i64)).collect());
+        let (name, mut fields): (&str, Vec<(&str, Json)>) = match self {
+            Message::Hello => (
+                "hello",
+                vec![
+                    ("protocol", Json::from("mjsonp")),
+                    ("protocol_version", Json::from(3)),
+                ],
+            ),
+            Message::Join { name, room } => (
+                "join",
+                vec![
+                    ("name", Json::from(name.as_str())),
+                    ("room", Json::from(room.as_str())),
+                ],
+            ),
+            Message::StartGame { id, names } => (
+                "start_game",
+                vec![
+                    ("id", seat(id)),
+                    (
+                        "names",
+                        Json::Array(names.iter().map(|n| Json::from(n.as_str())).collect()),
+                    ),
+                ],
+            ),
+            Message::StartKyoku {
+                bakaze,
+                kyoku,
+                honba,
+                kyotaku,
+                oya,
+                dora_marker,
+                scores,
+                tehais,
+            } => (
+                "start_kyoku",
+                vec![
+                    (
+                        "bakaze",
+                        Json::from(match bakaze {
+                            Wind::East => "E",
+                            Wind::South => "S",
+                            Wind::West => "W",
+                            Wind::North => "N",
+                        }),
+                    ),
+                    ("kyoku", seat(kyoku)),
+                    ("honba", Json::from(*honba as i64)),
+                    ("kyotaku", Json::from(*kyotaku as i64)),
+                    ("oya", seat(oya)),
+                    ("dora_marker", tile(dora_marker)),
+                    ("scores", integers(scores)),
+                    (
+                        "tehais",
+                        Json::Array(
+                            tehais
+                                .iter()
+                                .map(|hand| Json::Array(hand.iter().map(hidden).collect()))
+                                .collect(),
+                        ),
+                    ),
+                ],
+            ),
+            Message::Tsumo { actor, pai } => {
+                ("tsumo", vec![("actor", seat(actor)), ("pai", hidden(pai))])
+            }
+            Message::Dahai {
+                actor,
+                pai,
+                tsumogiri,
+            } => (
+                "dahai",
+                vec![
+                    ("actor", seat(actor)),
+                    ("pai", tile(pai)),
+                    ("tsumogiri", Json::Bool(*tsumogiri)),
+                ],
+            ),
+            Message::Chi {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Message::Pon {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | Message::Daiminkan {
+                actor,
+                target,
+                pai,
+                consumed,
+            } => (
+                match self {
+                    Message::Chi { .. } => "chi",
+                    Message::Pon { .. } => "pon",
+                    _ => "daiminkan",
+                },
+                vec![
+                    ("actor", seat(actor)),
+                    ("target", seat(target)),
+                    ("pai", tile(pai)),
+                    ("consumed", tiles(consumed)),
+                ],
+            ),
+            Message::Ankan { actor, consumed } => (
+                "ankan",
+                vec![("actor", seat(actor)), ("consumed", tiles(consumed))],
+            ),
+            Message::Kakan {
+                actor,
+                pai,
+                consumed,
+            } => (
+                "kakan",
+                vec![
+                    ("actor", seat(actor)),
+                    ("pai", tile(pai)),
+                    ("consumed", tiles(consumed)),
+                ],
+            ),
+            Message::Nukidora { actor, pai } => {
+                ("nukidora", vec![("actor", seat(actor)), ("pai", tile(pai))])
+            }
+            Message::Dora { dora_marker } => ("dora", vec![("dora_marker", tile(dora_marker))]),
+            Message::Reach { actor } => ("reach", vec![("actor", seat(actor))]),
+            Message::ReachAccepted { actor } => ("reach_accepted", vec![("actor", seat(actor))]),
+            Message::Hora {
+                actor,
+                target,
+                pai,
+                fu,
+                fan,
+                deltas,
+                scores,
+            } => (
+                "hora",
+                vec![
+                    ("actor", seat(actor)),
+                    ("target", seat(target)),
+                    ("pai", tile(pai)),
+                    ("fu", Json::from(*fu as i64)),
+                    ("fan", Json::from(*fan as i64)),
+                    ("deltas", integers(deltas)),
+                    ("scores", integers(scores)),
+                ],
+            ),
+            Message::Ryukyoku {
+                reason,
+                tenpais,
+                deltas,
+                scores,
+            } => (
+                "ryukyoku",
+                vec![
+                    ("reason", Json::from(reason.as_str())),
+                    (
+                        "tenpais",
+                        Json::Array(tenpais.iter().map(|&t| Json::Bool(t)).collect()),
+                    ),
+                    ("deltas", integers(deltas)),
+                    ("scores", integers(scores)),
+                ],
+            ),
+            Message::EndKyoku => ("end_kyoku", Vec::new()),
+            Message::EndGame => ("end_game", Vec::new()),
+            Message::None => ("none", Vec::new()),
+            Message::Error { message } => {
+                ("error", vec![("message", Json::from(message.as_str()))])
+            }
+        };
+        // 空の点数は省略する
+        fields
+            .retain(|(k, v)| !(matches!(*k, "deltas" | "scores") && *v == Json::Array(Vec::new())));
+        let mut entries = vec![("type".to_string(), Json::from(name))];
+        entries.extend(fields.into_iter().map(|(k, v)| (k.to_string(), v)));
+        Json::Object(entries)
+    }
+}
+
+/// 1行のJSONにする
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+impl FromStr for Message {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Message> {
+        Message::parse(s)
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// 牌の表記
+    fn tile_notation() {
+        assert_eq!(tile_to_mjai(Tile::new(Tile::M1)), "1m");
+        assert_eq!(tile_to_mjai(Tile::new_red(Tile::P5)), "5pr");
+        assert_eq!(tile_to_mjai(Tile::new(Tile::Z5)), "P");
+        for t in Tile::M1..=Tile::Z7 {
+            assert_eq!(
+                tile_from_mjai(&tile_to_mjai(Tile::new(t))).unwrap(),
+                Tile::new(t)
+            );
+        }
+        assert_eq!(tile_from_mjai("5sr").unwrap(), Tile::new_red(Tile::S5));
+        assert!(tile_from_mjai("4sr").is_err());
+        assert!(tile_from_mjai("0m").is_err());
+        assert!(tile_from_mjai("X").is_err());
+    }
+
+    #[test]
+    /// JSONとの相互変換
+    fn json() {
+        let messages = [
+            r#"{"type":"hello","protocol":"mjsonp","protocol_version":3}"#,
+            r#"{"type":"start_game","id":1,"names":["a","b","c","d"]}"#,
+            r#"{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":0,"kyotaku":0,"oya":0,"dora_marker":"5sr","scores":[25000,25000,25000,25000],"tehais":[["?","?"],["1m","E"]]}"#,
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"C","tsumogiri":true}"#,
+            r#"{"type":"chi","actor":1,"target":0,"pai":"3m","consumed":["4m","5mr"]}"#,
+            r#"{"type":"ankan","actor":2,"consumed":["N","N","N","N"]}"#,
+            r#"{"type":"hora","actor":1,"target":0,"pai":"3m","fu":30,"fan":2,"deltas":[-2000,2000,0,0]}"#,
+            r#"{"type":"ryukyoku","reason":"fanpai","tenpais":[true,false,false,true]}"#,
+            r#"{"type":"none"}"#,
+        ];
+        for m in messages {
+            assert_eq!(Message::parse(m).unwrap().to_string(), m);
+        }
+        assert_eq!(
+            r#"{"type":"dahai","actor":3,"pai":"9p"}"#.parse::<Message>().unwrap(),
+            Message::Dahai {
+                actor: 3,
+                pai: Tile::new(Tile::P9),
+                tsumogiri: false,
+            }
+        );
+        assert!(Message::parse(r#"{"type":"dahai","actor":3}"#).is_err());
+        assert!(Message::parse(r#"{"type":"unknown"}"#).is_err());
+    }
+
+    #[test]
+    /// 副露との相互変換
+    fn open_tiles() {
+        let pon =
+            Message::parse(r#"{"type":"pon","actor":0,"target":2,"pai":"P","consumed":["P","P"]}"#)
+                .unwrap();
+        let open = pon.to_open_tiles(4).unwrap();
+        assert_eq!(open.category, OpenType::Pon);
+        assert_eq!(open.from, OpenFrom::Opposite);
+        assert_eq!(
+            Message::from_open_tiles(0, 4, &open, Tile::new(Tile::Z5)).unwrap(),
+            pon
+        );
+
+        let chi = Message::Chi {
+            actor: 1,
+            target: 0,
+            pai: Tile::new(Tile::S3),
+            consumed: vec![Tile::new(Tile::S4), Tile::new_red(Tile::S5)],
+        };
+        let open = chi.to_open_tiles(4).unwrap();
+        assert_eq!(open.from, OpenFrom::Previous);
+        assert_eq!(open.tiles[2], Tile::new_red(Tile::S5));
+        assert_eq!(
+            Message::from_open_tiles(1, 4, &open, Tile::new(Tile::S3)).unwrap(),
+            chi
+        );
+        assert!(Message::Reach { actor: 0 }.to_open_tiles(4).is_none());
+
+        // 人数の範囲外の席や、自分からの鳴きは副露にしない
+        let pon = |actor: usize, target: usize| Message::Pon {
+            actor,
+            target,
+            pai: Tile::new(Tile::Z5),
+            consumed: vec![Tile::new(Tile::Z5); 2],
+        };
+        assert!(pon(0, 9).to_open_tiles(4).is_none());
+        assert!(pon(9, 0).to_open_tiles(4).is_none());
+        assert!(pon(0, 3).to_open_tiles(3).is_none());
+        assert!(pon(1, 1).to_open_tiles(4).is_none());
+        let ankan = Message::Ankan {
+            actor: 4,
+            consumed: vec![Tile::new(Tile::Z5); 4],
+        };
+        assert!(ankan.to_open_tiles(4).is_none());
+        assert!(Message::from_open_tiles(4, 4, &open, Tile::new(Tile::S3)).is_none());
+        assert!(Message::parse(r#"{"type":"reach","actor":-1}"#).is_err());
+    }
+}
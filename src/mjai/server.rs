@@ -0,0 +1,698 @@
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::rc::Rc;
+
+use rand::Rng;
+
+use crate::board::agent::*;
+use crate::board::engine::{play_game, GameRecord};
+use crate::board::event::Event;
+use crate::board::game::{Game, Placement};
+use crate::error::{Error, Result};
+use crate::hand_info::opened::OpenType;
+use crate::mjai::message::Message;
+use crate::settings::Settings;
+use crate::tile::*;
+
+/// mjaiで接続した外部の打ち手
+///
+/// 局の中の出来事をmjaiのメッセージにして送り、打ち手が1つ返すメッセージを行動にする。
+/// 他家の配牌は伏せて送る（他家の自摸は局の進行の側で伏せられている）。
+/// 通信に失敗するか、選べない行動が返ってきた場合は、以降はツモ切り（鳴きは見送り）を続け、`error`で失敗を返す。
+pub struct MjaiAgent {
+    reader: Box<dyn BufRead>,
+    writer: Box<dyn Write>,
+    /// 席
+    seat: usize,
+    /// 参加時に名乗った名前
+    name: String,
+    /// まだ送っていないメッセージ
+    pending: Vec<Message>,
+    /// 打ち手自身の立直宣言を送ったか
+    reach_sent: bool,
+    /// 標準入出力で接続した打ち手のプロセス
+    child: Option<Child>,
+    /// 最初に起きた通信の失敗（対局の後に取り出せるよう共有する）
+    error: Rc<RefCell<Option<Error>>>,
+}
+
+impl MjaiAgent {
+    /// 接続を受け取って参加のやりとり（`hello`と`join`）をする
+    pub fn new(reader: Box<dyn BufRead>, writer: Box<dyn Write>, seat: usize) -> Result<MjaiAgent> {
+        let mut agent = MjaiAgent {
+            reader,
+            writer,
+            seat,
+            name: String::new(),
+            pending: Vec::new(),
+            reach_sent: false,
+            child: None,
+            error: Rc::new(RefCell::new(None)),
+        };
+        match agent.exchange(&Message::Hello)? {
+            Message::Join { name, .. } => agent.name = name,
+            message => {
+                return Err(Error::UnexpectedMessage(format!(
+                    "Expected join, got {}",
+                    message
+                )))
+            }
+        }
+        Ok(agent)
+    }
+
+    /// ローカルのTCPで接続してきた打ち手を受け入れる
+    pub fn accept(listener: &TcpListener, seat: usize) -> Result<MjaiAgent> {
+        let (stream, _) = listener.accept()?;
+        // 1行ずつやりとりするので、小さな書き込みをまとめて待たないようにする
+        stream.set_nodelay(true)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        MjaiAgent::new(Box::new(reader), Box::new(BufWriter::new(stream)), seat)
+    }
+
+    /// 打ち手のプロセスを起動し、標準入出力で接続する
+    pub fn spawn(command: &mut Command, seat: usize) -> Result<MjaiAgent> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| no_pipe("No stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| no_pipe("No stdout"))?;
+        let result = MjaiAgent::new(
+            Box::new(BufReader::new(stdout)),
+            Box::new(BufWriter::new(stdin)),
+            seat,
+        );
+        match result {
+            Ok(mut agent) => {
+                agent.child = Some(child);
+                Ok(agent)
+            }
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(e)
+            }
+        }
+    }
+
+    /// 打ち手の名前
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 最初に起きた通信の失敗
+    pub fn error(&self) -> Option<String> {
+        self.error.borrow().as_ref().map(|e| e.to_string())
+    }
+
+    /// 対局の開始を知らせる
+    pub fn start_game(&mut self, names: &[String]) -> Result<()> {
+        self.exchange(&Message::StartGame {
+            id: self.seat,
+            names: names.to_vec(),
+        })?;
+        Ok(())
+    }
+
+    /// メッセージを1つ送り、返ってきたメッセージを受け取る
+    fn exchange(&mut self, message: &Message) -> Result<Message> {
+        writeln!(self.writer, "{}", message)?;
+        self.writer.flush()?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::ConnectionClosed(self.seat));
+        }
+        Message::parse(line.trim())
+    }
+
+    /// まだ送っていないメッセージを全て送り、最後のメッセージへの返事を返す
+    ///
+    /// 失敗したら記録し、以降は何も送らずに`None`を返す。
+    fn flush(&mut self) -> Option<Message> {
+        let messages = std::mem::take(&mut self.pending);
+        if self.error.borrow().is_some() {
+            return None;
+        }
+        let mut reply = None;
+        for message in &messages {
+            match self.exchange(message) {
+                Ok(r) => reply = Some(r),
+                Err(e) => {
+                    self.fail(e);
+                    return None;
+                }
+            }
+        }
+        reply
+    }
+
+    fn fail(&mut self, error: Error) {
+        let mut current = self.error.borrow_mut();
+        if current.is_none() {
+            *current = Some(error);
+        }
+    }
+
+    /// 出来事をこの打ち手に送るメッセージにする
+    fn translate(&mut self, view: &PlayerView, event: &ObservedEvent) {
+        let event = match *event {
+            ObservedEvent::Draw { seat, tile } | ObservedEvent::DeadWallDraw { seat, tile } => {
+                self.pending.push(Message::Tsumo {
+                    actor: seat,
+                    pai: tile,
+                });
+                return;
+            }
+            ObservedEvent::North { seat, tile } => {
+                self.pending.push(Message::Nukidora {
+                    actor: seat,
+                    pai: Tile::new(Tile::Z4),
+                });
+                self.pending.push(Message::Tsumo {
+                    actor: seat,
+                    pai: tile,
+                });
+                return;
+            }
+            ObservedEvent::Discard {
+                seat,
+                tile,
+                ready,
+                tsumogiri,
+            } => {
+                if ready && !(seat == self.seat && self.reach_sent) {
+                    self.pending.push(Message::Reach { actor: seat });
+                }
+                self.pending.push(Message::Dahai {
+                    actor: seat,
+                    pai: tile,
+                    tsumogiri,
+                });
+                return;
+            }
+            ObservedEvent::Win {
+                seat,
+                from,
+                tile,
+                han,
+                fu,
+                ..
+            } => {
+                self.pending.push(Message::Hora {
+                    actor: seat,
+                    target: from.unwrap_or(seat),
+                    pai: tile.unwrap_or(Tile::new(Tile::M1)),
+                    fu,
+                    fan: han,
+                    deltas: Vec::new(),
+                    scores: Vec::new(),
+                });
+                return;
+            }
+            ObservedEvent::Public(event) => event,
+        };
+        let message = match event {
+            Event::Ready { seat } => Message::ReachAccepted { actor: *seat },
+            Event::Call {
+                seat,
+                from,
+                category,
+                tiles,
+                called,
+            } => {
+                let (actor, target, pai) = (*seat, *from, *called);
+                let consumed: Vec<Tile> = tiles.iter().map(|&t| Tile::new(t)).collect();
+                match category {
+                    OpenType::Chi => Message::Chi {
+                        actor,
+                        target,
+                        pai,
+                        consumed,
+                    },
+                    OpenType::Pon => Message::Pon {
+                        actor,
+                        target,
+                        pai,
+                        consumed,
+                    },
+                    OpenType::Kan => Message::Daiminkan {
+                        actor,
+                        target,
+                        pai,
+                        consumed,
+                    },
+                }
+            }
+            Event::ClosedKan { seat, tile } => Message::Ankan {
+                actor: *seat,
+                consumed: vec![Tile::new(*tile); 4],
+            },
+            Event::AddedKan { seat, tile } => Message::Kakan {
+                actor: *seat,
+                pai: Tile::new(*tile),
+                consumed: vec![Tile::new(*tile); 3],
+            },
+            Event::Dora { indicator } => Message::Dora {
+                dora_marker: *indicator,
+            },
+            Event::ExhaustiveDraw { ready, .. } => Message::Ryukyoku {
+                reason: "fanpai".to_string(),
+                tenpais: ready.clone(),
+                deltas: Vec::new(),
+                scores: Vec::new(),
+            },
+            Event::EndRound { score_changes, .. } => {
+                // 局全体の点数の移動を最後の和了・流局に載せる
+                let scores: Vec<i32> = (0..view.players()).map(|s| view.score(s)).collect();
+                if let Some(Message::Hora {
+                    deltas: d,
+                    scores: s,
+                    ..
+                })
+                | Some(Message::Ryukyoku {
+                    deltas: d,
+                    scores: s,
+                    ..
+                }) = self.pending.last_mut()
+                {
+                    *d = score_changes.clone();
+                    *s = scores;
+                }
+                Message::EndKyoku
+            }
+            // 自摸・打牌・和了は上で扱い、配牌は打ち手に見せない
+            _ => return,
+        };
+        self.pending.push(message);
+    }
+
+    /// 返ってきたメッセージを手番の行動にする
+    fn turn_action(&mut self, reply: Message) -> Result<TurnAction> {
+        let action = match reply {
+            Message::Dahai { pai, .. } => TurnAction::Discard {
+                tile: pai.get(),
+                ready: false,
+            },
+            Message::Reach { actor } if actor == self.seat => {
+                self.reach_sent = true;
+                match self.exchange(&Message::Reach { actor })? {
+                    Message::Dahai { pai, .. } => TurnAction::Discard {
+                        tile: pai.get(),
+                        ready: true,
+                    },
+                    message => {
+                        return Err(Error::UnexpectedMessage(format!(
+                            "Expected dahai after reach, got {}",
+                            message
+                        )))
+                    }
+                }
+            }
+            Message::Hora { .. } => TurnAction::Tsumo,
+            Message::Ankan { consumed, .. } if !consumed.is_empty() => {
+                TurnAction::ClosedKan(consumed[0].get())
+            }
+            Message::Kakan { pai, .. } => TurnAction::AddedKan(pai.get()),
+            Message::Nukidora { .. } => TurnAction::North,
+            message => {
+                return Err(Error::UnexpectedMessage(format!(
+                    "Unexpected reply on turn: {}",
+                    message
+                )))
+            }
+        };
+        Ok(action)
+    }
+
+    /// 返ってきたメッセージを他家の打牌に対する行動にする
+    fn call_action(reply: Message, actions: &[CallAction]) -> Result<CallAction> {
+        let action = match reply {
+            Message::None => CallAction::Pass,
+            Message::Hora { .. } => CallAction::Ron,
+            Message::Pon { .. } => CallAction::Pon,
+            Message::Daiminkan { .. } => CallAction::Kan,
+            Message::Chi { consumed, .. } if consumed.len() == 2 => {
+                let mut tiles = [consumed[0].get(), consumed[1].get()];
+                tiles.sort_unstable();
+                actions
+                    .iter()
+                    .copied()
+                    .find(|a| match a {
+                        CallAction::Chi(t) => {
+                            let mut t = *t;
+                            t.sort_unstable();
+                            t == tiles
+                        }
+                        _ => false,
+                    })
+                    .unwrap_or(CallAction::Chi(tiles))
+            }
+            message => {
+                return Err(Error::UnexpectedMessage(format!(
+                    "Unexpected reply to a discard: {}",
+                    message
+                )))
+            }
+        };
+        Ok(action)
+    }
+}
+
+impl Agent for MjaiAgent {
+    fn act(&mut self, view: &PlayerView, actions: &[TurnAction]) -> TurnAction {
+        let drawn = view.hand().drawn().map(|t| t.get());
+        let fallback = actions
+            .iter()
+            .copied()
+            .find(
+                |a| matches!(a, TurnAction::Discard { tile, ready: false } if Some(*tile) == drawn),
+            )
+            .or_else(|| {
+                actions
+                    .iter()
+                    .copied()
+                    .find(|a| matches!(a, TurnAction::Discard { ready: false, .. }))
+            })
+            .unwrap_or(actions[0]);
+        let reply = match self.flush() {
+            Some(reply) => reply,
+            None => return fallback,
+        };
+        match self.turn_action(reply) {
+            Ok(action) if actions.contains(&action) => action,
+            Ok(action) => {
+                self.fail(Error::IllegalAction {
+                    seat: self.seat,
+                    action,
+                });
+                fallback
+            }
+            Err(e) => {
+                self.fail(e);
+                fallback
+            }
+        }
+    }
+
+    fn respond(
+        &mut self,
+        _view: &PlayerView,
+        _tile: Tile,
+        _from: usize,
+        actions: &[CallAction],
+    ) -> CallAction {
+        let reply = match self.flush() {
+            Some(reply) => reply,
+            None => return CallAction::Pass,
+        };
+        match MjaiAgent::call_action(reply, actions) {
+            Ok(action) if actions.contains(&action) => action,
+            Ok(action) => {
+                self.fail(Error::IllegalCall {
+                    seat: self.seat,
+                    action,
+                });
+                CallAction::Pass
+            }
+            Err(e) => {
+                self.fail(e);
+                CallAction::Pass
+            }
+        }
+    }
+
+    fn start_round(&mut self, view: &PlayerView) {
+        let players = view.players();
+        self.reach_sent = false;
+        let tehais = (0..players)
+            .map(|seat| {
+                if seat == self.seat {
+                    view.hand().tiles().iter().map(|t| Some(*t)).collect()
+                } else {
+                    vec![None; view.hand().tiles().len()]
+                }
+            })
+            .collect();
+        self.pending.push(Message::StartKyoku {
+            bakaze: view.prevailing_wind(),
+            kyoku: view.dealer() + 1,
+            honba: view.honba(),
+            kyotaku: view.deposits(),
+            oya: view.dealer(),
+            dora_marker: view.dora_indicators()[0],
+            scores: (0..players).map(|s| view.score(s)).collect(),
+            tehais,
+        });
+    }
+
+    fn observe(&mut self, view: &PlayerView, event: &ObservedEvent) {
+        self.translate(view, event);
+        if let ObservedEvent::Public(Event::EndRound { .. }) = event {
+            self.flush();
+        }
+    }
+
+    fn end_game(&mut self, _placements: &[Placement]) {
+        if self.error.borrow().is_none() {
+            // 打ち手は返事をせずに終了してよい
+            if let Err(e) =
+                writeln!(self.writer, "{}", Message::EndGame).and_then(|_| self.writer.flush())
+            {
+                self.fail(e.into());
+            }
+        }
+        if let Some(mut child) = self.child.take() {
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for MjaiAgent {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// 起動した打ち手のプロセスに標準入出力がつながっていない時のエラー
+fn no_pipe(message: &str) -> Error {
+    Error::Io(std::io::ErrorKind::BrokenPipe, message.to_string())
+}
+
+/// mjaiの打ち手（席順）で対局を最後まで進める
+///
+/// いずれかの打ち手との通信に失敗した場合は、対局を最後まで進めた後にエラーを返す。
+pub fn play_mjai_game<R: Rng + ?Sized>(
+    mut agents: Vec<MjaiAgent>,
+    settings: &Settings,
+    rng: &mut R,
+) -> Result<GameRecord> {
+    let names: Vec<String> = agents.iter().map(|a| a.name().to_string()).collect();
+    for (seat, agent) in agents.iter_mut().enumerate() {
+        agent.start_game(&names).map_err(|e| Error::Bot {
+            seat,
+            source: Box::new(e),
+        })?;
+    }
+    let errors: Vec<Rc<RefCell<Option<Error>>>> = agents.iter().map(|a| a.error.clone()).collect();
+    let mut agents: Vec<Box<dyn Agent>> = agents
+        .into_iter()
+        .map(|a| Box::new(a) as Box<dyn Agent>)
+        .collect();
+    let mut game = Game::new(settings);
+    let record = play_game(&mut game, &mut agents, settings, rng)?;
+    drop(agents);
+    for (seat, error) in errors.iter().enumerate() {
+        if let Some(e) = error.borrow_mut().take() {
+            return Err(Error::Bot {
+                seat,
+                source: Box::new(e),
+            });
+        }
+    }
+    Ok(record)
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::GameLength;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::io::BufReader;
+    use std::net::TcpStream;
+    use std::path::PathBuf;
+    use std::thread;
+
+    /// ツモ切りだけをする打ち手（自分の自摸にはその牌を捨て、それ以外は何もしない）
+    fn tsumogiri_bot(reader: impl BufRead, mut writer: impl Write) -> Result<usize> {
+        let mut seat = 0;
+        let mut rounds = 0;
+        for line in reader.lines() {
+            let line = line?;
+            let reply = match Message::parse(&line)? {
+                Message::Hello => Message::Join {
+                    name: "tsumogiri".to_string(),
+                    room: "default".to_string(),
+                },
+                Message::StartGame { id, .. } => {
+                    seat = id;
+                    Message::None
+                }
+                Message::StartKyoku { tehais, .. } => {
+                    // 他家の配牌は伏せられている
+                    assert!(tehais[seat].iter().all(|t| t.is_some()));
+                    assert!(tehais[(seat + 1) % tehais.len()]
+                        .iter()
+                        .all(|t| t.is_none()));
+                    rounds += 1;
+                    Message::None
+                }
+                Message::Tsumo {
+                    actor,
+                    pai: Some(pai),
+                } if actor == seat => Message::Dahai {
+                    actor,
+                    pai,
+                    tsumogiri: true,
+                },
+                Message::Tsumo { actor, pai: None } => {
+                    assert_ne!(actor, seat);
+                    Message::None
+                }
+                Message::EndGame => break,
+                _ => Message::None,
+            };
+            writeln!(writer, "{}", reply)?;
+            writer.flush()?;
+        }
+        Ok(rounds)
+    }
+
+    fn settings() -> Settings {
+        let mut settings = Settings::new();
+        settings.game_length = GameLength::EastOnly;
+        settings
+    }
+
+    #[test]
+    /// TCPで接続した打ち手と対局する
+    fn tcp_game() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let bots: Vec<thread::JoinHandle<Result<usize>>> = (0..4)
+            .map(|_| {
+                let stream = TcpStream::connect(address).unwrap();
+                thread::spawn(move || {
+                    tsumogiri_bot(BufReader::new(stream.try_clone()?), BufWriter::new(stream))
+                })
+            })
+            .collect();
+        let agents: Vec<MjaiAgent> = (0..4)
+            .map(|seat| MjaiAgent::accept(&listener, seat).unwrap())
+            .collect();
+        assert!(agents.iter().all(|a| a.name() == "tsumogiri"));
+        let mut rng = StdRng::seed_from_u64(5);
+        let record = play_mjai_game(agents, &settings(), &mut rng).unwrap();
+
+        let deals = record
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::Deal { .. }))
+            .count();
+        for bot in bots {
+            assert_eq!(bot.join().unwrap().unwrap(), deals);
+        }
+        // ツモ切りしかしないので、鳴き・立直は起きない
+        assert!(!record
+            .events
+            .iter()
+            .any(|e| matches!(e, Event::Call { .. } | Event::Ready { .. })));
+    }
+
+    #[test]
+    /// 標準入出力で接続した打ち手のプロセスと対局する
+    fn stdio_game() {
+        // `cargo test`で一緒にビルドされるサンプルの打ち手
+        let path: PathBuf = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join("examples")
+            .join(format!("mjai_tsumogiri{}", std::env::consts::EXE_SUFFIX));
+        assert!(path.exists(), "Build the example first: {:?}", path);
+        let agents: Vec<MjaiAgent> = (0..4)
+            .map(|seat| MjaiAgent::spawn(&mut Command::new(&path), seat).unwrap())
+            .collect();
+        let mut rng = StdRng::seed_from_u64(6);
+        let record = play_mjai_game(agents, &settings(), &mut rng).unwrap();
+        assert_eq!(record.placements.len(), 4);
+    }
+
+    #[test]
+    /// 打ち手が途中で切断したら対局の後にエラーになる
+    fn disconnected() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut streams: Vec<TcpStream> = Vec::new();
+        let mut bots = Vec::new();
+        for seat in 0..4 {
+            let stream = TcpStream::connect(address).unwrap();
+            if seat == 2 {
+                // 参加だけして、その後は何も返さずに切断する
+                streams.push(stream.try_clone().unwrap());
+                bots.push(thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone()?);
+                    let mut line = String::new();
+                    reader.read_line(&mut line)?;
+                    let mut writer = stream;
+                    writeln!(
+                        writer,
+                        "{}",
+                        Message::Join {
+                            name: "quitter".to_string(),
+                            room: String::new(),
+                        }
+                    )?;
+                    Ok(0)
+                }));
+            } else {
+                bots.push(thread::spawn(move || {
+                    tsumogiri_bot(BufReader::new(stream.try_clone()?), BufWriter::new(stream))
+                }));
+            }
+        }
+        let agents: Vec<MjaiAgent> = (0..4)
+            .map(|seat| MjaiAgent::accept(&listener, seat).unwrap())
+            .collect();
+        let seat = agents.iter().position(|a| a.name() == "quitter").unwrap();
+        for stream in streams {
+            stream.shutdown(std::net::Shutdown::Both).unwrap();
+        }
+        let mut rng = StdRng::seed_from_u64(5);
+        let error = play_mjai_game(agents, &settings(), &mut rng).unwrap_err();
+        assert!(error.to_string().contains(&format!("seat {}", seat)));
+        // 切断に気付くのは読み込みか書き込みのどちらか
+        match error {
+            Error::Bot { seat: s, source } => {
+                assert_eq!(s, seat);
+                assert!(matches!(
+                    *source,
+                    Error::ConnectionClosed(_) | Error::Io(..)
+                ));
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+        for bot in bots {
+            let _ = bot.join().unwrap();
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::error::{Error, Result};
+use crate::hand::Hand;
+use crate::tile::*;
+
+/// 1種類の牌の枚数に使うビット数
+const BITS: u32 = 3;
+/// 1種類の牌の枚数を取り出すマスク
+const MASK: u32 = (1 << BITS) - 1;
+
+/// 牌の種類ごとの枚数を1種類3ビットに詰めた手牌
+///
+/// 萬子・筒子・索子・字牌をそれぞれ1つの`u32`に入れる（数牌は27ビット、字牌は21ビット）。
+/// 赤ドラや副露の区別は持たない。比較・ハッシュは16バイトの値どうしで行うので、
+/// キャッシュや置換表のキーに使える。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::hand::Hand;
+/// use riichi_mahjong_rs::packed_hand::PackedHand;
+/// use riichi_mahjong_rs::tile::Tile;
+///
+/// let mut packed = PackedHand::from_hand(&Hand::parse_lossy("123m456p789s1122z")).unwrap();
+/// packed.add(Tile::Z2).unwrap();
+/// assert_eq!(packed.count(Tile::Z2), Ok(3));
+/// assert_eq!(packed.len(), 14);
+///
+/// // 数牌の種類を入れ替えた手牌は同じ標準形になる
+/// let other = PackedHand::from_hand(&Hand::parse_lossy("123p456s789m1122z 2z")).unwrap();
+/// assert_ne!(packed, other);
+/// assert_eq!(packed.canonical(), other.canonical());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackedHand {
+    /// 萬子・筒子・索子・字牌の順
+    words: [u32; 4],
+}
+
+impl PackedHand {
+    /// 牌のない手牌
+    pub fn new() -> PackedHand {
+        PackedHand::default()
+    }
+
+    /// 牌の種類ごとの枚数から作る（各牌は4枚まで）
+    pub fn from_summary(summary: &TileSummarize) -> Result<PackedHand> {
+        let mut result = PackedHand::new();
+        for (t, &count) in summary.iter().enumerate() {
+            if count > 4 {
+                return Err(Error::TooManyCopies(t as TileType));
+            }
+            let (word, shift) = PackedHand::position(t as TileType);
+            result.words[word] |= count << shift;
+        }
+        return Ok(result);
+    }
+
+    /// 手牌（副露・ツモった牌を含む）から作る
+    pub fn from_hand(hand: &Hand) -> Result<PackedHand> {
+        return PackedHand::from_summary(&hand.summarize_tiles());
+    }
+
+    /// 牌の種類ごとの枚数に戻す
+    pub fn to_summary(&self) -> TileSummarize {
+        let mut result: TileSummarize = [0; Tile::LEN];
+        for (t, count) in result.iter_mut().enumerate() {
+            *count = self.get(t as TileType);
+        }
+        return result;
+    }
+
+    /// 副露のない手牌に戻す（赤ドラの区別はなくなり、ツモった牌は手牌に入る）
+    pub fn to_hand(&self) -> Hand {
+        return Hand::from_summarized(&self.to_summary());
+    }
+
+    /// 牌の枚数
+    pub fn count(&self, tile: TileType) -> Result<u32> {
+        if tile > Tile::Z7 {
+            return Err(Error::InvalidTile(tile));
+        }
+        return Ok(self.get(tile));
+    }
+
+    /// 牌を1枚加える（5枚目は加えられない）
+    pub fn add(&mut self, tile: TileType) -> Result<()> {
+        if self.count(tile)? >= 4 {
+            return Err(Error::TooManyCopies(tile));
+        }
+        let (word, shift) = PackedHand::position(tile);
+        self.words[word] += 1 << shift;
+        return Ok(());
+    }
+
+    /// 牌を1枚取り除く
+    pub fn remove(&mut self, tile: TileType) -> Result<()> {
+        if self.count(tile)? == 0 {
+            return Err(Error::NoTileToRemove(tile));
+        }
+        let (word, shift) = PackedHand::position(tile);
+        self.words[word] -= 1 << shift;
+        return Ok(());
+    }
+
+    /// 牌の枚数の合計
+    pub fn len(&self) -> u32 {
+        let mut result = 0;
+        for word in self.words {
+            let mut rest = word;
+            while rest > 0 {
+                result += rest & MASK;
+                rest >>= BITS;
+            }
+        }
+        return result;
+    }
+
+    /// 牌がないか
+    pub fn is_empty(&self) -> bool {
+        self.words == [0; 4]
+    }
+
+    /// 萬子・筒子・索子を入れ替えても同じになる標準形
+    ///
+    /// 数牌の3つの`u32`を大きい順に並べ替える。向聴数や和了牌の形のように
+    /// 数牌の種類によらない結果を、入れ替えた手牌の間で共有するために使う。
+    pub fn canonical(&self) -> PackedHand {
+        let mut words = self.words;
+        words[..3].sort_unstable_by(|a, b| b.cmp(a));
+        return PackedHand { words };
+    }
+
+    /// 萬子・筒子・索子・字牌の順に詰めた値
+    pub fn words(&self) -> [u32; 4] {
+        self.words
+    }
+
+    /// 牌の枚数（`tile`は範囲内であること）
+    fn get(&self, tile: TileType) -> u32 {
+        let (word, shift) = PackedHand::position(tile);
+        return (self.words[word] >> shift) & MASK;
+    }
+
+    /// 牌の入る`u32`の位置とビットの位置
+    fn position(tile: TileType) -> (usize, u32) {
+        return ((tile / 9) as usize, (tile % 9) * BITS);
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::hand_info::shanten::shanten_of_summary;
+    #[cfg(feature = "std")]
+    use crate::test_util::RandomHands;
+    use alloc::string::ToString;
+    #[cfg(feature = "std")]
+    use rand::seq::SliceRandom;
+
+    #[test]
+    /// 手牌・牌の種類ごとの枚数との変換
+    fn conversion() {
+        let hand = Hand::parse_lossy("1112345678999m 5m");
+        let packed = PackedHand::from_hand(&hand).unwrap();
+        assert_eq!(packed.to_summary(), hand.summarize_tiles());
+        assert_eq!(packed.count(Tile::M1), Ok(3));
+        assert_eq!(packed.count(Tile::M5), Ok(2));
+        assert_eq!(packed.count(Tile::Z7), Ok(0));
+        assert_eq!(packed.count(Tile::Z7 + 1), Err(Error::InvalidTile(Tile::Z7 + 1)));
+        assert_eq!(packed.to_hand().to_string(), "1m1m1m2m3m4m5m5m6m7m8m9m9m9m");
+        assert_eq!(packed.words()[0], 0o311121113);
+
+        let mut summary = [0; Tile::LEN];
+        summary[Tile::Z1 as usize] = 5;
+        assert!(PackedHand::from_summary(&summary).is_err());
+    }
+
+    #[test]
+    /// 牌の出し入れ
+    fn add_remove() {
+        let mut packed = PackedHand::new();
+        assert!(packed.is_empty());
+        for _ in 0..4 {
+            packed.add(Tile::Z7).unwrap();
+        }
+        assert!(packed.add(Tile::Z7).is_err());
+        assert!(packed.add(Tile::Z7 + 1).is_err());
+        packed.add(Tile::S9).unwrap();
+        assert_eq!(packed.len(), 5);
+        packed.remove(Tile::Z7).unwrap();
+        assert_eq!(packed.count(Tile::Z7), Ok(3));
+        assert_eq!(packed.count(Tile::S9), Ok(1));
+        assert!(packed.remove(Tile::M1).is_err());
+        assert_ne!(packed, PackedHand::new());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    /// 数牌の種類を入れ替えた手牌は同じ標準形・同じ向聴数になる
+    fn canonical() {
+        let mut random = RandomHands::new(43);
+        for _ in 0..200 {
+            let packed =
+                PackedHand::from_summary(&Hand::new(random.tiles(14), None).summarize_tiles())
+                    .unwrap();
+            let mut suits = [0, 1, 2];
+            suits.shuffle(random.rng());
+            let mut swapped = PackedHand::new();
+            for t in Tile::M1..=Tile::Z7 {
+                let to = if t <= Tile::S9 {
+                    suits[(t / 9) as usize] * 9 + t % 9
+                } else {
+                    t
+                };
+                for _ in 0..packed.count(t).unwrap() {
+                    swapped.add(to).unwrap();
+                }
+            }
+            assert_eq!(packed.canonical(), swapped.canonical());
+            assert_eq!(packed.canonical().canonical(), packed.canonical());
+            assert_eq!(
+                shanten_of_summary(&packed.to_summary()),
+                shanten_of_summary(&packed.canonical().to_summary())
+            );
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 /// 表示をどの言語にするかの列挙型
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Lang {
     /// 英語
     En,
@@ -7,12 +11,86 @@ pub enum Lang {
     Ja,
 }
 
+/// 対局の長さの列挙型
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameLength {
+    /// 東風戦
+    EastOnly,
+    /// 東南戦（半荘）
+    EastSouth,
+}
+
+/// 人数の列挙型
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameMode {
+    /// 四人麻雀
+    FourPlayers,
+    /// 三人麻雀
+    ThreePlayers,
+}
+
+impl GameMode {
+    /// 人数を返す
+    pub fn players(&self) -> usize {
+        match self {
+            GameMode::FourPlayers => 4,
+            GameMode::ThreePlayers => 3,
+        }
+    }
+    /// チーができるか
+    pub fn allows_chi(&self) -> bool {
+        *self == GameMode::FourPlayers
+    }
+}
+
+/// 三人麻雀の自摸和了の支払い方の列挙型
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreePlayerTsumo {
+    /// ツモ損（いない北家の分は支払われない）
+    Loss,
+    /// 北家折半（いない北家の分を残りの2人で折半する）
+    Split,
+}
+
 /// 設定
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Settings {
     /// 表示言語（デフォルトは日本語）
     pub display_lang: Lang,
     /// 喰いタンありかなしか（デフォルトはあり）
     pub openned_all_simples: bool,
+    /// 役のない聴牌（形式聴牌）を流局時に聴牌と認めるか（デフォルトは認める）
+    pub ready_without_winning_hand: bool,
+    /// 自分で4枚使っている牌のみを待つ聴牌を流局時に聴牌と認めるか（デフォルトは認めない）
+    pub ready_with_own_four_tiles: bool,
+    /// 人数（デフォルトは四人麻雀）
+    pub game_mode: GameMode,
+    /// 三人麻雀の自摸和了の支払い方（デフォルトはツモ損）
+    pub three_player_tsumo: ThreePlayerTsumo,
+    /// 対局の長さ（デフォルトは東南戦）
+    pub game_length: GameLength,
+    /// 配給原点（デフォルトは25000点）
+    pub initial_score: i32,
+    /// 返し点（デフォルトは30000点）。オーラス終了時に誰も達していなければ延長戦に入る
+    pub returning_score: i32,
+    /// 順位ごとのウマ（デフォルトは1位から順に+20000点・+10000点・-10000点・-20000点）
+    pub uma: Vec<i32>,
+    /// 持ち点が0点未満になったら終了するか（デフォルトは終了する）
+    pub busting: bool,
+    /// 返し点に誰も達していない時に延長戦（東風戦なら南入、東南戦なら西入）を行うか（デフォルトは行う）
+    pub extra_round: bool,
+    /// オーラスで親が和了るか聴牌して1位なら終了できるか（デフォルトは終了できる）
+    pub dealer_stop_at_last_round: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Settings {
@@ -20,6 +98,28 @@ impl Settings {
         Settings {
             display_lang: Lang::Ja,
             openned_all_simples: true,
+            ready_without_winning_hand: true,
+            ready_with_own_four_tiles: false,
+            game_mode: GameMode::FourPlayers,
+            three_player_tsumo: ThreePlayerTsumo::Loss,
+            game_length: GameLength::EastSouth,
+            initial_score: 25000,
+            returning_score: 30000,
+            uma: vec![20000, 10000, -10000, -20000],
+            busting: true,
+            extra_round: true,
+            dealer_stop_at_last_round: true,
+        }
+    }
+
+    /// 三人麻雀の設定（35000点持ち40000点返し、ウマは+20000点・0点・-20000点）
+    pub fn new_three_players() -> Settings {
+        Settings {
+            game_mode: GameMode::ThreePlayers,
+            initial_score: 35000,
+            returning_score: 40000,
+            uma: vec![20000, 0, -20000],
+            ..Settings::new()
         }
     }
 }
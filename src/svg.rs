@@ -0,0 +1,523 @@
+use std::fmt::Write;
+
+use crate::board::round::{Discard, Round};
+use crate::board::wall::Wall;
+use crate::hand::Hand;
+use crate::hand_info::opened::{OpenFrom, OpenTiles, OpenType};
+use crate::tile::*;
+
+/// 牌の幅
+pub const TILE_WIDTH: i32 = 30;
+/// 牌の高さ
+pub const TILE_HEIGHT: i32 = 40;
+
+/// 卓の中央の枠の大きさの半分（河はこの外側に並べる）
+const CENTER: i32 = 120;
+/// 河の1段に並べる牌の数
+const RIVER_ROW: usize = 6;
+/// 卓の大きさの半分
+const TABLE: i32 = CENTER + 4 + TILE_HEIGHT * 4 + 16;
+
+/// 萬子の漢数字
+const NUMERALS: [&str; 9] = ["一", "二", "三", "四", "五", "六", "七", "八", "九"];
+/// 字牌の表記
+const HONORS: [&str; 7] = ["東", "南", "西", "北", "白", "發", "中"];
+
+/// 牌の置き方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pose {
+    /// 縦に置く
+    Upright,
+    /// 横に倒す（鳴いた牌・立直宣言牌）
+    Sideways,
+}
+
+/// 手牌をSVGの画像にする
+///
+/// 手牌・ツモった牌・副露の順に左から並べる。鳴いた牌は誰から鳴いたかに応じて横に倒し
+/// （上家は左端・対面は中央・下家は右端、チーは常に左端）、加カンで加えた牌はその上に重ねる。
+/// 暗カンは両端の2枚を伏せる。
+pub fn hand_to_svg(hand: &Hand) -> String {
+    let mut body = String::new();
+    let bottom = 4 + TILE_WIDTH * 2;
+    let width = draw_hand(&mut body, hand, 4, bottom);
+    return wrap_svg(width + 8, bottom + 4, &body);
+}
+
+/// 牌1枚をSVGの画像にする（`None`なら伏せた牌）
+pub fn tile_to_svg(tile: Option<Tile>) -> String {
+    let mut body = String::new();
+    draw_tile(&mut body, tile, 2, 2, Pose::Upright, false);
+    return wrap_svg(TILE_WIDTH + 4, TILE_HEIGHT + 4, &body);
+}
+
+/// 卓の状態をSVGの画像にする
+///
+/// 各席の河（立直宣言牌は横に倒し、鳴かれた牌は薄くする）と、中央に局・本場・供託・残り枚数、
+/// ドラ表示牌（めくられていない分は伏せる）、各席の自風と持ち点、立直棒を描く。
+/// `viewer`の席を下側に置き、そこから反時計回りに下家・対面・上家の順に並べる（三人麻雀では対面を空ける）。
+pub fn table_to_svg(round: &Round, wall: &Wall, viewer: usize) -> String {
+    let mut body = String::new();
+    let players = round.players.len();
+    let _ = write!(
+        body,
+        r##"<rect width="{s}" height="{s}" fill="#2d6a4f"/><g transform="translate({c},{c})"><rect x="{x}" y="{x}" width="{w}" height="{w}" rx="8" fill="#1b4332"/>"##,
+        s = TABLE * 2,
+        c = TABLE,
+        x = -CENTER,
+        w = CENTER * 2,
+    );
+
+    // 局の情報
+    let _ = write!(
+        body,
+        r##"<text x="0" y="-62" font-size="16" fill="#fff" text-anchor="middle">{}{}局 {}本場</text>"##,
+        HONORS[round.prevailing_wind as usize - Tile::Z1 as usize],
+        round.dealer + 1,
+        round.honba,
+    );
+    let _ = write!(
+        body,
+        r##"<text x="0" y="24" font-size="12" fill="#fff" text-anchor="middle">供託 {} 残り {}</text>"##,
+        round.deposits,
+        wall.remaining(),
+    );
+    // ドラ表示牌（最大5枚）
+    let indicators = wall.dora_indicators();
+    let x = -TILE_WIDTH * 5 / 2;
+    for i in 0..5 {
+        draw_tile(
+            &mut body,
+            indicators.get(i).copied(),
+            x + TILE_WIDTH * i as i32,
+            -TILE_HEIGHT - 4,
+            Pose::Upright,
+            false,
+        );
+    }
+
+    for seat in 0..players {
+        let mut position = (seat + players - viewer) % players;
+        if players == 3 && position == 2 {
+            // 三人麻雀の上家は左側に置く
+            position = 3;
+        }
+        let angle = -90 * position as i32;
+        let _ = write!(body, r#"<g transform="rotate({})">"#, angle);
+        let player = &round.players[seat];
+        let wind = HONORS[round.player_wind(seat) as usize - Tile::Z1 as usize];
+        let _ = write!(
+            body,
+            r##"<text x="0" y="{}" font-size="14" fill="#fff" text-anchor="middle">{} {}</text>"##,
+            CENTER - 14,
+            wind,
+            player.score,
+        );
+        if player.status.has_claimed_ready {
+            draw_ready_stick(&mut body, 0, CENTER - 30);
+        }
+        draw_river(&mut body, &player.river, -CENTER, CENTER + 4);
+        body.push_str("</g>");
+    }
+    body.push_str("</g>");
+    return wrap_svg(TABLE * 2, TABLE * 2, &body);
+}
+
+/// `svg`要素で囲む
+fn wrap_svg(width: i32, height: i32, body: &str) -> String {
+    return format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\">{b}</svg>\n",
+        w = width,
+        h = height,
+        b = body
+    );
+}
+
+/// 手牌を並べ、並べた幅を返す（`bottom`は牌の下端）
+fn draw_hand(out: &mut String, hand: &Hand, x: i32, bottom: i32) -> i32 {
+    let mut pos = x;
+    for tile in hand.tiles() {
+        draw_tile(
+            out,
+            Some(*tile),
+            pos,
+            bottom - TILE_HEIGHT,
+            Pose::Upright,
+            false,
+        );
+        pos += TILE_WIDTH;
+    }
+    if let Some(tile) = hand.drawn() {
+        pos += 8;
+        draw_tile(
+            out,
+            Some(tile),
+            pos,
+            bottom - TILE_HEIGHT,
+            Pose::Upright,
+            false,
+        );
+        pos += TILE_WIDTH;
+    }
+    for (i, open) in hand.opened().iter().enumerate() {
+        pos += if i == 0 { 16 } else { 6 };
+        pos += draw_open(out, open, pos, bottom);
+    }
+    return pos - x;
+}
+
+/// 副露を並べ、並べた幅を返す
+fn draw_open(out: &mut String, open: &OpenTiles, x: i32, bottom: i32) -> i32 {
+    let closed = open.category == OpenType::Kan && open.from == OpenFrom::Myself;
+    let added = open.category == OpenType::Kan && open.added;
+    let mut tiles: Vec<Tile> = open.tiles.to_vec();
+    if open.category == OpenType::Kan && !added {
+        tiles.push(open.tiles[0]);
+    }
+    // 横に倒す牌の位置
+    let sideways = match (open.category, open.from) {
+        (_, OpenFrom::Previous) => Some(0),
+        (_, OpenFrom::Opposite) => Some(1),
+        (_, OpenFrom::Following) => Some(tiles.len() - 1),
+        _ => None,
+    };
+    if let (Some(s), Some(called)) = (sideways, open.called) {
+        if let Some(p) = tiles.iter().position(|t| *t == called) {
+            let t = tiles.remove(p);
+            tiles.insert(s, t);
+        }
+    }
+
+    let mut pos = x;
+    for (i, tile) in tiles.iter().enumerate() {
+        if Some(i) == sideways {
+            draw_tile(
+                out,
+                Some(*tile),
+                pos,
+                bottom - TILE_WIDTH,
+                Pose::Sideways,
+                false,
+            );
+            if added {
+                draw_tile(
+                    out,
+                    Some(open.tiles[0]),
+                    pos,
+                    bottom - TILE_WIDTH * 2,
+                    Pose::Sideways,
+                    false,
+                );
+            }
+            pos += TILE_HEIGHT;
+        } else {
+            // 暗カンは両端を伏せる
+            let face = if closed && (i == 0 || i == tiles.len() - 1) {
+                None
+            } else {
+                Some(*tile)
+            };
+            draw_tile(out, face, pos, bottom - TILE_HEIGHT, Pose::Upright, false);
+            pos += TILE_WIDTH;
+        }
+    }
+    // 誰から鳴いたか不明な加カンは4枚目を並べる
+    if added && sideways.is_none() {
+        draw_tile(
+            out,
+            Some(open.tiles[0]),
+            pos,
+            bottom - TILE_HEIGHT,
+            Pose::Upright,
+            false,
+        );
+        pos += TILE_WIDTH;
+    }
+    return pos - x;
+}
+
+/// 河を6枚ずつ並べる（`x`・`y`は左上）
+fn draw_river(out: &mut String, river: &[Discard], x: i32, y: i32) {
+    for (row, discards) in river.chunks(RIVER_ROW).enumerate() {
+        let top = y + TILE_HEIGHT * row as i32;
+        let mut pos = x;
+        for d in discards {
+            if d.is_ready {
+                draw_tile(
+                    out,
+                    Some(d.tile),
+                    pos,
+                    top + TILE_HEIGHT - TILE_WIDTH,
+                    Pose::Sideways,
+                    d.is_called,
+                );
+                pos += TILE_HEIGHT;
+            } else {
+                draw_tile(out, Some(d.tile), pos, top, Pose::Upright, d.is_called);
+                pos += TILE_WIDTH;
+            }
+        }
+    }
+}
+
+/// 立直棒を描く（`x`・`y`は中心）
+fn draw_ready_stick(out: &mut String, x: i32, y: i32) {
+    let _ = write!(
+        out,
+        r##"<rect x="{}" y="{}" width="80" height="6" rx="3" fill="#fff"/><circle cx="{}" cy="{}" r="2.5" fill="#d00"/>"##,
+        x - 40,
+        y - 3,
+        x,
+        y,
+    );
+}
+
+/// 牌を1枚描く（`x`・`y`は置いた牌の左上。`None`なら伏せた牌、`dim`なら薄くする）
+fn draw_tile(out: &mut String, tile: Option<Tile>, x: i32, y: i32, pose: Pose, dim: bool) {
+    // 牌の絵は縦に置いた時の座標で描き、横に倒す時は回転する
+    let transform = match pose {
+        Pose::Upright => format!("translate({},{})", x, y),
+        Pose::Sideways => format!("translate({},{}) rotate(-90)", x, y + TILE_WIDTH),
+    };
+    let opacity = if dim { r#" opacity="0.5""# } else { "" };
+    let _ = write!(out, r#"<g transform="{}"{}>"#, transform, opacity);
+    let fill = if tile.is_some() { "#fdfbf3" } else { "#2e7d5b" };
+    let _ = write!(
+        out,
+        r##"<rect x="0.5" y="0.5" width="{}" height="{}" rx="4" fill="{}" stroke="#555"/>"##,
+        TILE_WIDTH - 1,
+        TILE_HEIGHT - 1,
+        fill,
+    );
+    if let Some(t) = tile {
+        draw_face(out, t);
+    }
+    out.push_str("</g>");
+}
+
+/// 牌の絵柄を描く
+fn draw_face(out: &mut String, tile: Tile) {
+    let t = tile.get();
+    let red = tile.is_red_dora();
+    let number = (t % 9 + 1) as usize;
+    match t {
+        Tile::M1..=Tile::M9 => {
+            let color = if red { "#d00" } else { "#222" };
+            let _ = write!(
+                out,
+                r##"<text x="15" y="18" font-size="14" fill="{}" text-anchor="middle">{}</text><text x="15" y="35" font-size="14" fill="#c00" text-anchor="middle">萬</text>"##,
+                color,
+                NUMERALS[number - 1],
+            );
+        }
+        Tile::P1..=Tile::P9 => {
+            let color = if red { "#d00" } else { "#1a4f9c" };
+            let r = match number {
+                1 => 10.0,
+                2..=6 => 4.8,
+                _ => 3.6,
+            };
+            for (cx, cy) in pips(number) {
+                let _ = write!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="1.5"/><circle cx="{}" cy="{}" r="{:.1}" fill="{}"/>"#,
+                    cx,
+                    cy,
+                    r,
+                    color,
+                    cx,
+                    cy,
+                    r / 3.0,
+                    color,
+                );
+            }
+        }
+        Tile::S1..=Tile::S9 => {
+            let color = if red { "#d00" } else { "#1b7a3a" };
+            let (w, h) = match number {
+                1 => (6.0, 24.0),
+                7 | 8 => (3.5, 7.0),
+                _ => (3.5, 9.0),
+            };
+            for (cx, cy) in pips(number) {
+                let _ = write!(
+                    out,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" rx="1.5" fill="{}"/>"#,
+                    cx - w / 2.0,
+                    cy - h / 2.0,
+                    w,
+                    h,
+                    color,
+                );
+            }
+        }
+        _ => {
+            let color = match t {
+                Tile::Z5 => "#1a4f9c",
+                Tile::Z6 => "#1b7a3a",
+                Tile::Z7 => "#d00",
+                _ => "#222",
+            };
+            if t == Tile::Z5 {
+                // 白は枠だけを描く
+                let _ = write!(
+                    out,
+                    r#"<rect x="6" y="7" width="18" height="26" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+                    color
+                );
+            } else {
+                let _ = write!(
+                    out,
+                    r#"<text x="15" y="28" font-size="20" fill="{}" text-anchor="middle">{}</text>"#,
+                    color,
+                    HONORS[(t - Tile::Z1) as usize],
+                );
+            }
+        }
+    }
+}
+
+/// 筒子・索子の絵柄の位置
+fn pips(number: usize) -> Vec<(f64, f64)> {
+    let (l, c, r) = (9.0, 15.0, 21.0);
+    return match number {
+        1 => vec![(c, 20.0)],
+        2 => vec![(c, 12.0), (c, 28.0)],
+        3 => vec![(l, 9.0), (c, 20.0), (r, 31.0)],
+        4 => vec![(l, 12.0), (r, 12.0), (l, 28.0), (r, 28.0)],
+        5 => vec![(l, 11.0), (r, 11.0), (c, 20.0), (l, 29.0), (r, 29.0)],
+        6 => vec![
+            (l, 9.0),
+            (r, 9.0),
+            (l, 20.0),
+            (r, 20.0),
+            (l, 31.0),
+            (r, 31.0),
+        ],
+        7 => vec![
+            (l, 8.0),
+            (c, 12.0),
+            (r, 16.0),
+            (l, 24.0),
+            (r, 24.0),
+            (l, 32.0),
+            (r, 32.0),
+        ],
+        8 => vec![
+            (l, 8.0),
+            (r, 8.0),
+            (l, 16.0),
+            (r, 16.0),
+            (l, 24.0),
+            (r, 24.0),
+            (l, 32.0),
+            (r, 32.0),
+        ],
+        _ => [9.0, 20.0, 31.0]
+            .iter()
+            .flat_map(|y| [(l, *y), (c, *y), (r, *y)])
+            .collect(),
+    };
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::engine::deal;
+    use crate::board::game::Game;
+    use crate::settings::Settings;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// 伏せた牌の数
+    fn face_down(svg: &str) -> usize {
+        svg.matches("#2e7d5b").count()
+    }
+
+    /// 横に倒した牌の数
+    fn sideways(svg: &str) -> usize {
+        svg.matches(") rotate(-90)").count()
+    }
+
+    #[test]
+    /// 鳴いた牌を横に倒し、暗カンの両端を伏せる
+    fn hand() {
+        let hand = Hand::parse_lossy("1m9p [7z7z7z7z] 4s<5s6s 1z^1z1z 2m2m>2m2m 5p5p^5p+5p 1m");
+        let svg = hand_to_svg(&hand);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // 手牌2枚・ツモ1枚・副露3+4+3+3+4枚（加カンの2枚目も数える）
+        assert_eq!(
+            svg.matches("<rect x=\"0.5\"").count(),
+            2 + 1 + 4 + 3 + 3 + 4 + 4
+        );
+        assert_eq!(face_down(&svg), 2);
+        // チー・ポン・大明槓・加カン（重ねた牌を含む）
+        assert_eq!(sideways(&svg), 5);
+        // 誰から鳴いたか不明なら倒さない
+        assert_eq!(
+            sideways(&hand_to_svg(&Hand::parse_lossy("1m 123m 1z1z1z 2m2m2m2m"))),
+            0
+        );
+    }
+
+    #[test]
+    /// 赤ドラと字牌の絵柄
+    fn tiles() {
+        let svg = tile_to_svg(Some(Tile::new_red(Tile::P5)));
+        assert_eq!(svg.matches("<circle").count(), 10);
+        assert!(svg.contains("#d00"));
+        assert!(tile_to_svg(Some(Tile::new(Tile::Z7))).contains(">中<"));
+        assert!(tile_to_svg(Some(Tile::new(Tile::M3))).contains(">三<"));
+        assert_eq!(face_down(&tile_to_svg(None)), 1);
+    }
+
+    #[test]
+    /// 河・ドラ表示牌・点数・立直棒を描く
+    fn table() {
+        let settings = Settings::new();
+        let game = Game::new(&settings);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut wall = Wall::new(4, &mut rng);
+        let hands = deal(&mut wall, 0, 4).unwrap();
+        let mut round = game.new_round(hands);
+        for seat in 0..4 {
+            for _ in 0..7 {
+                let tile = wall.draw().unwrap();
+                round.players[seat].river.push(Discard::new(tile));
+            }
+        }
+        round.players[2].river[3].is_ready = true;
+        round.players[2].status.has_claimed_ready = true;
+        round.players[1].river[0].is_called = true;
+        round.players[3].score = 31000;
+
+        let svg = table_to_svg(&round, &wall, 0);
+        assert!(svg.contains(">東1局 0本場<"));
+        assert!(svg.contains(">北 31000<"));
+        assert!(svg.contains(">東 25000<"));
+        // 河28枚とドラ表示牌5枚（1枚だけめくられている）
+        assert_eq!(svg.matches("<rect x=\"0.5\"").count(), 28 + 5);
+        assert_eq!(face_down(&svg), 4);
+        assert_eq!(sideways(&svg), 1);
+        assert_eq!(svg.matches("opacity=\"0.5\"").count(), 1);
+        assert_eq!(svg.matches("rx=\"3\" fill=\"#fff\"").count(), 1);
+        // 見る席を変えると回転が変わる
+        assert!(table_to_svg(&round, &wall, 1).contains("rotate(-270)"));
+
+        // 三人麻雀では上家を左側に置き、対面を空ける
+        let settings = Settings::new_three_players();
+        let game = Game::new(&settings);
+        let mut wall = Wall::new(3, &mut rng);
+        let hands = deal(&mut wall, 0, 3).unwrap();
+        let round = game.new_round(hands);
+        for viewer in 0..3 {
+            let svg = table_to_svg(&round, &wall, viewer);
+            for angle in [0, -90, -270] {
+                assert!(svg.contains(&format!("<g transform=\"rotate({})\">", angle)));
+            }
+            assert!(!svg.contains("<g transform=\"rotate(-180)\">"));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(feature = "std")]
+use crate::hand::Hand;
+use crate::tile::Tile;
+use alloc::vec::Vec;
+use proptest::prelude::*;
+#[cfg(feature = "std")]
+use rand::rngs::StdRng;
+#[cfg(feature = "std")]
+use rand::seq::SliceRandom;
+#[cfg(feature = "std")]
+use rand::SeedableRng;
+
+/// 四人麻雀の牌の一式（赤ドラなし、同じ牌は4枚ずつ）を返す
+pub(crate) fn tile_set() -> Vec<Tile> {
+    return (Tile::M1..=Tile::Z7).flat_map(|t| [Tile::new(t); 4]).collect();
+}
+
+/// 牌の一式をシャッフルして先頭の`len`枚を返す
+pub(crate) fn arb_tiles(len: usize) -> impl Strategy<Value = Vec<Tile>> {
+    return Just(tile_set())
+        .prop_shuffle()
+        .prop_map(move |tiles| tiles[..len].to_vec());
+}
+
+/// 種を決めた乱数で、牌の一式をシャッフルした手牌を作る
+#[cfg(feature = "std")]
+pub(crate) struct RandomHands {
+    rng: StdRng,
+    tiles: Vec<Tile>,
+}
+
+#[cfg(feature = "std")]
+impl RandomHands {
+    /// 四人麻雀の牌の一式から作る
+    pub fn new(seed: u64) -> RandomHands {
+        return RandomHands::with_tiles(seed, tile_set());
+    }
+
+    /// 与えた牌の一式から作る
+    pub fn with_tiles(seed: u64, tiles: Vec<Tile>) -> RandomHands {
+        return RandomHands {
+            rng: StdRng::seed_from_u64(seed),
+            tiles,
+        };
+    }
+
+    /// 牌の一式をシャッフルして先頭の`len`枚を返す
+    pub fn tiles(&mut self, len: usize) -> Vec<Tile> {
+        self.tiles.shuffle(&mut self.rng);
+        return self.tiles[..len].to_vec();
+    }
+
+    /// 13枚の手牌を作り、`drawn`なら14枚目をツモった牌とする
+    pub fn hand(&mut self, drawn: bool) -> Hand {
+        let tiles = self.tiles(14);
+        return Hand::new(tiles[..13].to_vec(), drawn.then_some(tiles[13]));
+    }
+
+    /// 手牌を作るのに使っている乱数
+    pub fn rng(&mut self) -> &mut StdRng {
+        return &mut self.rng;
+    }
+}
@@ -1,10 +1,12 @@
+use alloc::string::{String, ToString};
+
 /// 牌の種類を示す型
 pub type TileType = u32;
 
 pub type TileSummarize = [u32; Tile::LEN];
 
 /// 牌
-#[derive(Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Tile {
     index: TileType,
     red_dora: bool,
@@ -101,10 +103,23 @@ impl Tile {
         };
     }
 
+    /// 赤ドラの牌を作る（五萬・五筒・五索以外は通常の牌になる）
+    pub fn new_red(tile_type: TileType) -> Tile {
+        return Tile {
+            index: tile_type,
+            red_dora: matches!(tile_type, Tile::M5 | Tile::P5 | Tile::S5),
+        };
+    }
+
     pub fn get(&self) -> TileType {
         return self.index;
     }
 
+    /// 赤ドラか否かを返す
+    pub fn is_red_dora(&self) -> bool {
+        return self.red_dora;
+    }
+
     /*
         /// 萬子か否かを返す
         pub fn is_character(&self) -> bool {
@@ -169,11 +184,10 @@ impl Tile {
     pub fn to_char(&self) -> char {
         return Tile::CHARS[self.index as usize];
     }
-    pub fn to_string(&self) -> String {
-        return Tile::ASCII[self.index as usize].to_string();
-    }
-
-    pub fn from(tile_name: &str) -> Option<Tile> {
+    /// `"5m"`・`"🀋"`のような文字列から牌を作る（読めなければ`None`）
+    ///
+    /// 赤ドラの五（`"0m"`など）は読めない。赤ドラも読むなら`parse`（`FromStr`）を使う。
+    pub fn from_name(tile_name: &str) -> Option<Tile> {
         let t = match tile_name {
             "1m" | "🀇" => Tile::M1,
             "2m" | "🀈" => Tile::M2,
@@ -215,10 +229,72 @@ impl Tile {
         };
         return Some(Tile::new(t));
     }
+
+    /// `from_name`の旧名（`From`トレイトと紛らわしいので改名した）
+    #[deprecated(note = "use `Tile::from_name` or `str::parse::<Tile>` instead")]
+    pub fn from(tile_name: &str) -> Option<Tile> {
+        return Tile::from_name(tile_name);
+    }
+}
+
+/// `"5m"`のような文字列にする（赤ドラの五は`"0m"`・`"0p"`・`"0s"`）
+impl core::fmt::Display for Tile {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let name = Tile::ASCII[self.index as usize];
+        if self.red_dora {
+            return write!(f, "0{}", &name[1..]);
+        }
+        f.write_str(name)
+    }
+}
+
+/// `"5m"`・`"🀋"`のような文字列から読み込む（`"0m"`・`"0p"`・`"0s"`は赤ドラの五）
+impl core::str::FromStr for Tile {
+    type Err = ParseTileError;
+
+    fn from_str(s: &str) -> core::result::Result<Tile, ParseTileError> {
+        let tile = match s {
+            "0m" => Some(Tile::new_red(Tile::M5)),
+            "0p" => Some(Tile::new_red(Tile::P5)),
+            "0s" => Some(Tile::new_red(Tile::S5)),
+            name => Tile::from_name(name),
+        };
+        return tile.ok_or_else(|| ParseTileError(s.to_string()));
+    }
+}
+
+/// 牌として読めない文字列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTileError(pub String);
+
+impl core::fmt::Display for ParseTileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "invalid tile: {}", self.0)
+    }
+}
+
+impl core::error::Error for ParseTileError {}
+
+/// `"5m"`のような文字列にする（赤ドラの五は`"0m"`・`"0p"`・`"0s"`）
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tile {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.collect_str(self);
+    }
+}
+
+/// `"5m"`・`"0p"`（赤ドラ）のような文字列から読み込む
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tile {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Tile, D::Error> {
+        let name = <alloc::borrow::Cow<str>>::deserialize(deserializer)?;
+        return name.parse().map_err(serde::de::Error::custom);
+    }
 }
 
 /// 自風／場風
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Wind {
     /// 東家（`Tile::Z1`）
     East = Tile::Z1 as isize,
@@ -243,6 +319,15 @@ impl Wind {
     pub fn is_tile(tile: &Tile) -> Option<Wind> {
         Wind::is_tile_type(tile.get())
     }
+    /// 席と親の席、人数から自風を返す（三人麻雀では北家はいない）
+    pub fn from_seat(seat: usize, dealer: usize, players: usize) -> Wind {
+        match (seat + players - dealer) % players {
+            0 => Wind::East,
+            1 => Wind::South,
+            2 => Wind::West,
+            _ => Wind::North,
+        }
+    }
 }
 
 /// 三元牌
@@ -272,7 +357,9 @@ impl Dragon {
 
 #[cfg(test)]
 mod tests {
-    //use super::*;
+    use super::*;
+    use alloc::vec::Vec;
+
     /*
     /// 萬子の属性テスト
     #[test]
@@ -431,4 +518,41 @@ mod tests {
         );
     }
     */
+
+    /// 文字列との変換
+    #[test]
+    fn display_from_str_test() {
+        for i in Tile::M1..=Tile::Z7 {
+            let t = Tile::new(i);
+            assert_eq!(t.to_string().parse::<Tile>(), Ok(t));
+        }
+        assert_eq!(Tile::new(Tile::P5).to_string(), "5p");
+        assert_eq!(Tile::new_red(Tile::P5).to_string(), "0p");
+        assert_eq!("0p".parse::<Tile>(), Ok(Tile::new_red(Tile::P5)));
+        assert_eq!("🀄".parse::<Tile>(), Ok(Tile::new(Tile::Z7)));
+        assert_eq!("8z".parse::<Tile>(), Err(ParseTileError("8z".to_string())));
+        assert_eq!(ParseTileError("x".to_string()).to_string(), "invalid tile: x");
+    }
+
+    /// 赤ドラを含む37種類の牌はすべて文字列にして読み直すと元に戻る
+    #[test]
+    fn display_round_trip_test() {
+        let tiles: Vec<Tile> = (Tile::M1..=Tile::Z7)
+            .map(Tile::new)
+            .chain([Tile::M5, Tile::P5, Tile::S5].map(Tile::new_red))
+            .collect();
+        assert_eq!(tiles.len(), 37);
+        for t in tiles {
+            assert_eq!(t.to_string().parse::<Tile>(), Ok(t));
+        }
+    }
+
+    /// 赤ドラは別の牌として区別する
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_test() {
+        let set: std::collections::HashSet<Tile> =
+            [Tile::new(Tile::M5), Tile::new(Tile::M5), Tile::new_red(Tile::M5)].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
 }
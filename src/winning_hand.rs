@@ -4,6 +4,12 @@ pub mod checker;
 /// 役の名前
 pub mod name;
 
+/// 符・翻・基本点の計算
+pub mod score;
+
+/// 和了の読み上げ
+pub mod announcement;
+
 /// 1翻役の判定
 mod check_1_han;
 
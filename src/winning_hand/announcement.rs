@@ -0,0 +1,247 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::board::payment::Payment;
+use crate::settings::Lang;
+use crate::winning_hand::name;
+use crate::winning_hand::score::Score;
+
+/// 基本点から満貫以上の名前を返す（満貫に満たなければ`None`）
+///
+/// 数え役満も`役満`になる。
+pub fn limit_name(base_points: i32, yakuman: u32, lang: Lang) -> Option<&'static str> {
+    let limit = match base_points {
+        b if yakuman > 0 || b >= 8000 => 4,
+        b if b >= 6000 => 3,
+        b if b >= 4000 => 2,
+        b if b >= 3000 => 1,
+        b if b >= 2000 => 0,
+        _ => return None,
+    };
+    let names = match lang {
+        Lang::Ja => ["満貫", "跳満", "倍満", "三倍満", "役満"],
+        Lang::En => ["Mangan", "Haneman", "Baiman", "Sanbaiman", "Yakuman"],
+    };
+    return Some(names[limit]);
+}
+
+/// 支払いの表記（親の自摸は`2600オール`・`2600 all`、子の自摸は子・親の順に`1300-2600`・`1300/2600`）
+pub fn payment_to_string(payment: &Payment, lang: Lang) -> String {
+    match (payment, lang) {
+        (Payment::Ron(points), _) => points.to_string(),
+        (Payment::DealerTsumo(points), Lang::Ja) => format!("{}オール", points),
+        (Payment::DealerTsumo(points), Lang::En) => format!("{} all", points),
+        (Payment::Tsumo { child, dealer }, Lang::Ja) => format!("{}-{}", child, dealer),
+        (Payment::Tsumo { child, dealer }, Lang::En) => format!("{}/{}", child, dealer),
+    }
+}
+
+/// 和了の読み上げ（役・ドラ・翻・符・満貫以上の名前・支払い）を1行にする
+///
+/// 役の名前は`name::get`による。`has_opened`なら喰い下がり役に「（鳴）」が付く。
+///
+/// # Examples
+///
+/// ```
+/// use riichi_mahjong_rs::board::payment::Payment;
+/// use riichi_mahjong_rs::settings::Lang;
+/// use riichi_mahjong_rs::winning_hand::announcement::announce;
+/// use riichi_mahjong_rs::winning_hand::name::Kind;
+/// use riichi_mahjong_rs::winning_hand::score::Score;
+///
+/// let score = Score {
+///     winning_hands: vec![
+///         (Kind::ReadyHand, "立直", 1),
+///         (Kind::SelfPick, "門前清自摸和", 1),
+///         (Kind::NoPointsHand, "平和", 1),
+///     ],
+///     dora: 1,
+///     han: 4,
+///     fu: 20,
+///     yakuman: 0,
+///     base_points: 1280,
+/// };
+/// let payment = Payment::Tsumo { child: 1300, dealer: 2600 };
+/// assert_eq!(
+///     announce(&score, &payment, false, Lang::Ja),
+///     "立直 門前清自摸和 平和 ドラ1 4翻20符 1300-2600"
+/// );
+/// ```
+pub fn announce(score: &Score, payment: &Payment, has_opened: bool, lang: Lang) -> String {
+    let mut names: Vec<String> = score
+        .winning_hands
+        .iter()
+        .map(|(kind, _, _)| name::get(*kind, has_opened, lang).to_string())
+        .collect();
+    if score.dora > 0 {
+        names.push(match lang {
+            Lang::Ja => format!("ドラ{}", score.dora),
+            Lang::En => format!("Dora {}", score.dora),
+        });
+    }
+    let limit = limit_name(score.base_points, score.yakuman, lang);
+    let value = if score.yakuman > 0 {
+        yakuman_name(score.yakuman, lang)
+    } else {
+        let mut value = match (lang, score.han >= 5) {
+            (Lang::Ja, true) => format!("{}翻", score.han),
+            (Lang::Ja, false) => format!("{}翻{}符", score.han, score.fu),
+            (Lang::En, true) => format!("{} han", score.han),
+            (Lang::En, false) => format!("{} han {} fu", score.han, score.fu),
+        };
+        if let Some(limit) = limit {
+            match lang {
+                Lang::Ja => value = format!("{} {}", value, limit),
+                Lang::En => value = format!("{}, {}", value, limit),
+            }
+        }
+        value
+    };
+    let payment = payment_to_string(payment, lang);
+    match lang {
+        Lang::Ja => format!("{} {} {}", names.join(" "), value, payment),
+        Lang::En => format!("{} — {} — {}", names.join(", "), value, payment),
+    }
+}
+
+/// 役満の倍数の名前
+fn yakuman_name(multiple: u32, lang: Lang) -> String {
+    match (multiple, lang) {
+        (1, Lang::Ja) => "役満".to_string(),
+        (1, Lang::En) => "Yakuman".to_string(),
+        (2, Lang::Ja) => "ダブル役満".to_string(),
+        (2, Lang::En) => "Double Yakuman".to_string(),
+        (3, Lang::Ja) => "トリプル役満".to_string(),
+        (3, Lang::En) => "Triple Yakuman".to_string(),
+        (n, Lang::Ja) => format!("{}倍役満", n),
+        (n, Lang::En) => format!("{}x Yakuman", n),
+    }
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use crate::board::payment::{base_points, yakuman_base_points};
+    use crate::winning_hand::name::Kind;
+
+    fn score(winning_hands: &[Kind], dora: u32, han: u32, fu: u32) -> Score {
+        Score {
+            winning_hands: winning_hands
+                .iter()
+                .map(|kind| (*kind, name::get(*kind, false, Lang::Ja), 1))
+                .collect(),
+            dora,
+            han,
+            fu,
+            yakuman: 0,
+            base_points: base_points(han, fu),
+        }
+    }
+
+    #[test]
+    /// 満貫に満たない和了
+    fn below_limit() {
+        let s = score(
+            &[Kind::ReadyHand, Kind::SelfPick, Kind::NoPointsHand],
+            2,
+            4,
+            20,
+        );
+        let tsumo = Payment::Tsumo {
+            child: 1300,
+            dealer: 2600,
+        };
+        assert_eq!(
+            announce(&s, &tsumo, false, Lang::Ja),
+            "立直 門前清自摸和 平和 ドラ2 4翻20符 1300-2600"
+        );
+        assert_eq!(
+            announce(&s, &tsumo, false, Lang::En),
+            "Ready Hand, Self Pick, No Points Hand, Dora 2 — 4 han 20 fu — 1300/2600"
+        );
+        assert_eq!(
+            announce(&s, &Payment::DealerTsumo(2600), false, Lang::Ja),
+            "立直 門前清自摸和 平和 ドラ2 4翻20符 2600オール"
+        );
+        assert_eq!(
+            announce(&s, &Payment::DealerTsumo(2600), false, Lang::En),
+            "Ready Hand, Self Pick, No Points Hand, Dora 2 — 4 han 20 fu — 2600 all"
+        );
+    }
+
+    #[test]
+    /// 満貫以上の名前
+    fn limits() {
+        let s = score(&[Kind::AllSimples], 3, 4, 40);
+        assert_eq!(
+            announce(&s, &Payment::Ron(8000), false, Lang::Ja),
+            "断么九 ドラ3 4翻40符 満貫 8000"
+        );
+        assert_eq!(
+            announce(&s, &Payment::Ron(12000), false, Lang::En),
+            "All Simples, Dora 3 — 4 han 40 fu, Mangan — 12000"
+        );
+        let s = score(&[Kind::ThreeColourStraight], 5, 6, 30);
+        assert_eq!(
+            announce(&s, &Payment::Ron(12000), true, Lang::Ja),
+            "三色同順（鳴） ドラ5 6翻 跳満 12000"
+        );
+        let names: Vec<Option<&str>> = [4, 6, 8, 11, 13]
+            .iter()
+            .map(|han| limit_name(base_points(*han, 30), 0, Lang::Ja))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                None,
+                Some("跳満"),
+                Some("倍満"),
+                Some("三倍満"),
+                Some("役満")
+            ]
+        );
+        assert_eq!(
+            limit_name(base_points(11, 30), 0, Lang::En),
+            Some("Sanbaiman")
+        );
+        assert_eq!(limit_name(base_points(3, 30), 0, Lang::Ja), None);
+    }
+
+    #[test]
+    /// 役満
+    fn yakuman() {
+        let s = Score {
+            winning_hands: vec![(Kind::ThirteenOrphans, "国士無双", 13)],
+            dora: 0,
+            han: 13,
+            fu: 0,
+            yakuman: 1,
+            base_points: yakuman_base_points(1),
+        };
+        assert_eq!(
+            announce(&s, &Payment::Ron(48000), false, Lang::Ja),
+            "国士無双 役満 48000"
+        );
+        let s = Score {
+            winning_hands: vec![
+                (Kind::BigThreeDragons, "大三元", 13),
+                (Kind::AllHonors, "字一色", 13),
+            ],
+            han: 26,
+            yakuman: 2,
+            base_points: yakuman_base_points(2),
+            ..s
+        };
+        let tsumo = Payment::Tsumo {
+            child: 16000,
+            dealer: 32000,
+        };
+        assert_eq!(
+            announce(&s, &tsumo, false, Lang::En),
+            "Big Three Dragons, All Honors — Double Yakuman — 16000/32000"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::block::BlockProperty;
 use crate::hand_info::hand_analyzer::*;
@@ -25,7 +25,8 @@ pub fn check_ready_hand(
     if status.has_claimed_open {
         return Ok((name, false, 0));
     }
-    if status.has_claimed_ready {
+    // ダブル立直の場合は立直は付かない
+    if status.has_claimed_ready && !status.has_claimed_double_ready {
         Ok((name, true, 1))
     } else {
         Ok((name, false, 0))
@@ -88,7 +89,11 @@ pub fn check_last_tile_from_the_wall(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.is_last_tile && status.is_self_picked {
+        Ok((name, true, 1))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 河底撈魚
 pub fn check_last_discard(
@@ -104,7 +109,11 @@ pub fn check_last_discard(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.is_last_tile && !status.is_self_picked {
+        Ok((name, true, 1))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 嶺上開花
 pub fn check_dead_wall_draw(
@@ -120,7 +129,11 @@ pub fn check_dead_wall_draw(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.is_dead_wall_draw && status.is_self_picked {
+        Ok((name, true, 1))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 搶槓
 pub fn check_robbing_a_quad(
@@ -136,7 +149,11 @@ pub fn check_robbing_a_quad(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.is_robbing_a_quad && !status.is_self_picked {
+        Ok((name, true, 1))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// ダブル立直
 pub fn check_double_ready(
@@ -152,7 +169,14 @@ pub fn check_double_ready(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.has_claimed_open {
+        return Ok((name, false, 0));
+    }
+    if status.has_claimed_double_ready {
+        Ok((name, true, 2))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 平和
 pub fn check_no_points_hand(
@@ -174,11 +198,11 @@ pub fn check_no_points_hand(
         return Ok((name, false, 0));
     }
     // 面子はすべて順子で構成されているか
-    if hand.same3.len() > 0 || hand.sequential3.len() != 4 || hand.same2.len() != 1 {
+    if !hand.same3.is_empty() || hand.sequential3.len() != 4 || hand.same2.len() != 1 {
         return Ok((name, false, 0));
     }
     // 雀頭が役牌でないこと
-    if let Some(head) = hand.same2.get(0) {
+    if let Some(head) = hand.same2.first() {
         if head.has_wind(status.player_wind)?
             || head.has_wind(status.prevailing_wind)?
             || head.has_dragon(Dragon::White)?
@@ -440,7 +464,7 @@ mod tests {
     /// 立直で和了った
     fn test_win_by_ready_hand() {
         let test_str = "123m45678p999s11z 9p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -454,7 +478,7 @@ mod tests {
     /// 立直に一発が付いた
     fn test_win_by_one_shot() {
         let test_str = "123m45678p999s11z 9p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -469,7 +493,7 @@ mod tests {
     /// 門前清自摸和で和了った
     fn test_win_by_self_pick() {
         let test_str = "123m45678p999s11z 9p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -483,7 +507,7 @@ mod tests {
     /// 鳴いている場合は門前清自摸和は付かない
     fn test_not_win_by_self_pick_with_claiming_open() {
         let test_str = "123m45678p999s11z 9p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -495,10 +519,65 @@ mod tests {
         );
     }
     #[test]
+    /// ダブル立直では立直は付かず、ダブル立直の2翻が付く
+    fn test_win_by_double_ready() {
+        let test_str = "123m45678p999s11z 9p";
+        let test = Hand::parse_lossy(test_str);
+        let test_analyzer = HandAnalyzer::new(&test).unwrap();
+        let mut status = Status::new();
+        let settings = Settings::new();
+        status.has_claimed_ready = true;
+        status.has_claimed_double_ready = true;
+        assert_eq!(
+            check_ready_hand(&test_analyzer, &status, &settings).unwrap(),
+            ("立直", false, 0)
+        );
+        assert_eq!(
+            check_double_ready(&test_analyzer, &status, &settings).unwrap(),
+            ("ダブル立直", true, 2)
+        );
+    }
+    #[test]
+    /// 最後の牌で和了ると、自摸なら海底撈月・ロンなら河底撈魚が付く
+    fn test_win_by_last_tile() {
+        let test_str = "123m45678p999s11z 9p";
+        let test = Hand::parse_lossy(test_str);
+        let test_analyzer = HandAnalyzer::new(&test).unwrap();
+        let mut status = Status::new();
+        let settings = Settings::new();
+        status.is_last_tile = true;
+        status.is_self_picked = true;
+        assert!(check_last_tile_from_the_wall(&test_analyzer, &status, &settings).unwrap().1);
+        assert!(!check_last_discard(&test_analyzer, &status, &settings).unwrap().1);
+        status.is_self_picked = false;
+        assert!(!check_last_tile_from_the_wall(&test_analyzer, &status, &settings).unwrap().1);
+        assert!(check_last_discard(&test_analyzer, &status, &settings).unwrap().1);
+    }
+    #[test]
+    /// 嶺上開花は自摸のみ、搶槓はロンのみで付く
+    fn test_win_by_dead_wall_draw_and_robbing_a_quad() {
+        let test_str = "123m45678p999s11z 9p";
+        let test = Hand::parse_lossy(test_str);
+        let test_analyzer = HandAnalyzer::new(&test).unwrap();
+        let mut status = Status::new();
+        let settings = Settings::new();
+        status.is_dead_wall_draw = true;
+        status.is_self_picked = true;
+        assert!(check_dead_wall_draw(&test_analyzer, &status, &settings).unwrap().1);
+        status.is_self_picked = false;
+        assert!(!check_dead_wall_draw(&test_analyzer, &status, &settings).unwrap().1);
+
+        let mut status = Status::new();
+        status.is_robbing_a_quad = true;
+        assert!(check_robbing_a_quad(&test_analyzer, &status, &settings).unwrap().1);
+        status.is_self_picked = true;
+        assert!(!check_robbing_a_quad(&test_analyzer, &status, &settings).unwrap().1);
+    }
+    #[test]
     /// 断么九で和了った（喰い断あり鳴きなし）
     fn test_win_by_all_simples_open_rule_close_hand() {
         let test_str = "222456m777p56s88s 7s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -514,7 +593,7 @@ mod tests {
     /// 么九牌ありでは断么九にならない（一）
     fn test_not_win_by_all_simples_with_1() {
         let test_str = "111456m777p56s88s 7s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -530,7 +609,7 @@ mod tests {
     /// 么九牌ありでは断么九にならない（九）
     fn test_not_win_by_all_simples_with_9() {
         let test_str = "222456m777p5699s 7s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -546,7 +625,7 @@ mod tests {
     /// 么九牌ありでは断么九にならない（字牌）
     fn test_not_win_by_all_simples_with_honor() {
         let test_str = "222456m56s88s111z 7s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -562,7 +641,7 @@ mod tests {
     /// 断么九で和了った（喰い断あり鳴きあり）
     fn test_win_by_all_simples_open_rule_open_hand() {
         let test_str = "234m567m234p345s3s 3s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -578,7 +657,7 @@ mod tests {
     /// 断么九で和了った（喰い断なし鳴きなし）
     fn test_win_by_all_simples_close_rule_close_hand() {
         let test_str = "678m23455p33345ss 5p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -594,7 +673,7 @@ mod tests {
     /// 断么九で和了った（喰い断なし鳴きあり）->役無し
     fn test_win_by_all_simples_close_rule_open_hand() {
         let test_str = "222m456m777p56s88s 7s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let mut rules = Settings::new();
@@ -610,7 +689,7 @@ mod tests {
     /// 一盃口で和了った
     fn test_win_by_one_set_of_identical_sequences() {
         let test_str = "112233m456p456s7z 7z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -624,7 +703,7 @@ mod tests {
     /// 一盃口で和了った（鳴きあり）→役なし
     fn test_no_win_by_one_set_of_identical_sequences_with_openned() {
         let test_str = "112233m456p456s7z 7z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -638,7 +717,7 @@ mod tests {
     /// 平和で和了った
     fn test_win_by_no_points_hand() {
         let test_str = "123567m234p6799s 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -651,7 +730,7 @@ mod tests {
     /// 鳴いていると平和にならない
     fn test_not_win_by_no_points_hand_with_open() {
         let test_str = "123567m6799s 234p 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -665,7 +744,7 @@ mod tests {
     /// 刻子が含まれると平和にならない
     fn test_not_win_by_no_points_hand_with_triplet() {
         let test_str = "123456m789p222s3s 3s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -675,10 +754,24 @@ mod tests {
         );
     }
     #[test]
+    /// 1・9を待つ両面待ちでも平和になる
+    fn test_win_by_no_points_hand_with_terminal_wait() {
+        let settings = Settings::new();
+        let status = Status::new();
+        for test_str in ["123567m234p2388p 1p", "123567m234p7888p 9p"] {
+            let test = Hand::parse_lossy(test_str);
+            let analyzer = HandAnalyzer::new(&test).unwrap();
+            assert_eq!(
+                check_no_points_hand(&analyzer, &test, &status, &settings).unwrap(),
+                ("平和", true, 1)
+            );
+        }
+    }
+    #[test]
     /// 両面待ちでないと平和にならない（辺張待ち）
     fn test_not_win_by_no_points_hand_with_edge_wait() {
         let test_str = "12567m234p56799s 3m";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -692,7 +785,7 @@ mod tests {
     /// 両面待ちでないと平和にならない（嵌張待ち）
     fn test_not_win_by_no_points_hand_with_closed_wait() {
         let test_str = "123567m234p5799s 6s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -705,7 +798,7 @@ mod tests {
     /// 雀頭が役牌だと平和にならない
     fn test_not_win_by_no_points_hand_with_honor_pair() {
         let test_str = "123567m234p67s11z 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -720,7 +813,7 @@ mod tests {
     /// 自風で和了った
     fn test_win_by_honor_tiles_players_wind() {
         let test_str = "222m456m777p5s 222z 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -737,7 +830,7 @@ mod tests {
     /// 場風で和了った
     fn test_win_by_honor_tiles_prevailing_wind() {
         let test_str = "222m456m777p5s 111z 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -754,7 +847,7 @@ mod tests {
     /// 三元牌（白）で和了った
     fn test_win_by_honor_tiles_white_dragon() {
         let test_str = "222m456m777p5s 555z 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -771,7 +864,7 @@ mod tests {
     /// 三元牌（發）で和了った
     fn test_win_by_honor_tiles_green_dragon() {
         let test_str = "222m456m777p5s 666z 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -788,7 +881,7 @@ mod tests {
     /// 三元牌（中）で和了った
     fn test_win_by_honor_tiles_red_dragon() {
         let test_str = "222m456m777p5s 777z 5s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
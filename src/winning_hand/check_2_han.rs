@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::block::BlockProperty;
 use crate::hand_info::hand_analyzer::*;
@@ -218,7 +218,7 @@ pub fn check_terminal_or_honor_in_each_set(
     }
 
     // 混老頭とは複合しないため、必ず順子が含まれる
-    if hand.sequential3.len() == 0 {
+    if hand.sequential3.is_empty() {
         return Ok((name, false, 0));
     }
 
@@ -350,7 +350,7 @@ mod tests {
     /// 七対子で和了った
     fn test_win_by_seven_pairs() {
         let test_str = "1122m3344p5566s1z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -363,7 +363,7 @@ mod tests {
     /// 混全帯么九で和了った
     fn test_terminal_or_honor_in_each_set() {
         let test_str = "123999m111p79s44z 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -377,7 +377,7 @@ mod tests {
     /// 混全帯么九で和了った（食い下がり1翻）
     fn test_terminal_or_honor_in_each_set_open() {
         let test_str = "123m111p79s44z 789m 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -391,7 +391,7 @@ mod tests {
     /// 対々和で和了った
     fn test_all_triplet_hand() {
         let test_str = "777m333p22z 555m 999s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -405,7 +405,7 @@ mod tests {
     /// 一気通貫で和了った
     fn test_straight() {
         let test_str = "123456789m78p22z 9p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -420,7 +420,7 @@ mod tests {
     /// 一気通貫で和了った（食い下がり1翻）
     fn test_straight_open() {
         let test_str = "123m1p123s 456s 789s 1p";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -435,7 +435,7 @@ mod tests {
     /// 三色同順で和了った（門前）
     fn test_three_colour_straight_closed() {
         let test_str = "123m123p123s789m9p 9p";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -450,7 +450,7 @@ mod tests {
     /// 三色同順で和了った（鳴き）
     fn test_three_colour_straight_open() {
         let test_str = "123m789m9p 123p 123s 9p";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -465,7 +465,7 @@ mod tests {
     /// 三暗刻で和了った
     fn test_three_closed_triplets() {
         let test_str = "111333m444s1777z 1z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -479,7 +479,7 @@ mod tests {
     /// 三色同刻で和了った
     fn test_three_colour_triplets() {
         let test_str = "111m111p111s444m5z 5z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -493,7 +493,7 @@ mod tests {
     /// 混老頭で和了った
     fn test_all_terminals_and_honors() {
         let test_str = "111m999p111s777z55z 5z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -507,7 +507,7 @@ mod tests {
     /// 小三元で和了った
     fn test_little_three_dragons() {
         let test_str = "111m111p555z666z7z 7z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::block::BlockProperty;
 use crate::hand_info::hand_analyzer::*;
@@ -27,8 +27,8 @@ pub fn check_two_sets_of_identical_sequences(
     if hand.sequential3.len() < 4 {
         return Ok((name, false, 0));
     }
-    use std::collections::HashMap;
-    let mut map: HashMap<[TileType;3], usize> = HashMap::new();
+    use alloc::collections::BTreeMap;
+    let mut map: BTreeMap<[TileType;3], usize> = BTreeMap::new();
     for seq in &hand.sequential3 {
         *map.entry(seq.get()).or_insert(0) += 1;
     }
@@ -54,7 +54,7 @@ pub fn check_terminal_in_each_set(
         return Ok((name, false, 0));
     }
     // 清老頭とは複合しないため、必ず順子が含まれる
-    if hand.sequential3.len() == 0 {
+    if hand.sequential3.is_empty() {
         return Ok((name, false, 0));
     }
 
@@ -147,7 +147,7 @@ mod tests {
     /// 純全帯么九で和了った
     fn test_terminal_in_each_set() {
         let test_str = "123999m11p11179s 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -161,7 +161,7 @@ mod tests {
     /// 純全帯么九で和了った（食い下がり2翻）
     fn test_terminal_in_each_set_open() {
         let test_str = "123m111p7999s 789m 8s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
@@ -176,43 +176,39 @@ mod tests {
     /// 混全帯么九は純全帯么九と複合しない
     fn test_terminal_or_honor_in_each_set_does_not_combined_with_terminal_in_each_set() {
         let test_str = "111789m111p99s11z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
         status.has_claimed_open = false;
-        assert_eq!(
-            check_terminal_or_honor_in_each_set(&test_analyzer, &status, &settings).unwrap().1,
-            true
+        assert!(
+            check_terminal_or_honor_in_each_set(&test_analyzer, &status, &settings).unwrap().1
         );
-        assert_eq!(
-            check_terminal_in_each_set(&test_analyzer, &status, &settings).unwrap().1,
-            false
+        assert!(
+            !check_terminal_in_each_set(&test_analyzer, &status, &settings).unwrap().1
         );
     }
     #[test]
     /// 純全帯么九は混全帯么九と複合しない
     fn test_terminal_in_each_set_does_not_combined_with_terminal_or_honor_in_each_set() {
         let test_str = "111789m111p1199s 9s";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
         status.has_claimed_open = false;
-        assert_eq!(
-            check_terminal_or_honor_in_each_set(&test_analyzer, &status, &settings).unwrap().1,
-            false
+        assert!(
+            !check_terminal_or_honor_in_each_set(&test_analyzer, &status, &settings).unwrap().1
         );
-        assert_eq!(
-            check_terminal_in_each_set(&test_analyzer, &status, &settings).unwrap().1,
-            true
+        assert!(
+            check_terminal_in_each_set(&test_analyzer, &status, &settings).unwrap().1
         );
     }
     #[test]
     /// 二盃口の判定
     fn test_two_sets_of_identical_sequences() {
         let test_str = "112233m778899p7z 7z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -226,7 +222,7 @@ mod tests {
     /// 混一色（門前）で和了った
     fn test_half_flush_closed() {
         let test_str = "123456789m1112z 2z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -240,7 +236,7 @@ mod tests {
     /// 混一色（鳴き）で和了った
     fn test_half_flush_open() {
         let test_str = "111m789m2z 123m 222z 2z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
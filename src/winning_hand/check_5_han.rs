@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::hand_analyzer::*;
 use crate::hand_info::status::*;
@@ -19,7 +19,8 @@ pub fn check_nagashi_mangan(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    // 流し満貫は手牌ではなく河で決まるので、流局時に`board::exhaustive_draw`で判定する
+    Ok((name, false, 0))
 }
 
 /// ユニットテスト
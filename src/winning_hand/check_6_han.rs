@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::block::BlockProperty;
 use crate::hand_info::hand_analyzer::*;
@@ -69,7 +69,7 @@ mod tests {
     /// 清一色（門前）で和了った
     fn test_flush_closed() {
         let test_str = "1112223334445m 5m";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -83,7 +83,7 @@ mod tests {
     /// 清一色（鳴き）で和了った
     fn test_flush_open() {
         let test_str = "1234568889m 111m 9m";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let mut status = Status::new();
         let settings = Settings::new();
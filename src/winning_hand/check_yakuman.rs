@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::Result;
 
 use crate::hand_info::block::BlockProperty;
 use crate::hand_info::hand_analyzer::*;
@@ -152,10 +152,11 @@ pub fn check_all_honors(
         status.has_claimed_open,
         settings.display_lang,
     );
-    if !has_won(hand) {
+    // 国士無双の形は面子に分解されないので除く
+    if !has_won(hand) || hand.form == Form::ThirteenOrphens {
         return Ok((name, false, 0));
     }
-    if hand.sequential3.len() > 0 {
+    if !hand.sequential3.is_empty() {
         return Ok((name, false, 0));
     }
     for same in &hand.same3 {
@@ -181,19 +182,19 @@ pub fn check_all_terminals(
         status.has_claimed_open,
         settings.display_lang,
     );
-    if !has_won(hand) {
+    if !has_won(hand) || hand.form == Form::ThirteenOrphens {
         return Ok((name, false, 0));
     }
-    if hand.sequential3.len() > 0 {
+    if !hand.sequential3.is_empty() {
         return Ok((name, false, 0));
     }
     for same in &hand.same3 {
-        if !(same.has_1_or_9()? && !same.has_honor()?) {
+        if !same.has_1_or_9()? || same.has_honor()? {
             return Ok((name, false, 0));
         }
     }
     for pair in &hand.same2 {
-        if !(pair.has_1_or_9()? && !pair.has_honor()?) {
+        if !pair.has_1_or_9()? || pair.has_honor()? {
             return Ok((name, false, 0));
         }
     }
@@ -210,7 +211,7 @@ pub fn check_all_green(
         status.has_claimed_open,
         settings.display_lang,
     );
-    if !has_won(hand) {
+    if !has_won(hand) || hand.form == Form::ThirteenOrphens {
         return Ok((name, false, 0));
     }
     const GREEN: [TileType; 6] = [Tile::S2, Tile::S3, Tile::S4, Tile::S6, Tile::S8, Tile::Z6];
@@ -342,7 +343,11 @@ pub fn check_four_kans(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    if status.kan_count == 4 {
+        Ok((name, true, 13))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 天和
 pub fn check_heavenly_hand(
@@ -358,7 +363,12 @@ pub fn check_heavenly_hand(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    // 親の第一自摸で和了
+    if status.is_first_turn && status.is_self_picked && status.player_wind == Wind::East {
+        Ok((name, true, 13))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 /// 地和
 pub fn check_hand_of_earth(
@@ -374,7 +384,16 @@ pub fn check_hand_of_earth(
     if !has_won(hand) {
         return Ok((name, false, 0));
     }
-    todo!()
+    // 子の第一自摸で和了
+    if status.is_first_turn
+        && status.is_self_picked
+        && !status.has_claimed_open
+        && status.player_wind != Wind::East
+    {
+        Ok((name, true, 13))
+    } else {
+        Ok((name, false, 0))
+    }
 }
 
 /// ユニットテスト
@@ -387,7 +406,7 @@ mod tests {
     /// 国士無双で和了った
     fn test_win_by_thirteen_orphens() {
         let test_str = "19m19p19s1234567z 1m";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -401,7 +420,7 @@ mod tests {
     /// 四暗刻単騎で和了った
     fn test_win_by_four_concealed_triplets_single() {
         let test_str = "111333m444s1777z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         status.is_self_picked = true; // 自摸和了
@@ -416,7 +435,7 @@ mod tests {
     /// 通常の四暗刻では、自摸和了のみ（ロンした場合は三暗刻＋対々和になる）
     fn test_not_win_by_four_concealed_triplets_single_if_not_self_pick() {
         let test_str = "111333m444s1777z 1z";
-        let test = Hand::from(test_str);
+        let test = Hand::parse_lossy(test_str);
         let test_analyzer = HandAnalyzer::new(&test).unwrap();
         let mut status = Status::new();
         status.is_self_picked = false;
@@ -430,7 +449,7 @@ mod tests {
     /// 大三元で和了った
     fn test_big_three_dragons() {
         let test_str = "1234m555666z 777z 1m";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -444,7 +463,7 @@ mod tests {
     /// 小四喜で和了った
     fn test_little_four_winds() {
         let test_str = "111m1112223334z 4z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -458,7 +477,7 @@ mod tests {
     /// 大四喜で和了った
     fn test_big_four_winds() {
         let test_str = "1m111222333444z 1m";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -472,7 +491,7 @@ mod tests {
     /// 字一色で和了った
     fn test_all_honors() {
         let test_str = "1112223335556z 6z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -486,7 +505,7 @@ mod tests {
     /// 清老頭で和了った
     fn test_all_terminals() {
         let test_str = "111999m111999p1s 1s";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -500,7 +519,7 @@ mod tests {
     /// 緑一色で和了った
     fn test_all_green() {
         let test_str = "22334466s66z 888s 6z";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -514,7 +533,7 @@ mod tests {
     /// 九蓮宝燈で和了った
     fn test_nine_gates() {
         let test_str = "1112345678999m 5m";
-        let hand = Hand::from(test_str);
+        let hand = Hand::parse_lossy(test_str);
         let analyzer = HandAnalyzer::new(&hand).unwrap();
         let status = Status::new();
         let settings = Settings::new();
@@ -523,4 +542,40 @@ mod tests {
             ("九蓮宝燈", true, 13)
         );
     }
+
+    #[test]
+    /// 槓子が4つあれば四槓子
+    fn test_four_kans() {
+        let test_str = "1z 1111m 2222p 3333s 4444s 1z";
+        let hand = Hand::parse_lossy(test_str);
+        let analyzer = HandAnalyzer::new(&hand).unwrap();
+        let mut status = Status::new();
+        let settings = Settings::new();
+        status.kan_count = 4;
+        assert_eq!(
+            check_four_kans(&analyzer, &status, &settings).unwrap(),
+            ("四槓子", true, 13)
+        );
+        status.kan_count = 3;
+        assert!(!check_four_kans(&analyzer, &status, &settings).unwrap().1);
+    }
+
+    #[test]
+    /// 第一巡の自摸で和了ると、親なら天和・子なら地和
+    fn test_heavenly_hand_and_hand_of_earth() {
+        let test_str = "123m45678p999s11z 9p";
+        let hand = Hand::parse_lossy(test_str);
+        let analyzer = HandAnalyzer::new(&hand).unwrap();
+        let mut status = Status::new();
+        let settings = Settings::new();
+        status.is_first_turn = true;
+        status.is_self_picked = true;
+        assert!(check_heavenly_hand(&analyzer, &status, &settings).unwrap().1);
+        assert!(!check_hand_of_earth(&analyzer, &status, &settings).unwrap().1);
+        status.player_wind = Wind::South;
+        assert!(!check_heavenly_hand(&analyzer, &status, &settings).unwrap().1);
+        assert!(check_hand_of_earth(&analyzer, &status, &settings).unwrap().1);
+        status.is_self_picked = false;
+        assert!(!check_hand_of_earth(&analyzer, &status, &settings).unwrap().1);
+    }
 }
@@ -1,8 +1,8 @@
 /// 役を判定する
-use std::collections::HashMap;
-use anyhow::Result;
-use strum::{EnumCount, IntoEnumIterator};
+use alloc::collections::BTreeMap;
+use strum::IntoEnumIterator;
 
+use crate::error::Result;
 use crate::hand_info::hand_analyzer::HandAnalyzer;
 use crate::hand_info::status::Status;
 use crate::hand::Hand;
@@ -20,8 +20,8 @@ pub fn check(
     hand: &Hand,
     status: &Status,
     settings: &Settings,
-) -> Result<HashMap<Kind, (&'static str, bool, u32)>> {
-    let mut result = HashMap::with_capacity(Kind::COUNT);
+) -> Result<BTreeMap<Kind, (&'static str, bool, u32)>> {
+    let mut result = BTreeMap::new();
     for hand_kind in Kind::iter() {
         result.insert(hand_kind, ("Unknown", false, 0));
     }
@@ -3,7 +3,8 @@ use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
 use crate::settings::Lang;
 
 /// 和了時の手牌の形態
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Form {
     /// 七対子
     SevenPairs,
@@ -16,7 +17,8 @@ pub enum Form {
 /// 和了役を表す列挙型
 ///
 /// <https://en.wikipedia.org/wiki/Japanese_Mahjong_yaku>による英語名
-#[derive(Debug, PartialEq, Eq, Hash, EnumCountMacro, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumCountMacro, EnumIter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
     /// 立直
     ReadyHand,
@@ -122,7 +124,6 @@ pub enum Kind {
 /// assert_eq!(get(Kind::ThreeColourStraight, true, Lang::En), "Three Colour Straight (Open)");
 /// assert_eq!(get(Kind::ThreeColourStraight, false, Lang::En), "Three Colour Straight");
 /// ```
-
 pub fn get(hand_kind: Kind, has_openned: bool, lang: Lang) -> &'static str {
     match lang {
         Lang::En => get_en(hand_kind, has_openned),
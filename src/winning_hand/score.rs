@@ -0,0 +1,270 @@
+use alloc::vec::Vec;
+use strum::IntoEnumIterator;
+
+use crate::board::payment::{base_points, yakuman_base_points};
+use crate::error::Result;
+use crate::hand::Hand;
+use crate::hand_info::block::BlockProperty;
+use crate::hand_info::hand_analyzer::*;
+use crate::hand_info::opened::{OpenFrom, OpenType};
+use crate::hand_info::status::Status;
+use crate::settings::Settings;
+use crate::tile::*;
+use crate::winning_hand::checker;
+use crate::winning_hand::name::*;
+
+/// 和了の点数計算の結果
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Score {
+    /// 成立した役（種類・名前・翻数）
+    pub winning_hands: Vec<(Kind, &'static str, u32)>,
+    /// ドラ（裏ドラ・抜きドラを含む）の枚数
+    pub dora: u32,
+    /// 翻数（ドラを含む）
+    pub han: u32,
+    /// 符
+    pub fu: u32,
+    /// 役満の倍数（役満でなければ`0`）
+    pub yakuman: u32,
+    /// 基本点
+    pub base_points: i32,
+}
+
+/// 和了った手牌の役・ドラ・符を求める
+///
+/// `hand`は和了牌を`drawn`に入れた手牌、`dora`はドラ（表示牌ではない）の種類。
+/// `extra_dora`は抜きドラの枚数。和了の形でないか、役がない場合は`None`を返す。
+pub fn calculate(
+    hand: &Hand,
+    status: &Status,
+    settings: &Settings,
+    dora: &[TileType],
+    extra_dora: u32,
+) -> Result<Option<Score>> {
+    let analyzer = HandAnalyzer::new(hand)?;
+    if !has_won(&analyzer) {
+        return Ok(None);
+    }
+    let result = checker::check(&analyzer, hand, status, settings)?;
+    let mut winning_hands: Vec<(Kind, &'static str, u32)> = Vec::new();
+    for kind in Kind::iter() {
+        if let Some((name, true, han)) = result.get(&kind) {
+            winning_hands.push((kind, *name, *han));
+        }
+    }
+    if winning_hands.is_empty() {
+        return Ok(None);
+    }
+
+    // 役満があれば役満のみを数える
+    if winning_hands.iter().any(|(_, _, han)| *han >= 13) {
+        winning_hands.retain(|(_, _, han)| *han >= 13);
+        let han: u32 = winning_hands.iter().map(|(_, _, han)| han).sum();
+        let yakuman = han / 13;
+        return Ok(Some(Score {
+            winning_hands,
+            dora: 0,
+            han,
+            fu: 0,
+            yakuman,
+            base_points: yakuman_base_points(yakuman),
+        }));
+    }
+
+    let is_no_points_hand = winning_hands
+        .iter()
+        .any(|(kind, _, _)| *kind == Kind::NoPointsHand);
+    let fu = calculate_fu(&analyzer, hand, status, is_no_points_hand)?;
+    let dora = count_dora(hand, dora) + extra_dora;
+    let han = winning_hands.iter().map(|(_, _, han)| han).sum::<u32>() + dora;
+    Ok(Some(Score {
+        winning_hands,
+        dora,
+        han,
+        fu,
+        yakuman: 0,
+        base_points: base_points(han, fu),
+    }))
+}
+
+/// 手牌に含まれるドラの枚数を数える（槓子は4枚と数える）
+pub fn count_dora(hand: &Hand, dora: &[TileType]) -> u32 {
+    let mut counts = hand.summarize_tiles();
+    for open in hand.opened() {
+        if open.category == OpenType::Kan {
+            counts[open.tiles[0].get() as usize] += 1;
+        }
+    }
+    dora.iter().map(|d| counts[*d as usize]).sum()
+}
+
+/// 符を計算する
+///
+/// 面子の分け方は`HandAnalyzer`の結果に従う。
+pub fn calculate_fu(
+    analyzer: &HandAnalyzer,
+    hand: &Hand,
+    status: &Status,
+    is_no_points_hand: bool,
+) -> Result<u32> {
+    match analyzer.form {
+        Form::SevenPairs => return Ok(25),
+        Form::ThirteenOrphens => return Ok(30),
+        Form::Normal => {}
+    }
+    if is_no_points_hand {
+        return Ok(if status.is_self_picked { 20 } else { 30 });
+    }
+
+    let win_tile = hand.drawn().map(|t| t.get());
+    let mut fu: u32 = 20;
+    // 門前ロン
+    if !status.has_claimed_open && !status.is_self_picked {
+        fu += 10;
+    }
+    // 自摸
+    if status.is_self_picked {
+        fu += 2;
+    }
+
+    // 刻子・槓子
+    for same in &analyzer.same3 {
+        let tile = same.get()[0];
+        let open = hand
+            .opened()
+            .iter()
+            .find(|o| o.category != OpenType::Chi && o.tiles[0].get() == tile);
+        let is_closed = match open {
+            Some(o) => o.from == OpenFrom::Myself,
+            // ロンで完成した刻子は明刻扱い（ただし順子の一部とみなせるなら暗刻のまま）
+            None => {
+                status.is_self_picked
+                    || win_tile != Some(tile)
+                    || analyzer.sequential3.iter().any(|s| s.get().contains(&tile))
+            }
+        };
+        let mut value = 2;
+        if same.has_1_or_9()? || same.has_honor()? {
+            value *= 2;
+        }
+        if is_closed {
+            value *= 2;
+        }
+        if matches!(open, Some(o) if o.category == OpenType::Kan) {
+            value *= 4;
+        }
+        fu += value;
+    }
+
+    // 雀頭
+    if let Some(head) = analyzer.same2.first() {
+        let tile = head.get()[0];
+        if Dragon::is_tile_type(tile).is_some() {
+            fu += 2;
+        }
+        if head.has_wind(status.player_wind)? {
+            fu += 2;
+        }
+        if head.has_wind(status.prevailing_wind)? {
+            fu += 2;
+        }
+    }
+
+    // 待ち（単騎・嵌張・辺張）
+    if let Some(t) = win_tile {
+        let is_single_wait = analyzer.same2.iter().any(|h| h.get()[0] == t);
+        let is_edge_or_closed_wait = analyzer.sequential3.iter().any(|s| {
+            let [a, b, c] = s.get();
+            let offset = (a - Tile::M1) % 9;
+            b == t || (c == t && offset == 0) || (a == t && offset == 6)
+        });
+        if is_single_wait || is_edge_or_closed_wait {
+            fu += 2;
+        }
+    }
+
+    // 喰い平和の形は30符
+    if status.has_claimed_open && fu == 20 {
+        fu = 30;
+    }
+    Ok(fu.div_ceil(10) * 10)
+}
+
+/// ユニットテスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    /// 立直・門前清自摸和・平和・ドラ1
+    fn ready_self_pick_no_points() {
+        let hand = Hand::parse_lossy("123567m234p6799s 5s");
+        let mut status = Status::new();
+        status.has_claimed_ready = true;
+        status.is_self_picked = true;
+        status.player_wind = Wind::South;
+        let settings = Settings::new();
+        let score = calculate(&hand, &status, &settings, &[Tile::M1], 0)
+            .unwrap()
+            .unwrap();
+        let kinds: Vec<Kind> = score.winning_hands.iter().map(|(k, _, _)| *k).collect();
+        assert_eq!(kinds, vec![Kind::ReadyHand, Kind::SelfPick, Kind::NoPointsHand]);
+        assert_eq!((score.han, score.fu, score.dora), (4, 20, 1));
+        assert_eq!(score.base_points, 1280);
+    }
+
+    #[test]
+    /// 役がなければ和了にならない
+    fn no_winning_hand() {
+        let hand = Hand::parse_lossy("123m456p789s1z 999m 1z");
+        let mut status = Status::new();
+        status.has_claimed_open = true;
+        status.player_wind = Wind::South;
+        status.prevailing_wind = Wind::South;
+        let settings = Settings::new();
+        assert_eq!(calculate(&hand, &status, &settings, &[], 0).unwrap(), None);
+    }
+
+    #[test]
+    /// 符の計算
+    fn fu() {
+        let settings = Settings::new();
+        let mut status = Status::new();
+        status.player_wind = Wind::South;
+        // 中の暗刻（8符）・門前ロン（10符）・嵌張（2符）
+        let hand = Hand::parse_lossy("13m456p789s11p777z 2m");
+        let score = calculate(&hand, &status, &settings, &[], 0).unwrap().unwrap();
+        assert_eq!((score.han, score.fu), (1, 40));
+
+        // 中の明刻（4符）・自摸（2符）・単騎（2符）・喰いの形
+        let hand = Hand::parse_lossy("123m456p789s1p 777z 1p");
+        status.has_claimed_open = true;
+        status.is_self_picked = true;
+        let score = calculate(&hand, &status, &settings, &[], 0).unwrap().unwrap();
+        assert_eq!((score.han, score.fu), (1, 30));
+
+        // 七対子は25符
+        let hand = Hand::parse_lossy("1122m3344p5566s7z 7z");
+        let score = calculate(&hand, &Status::new(), &settings, &[], 0).unwrap().unwrap();
+        assert_eq!(score.fu, 25);
+    }
+
+    #[test]
+    /// 役満は役満の基本点になる
+    fn yakuman() {
+        let hand = Hand::parse_lossy("19m19p19s1234567z 1m");
+        let settings = Settings::new();
+        let score = calculate(&hand, &Status::new(), &settings, &[Tile::M1], 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!((score.yakuman, score.base_points), (1, 8000));
+    }
+
+    #[test]
+    /// 槓子のドラは4枚と数える
+    fn count_dora_of_kan() {
+        let hand = Hand::parse_lossy("123m456p789s1z 2222z 1z");
+        assert_eq!(count_dora(&hand, &[Tile::Z2, Tile::Z1]), 6);
+    }
+}